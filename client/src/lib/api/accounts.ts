@@ -0,0 +1,152 @@
+import { error, success } from '.';
+import { Account, ApiToken } from '../types/accounts';
+import { constants } from '../constants';
+
+/**
+ * Functions to make calls against the accounts, sessions and API tokens API.
+ */
+export class AccountsApi {
+	constructor() {}
+
+	/**
+	 * Make API request to sign in and start a new session.
+	 * @param username username of the account
+	 * @param password password of the account
+	 * @returns request response
+	 */
+	public login = async (username: string, password: string) => {
+		return fetch(`/api/${constants.API_VERSION}/login`, {
+			method: 'POST',
+			body: JSON.stringify({ username, password })
+		}).then((response) => {
+			if (response.status == 200) {
+				return response.json().then((json) => {
+					return success(Object.assign(new Account(), json));
+				});
+			} else {
+				return response.json().then((json) => {
+					return error(`Could not sign in: ${json.message}`);
+				});
+			}
+		});
+	};
+
+	/**
+	 * Make API request to end the current session.
+	 * @returns request response
+	 */
+	public logout = async () => {
+		return fetch(`/api/${constants.API_VERSION}/logout`, {
+			method: 'POST'
+		}).then((response) => {
+			if (response.status == 200) {
+				return success(null);
+			} else {
+				return error(`Could not sign out: ${response}`);
+			}
+		});
+	};
+
+	/**
+	 * Make API request to check whether the first account still needs to be created.
+	 * @returns request response
+	 */
+	public getSetup = async () => {
+		return fetch(`/api/${constants.API_VERSION}/accounts/setup`, {
+			method: 'GET'
+		}).then((response) => {
+			if (response.status == 200) {
+				return response.json().then((json) => {
+					return success(json.setup as boolean);
+				});
+			} else {
+				return error(`Could not get account setup: ${response}`);
+			}
+		});
+	};
+
+	/**
+	 * Make API request to create a new account.
+	 * @param username username of the new account
+	 * @param password password of the new account
+	 * @param setupSecret [optional] setup secret, required to create the first account
+	 * @returns request response
+	 */
+	public addAccount = async (
+		username: string,
+		password: string,
+		setupSecret: string | null = null
+	) => {
+		return fetch(`/api/${constants.API_VERSION}/accounts`, {
+			method: 'POST',
+			body: JSON.stringify({ username, password, setup_secret: setupSecret })
+		}).then((response) => {
+			if (response.status == 200) {
+				return response.json().then((json) => {
+					return success(Object.assign(new Account(), json));
+				});
+			} else {
+				return response.json().then((json) => {
+					return error(`Could not create account ${username}: ${json.message}`);
+				});
+			}
+		});
+	};
+
+	/**
+	 * Make API request to get the API tokens of the signed in user.
+	 * @returns request response
+	 */
+	public getTokens = async () => {
+		return fetch(`/api/${constants.API_VERSION}/tokens`, {
+			method: 'GET'
+		}).then((response) => {
+			if (response.status == 200) {
+				return response.json().then((json) => {
+					return success(json as ApiToken[]);
+				});
+			} else {
+				return error(`Could not get API tokens: ${response}`);
+			}
+		});
+	};
+
+	/**
+	 * Make API request to create a new API token.
+	 * @param name name describing what the token is used for
+	 * @returns request response, containing the token
+	 */
+	public addToken = async (name: string) => {
+		return fetch(`/api/${constants.API_VERSION}/tokens`, {
+			method: 'POST',
+			body: JSON.stringify({ name })
+		}).then((response) => {
+			if (response.status == 200) {
+				return response.json().then((json) => {
+					return success(json as ApiToken);
+				});
+			} else {
+				return response.json().then((json) => {
+					return error(`Could not create API token ${name}: ${json.message}`);
+				});
+			}
+		});
+	};
+
+	/**
+	 * Make API request to revoke an API token.
+	 * @param id ID of the token to revoke
+	 * @returns request response
+	 */
+	public deleteToken = async (id: number | null) => {
+		return fetch(`/api/${constants.API_VERSION}/tokens/${id}`, {
+			method: 'DELETE'
+		}).then((response) => {
+			if (response.status == 200) {
+				return success(null);
+			} else {
+				return error(`Could not delete API token: ${response}`);
+			}
+		});
+	};
+}
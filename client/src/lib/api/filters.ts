@@ -1,5 +1,5 @@
 import { error, success } from '.';
-import { Filter } from '../types/filters';
+import { Filter, FilterProfile } from '../types/filters';
 import { constants } from '../constants';
 
 /**
@@ -27,22 +27,174 @@ export class FiltersApi {
 	};
 
 	/**
-	 * Make API request to update all filters
-	 * @param filters updated filters data
+	 * Make API request to create a new filter.
+	 * @param filter filter data
 	 * @returns request response
 	 */
-	public updateFilters = async (filters: Filter[]) => {
+	public addFilter = async (filter: Filter) => {
 		return fetch(`/api/${constants.API_VERSION}/filters`, {
+			method: 'POST',
+			body: JSON.stringify(filter)
+		}).then((response) => {
+			if (response.status == 200) {
+				return response.json().then((json) => {
+					return success(json as Filter);
+				});
+			} else {
+				return response.json().then((json) => {
+					return error(`Could not create filter ${filter.name}: ${json.message}`);
+				});
+			}
+		});
+	};
+
+	/**
+	 * Make API request to update an existing filter.
+	 * @param filter updated filter data
+	 * @returns request response
+	 */
+	public updateFilter = async (filter: Filter) => {
+		return fetch(`/api/${constants.API_VERSION}/filters/${filter.id}`, {
 			method: 'PUT',
-			body: JSON.stringify(filters)
+			body: JSON.stringify(filter)
+		}).then((response) => {
+			if (response.status == 200) {
+				return response.json().then((json) => {
+					return success(json as Filter);
+				});
+			} else {
+				return response.json().then((json) => {
+					return error(`Could not update filter ${filter.name}: ${json.message}`);
+				});
+			}
+		});
+	};
+
+	/**
+	 * Make API request to delete a filter.
+	 * @param id ID of the filter to delete
+	 * @returns request response
+	 */
+	public deleteFilter = async (id: number | null) => {
+		return fetch(`/api/${constants.API_VERSION}/filters/${id}`, {
+			method: 'DELETE'
+		}).then((response) => {
+			if (response.status == 200) {
+				return success(null);
+			} else {
+				return error('Could not delete filter');
+			}
+		});
+	};
+
+	/**
+	 * Make API request to get all filter profiles.
+	 * @returns request response
+	 */
+	public getFilterProfiles = async () => {
+		return fetch(`/api/${constants.API_VERSION}/filter_profiles`, {
+			method: 'GET'
+		}).then((response) => {
+			if (response.status == 200) {
+				return response.json().then((json) => {
+					return success(json as FilterProfile[]);
+				});
+			} else {
+				return error(`Could not get filter profiles: ${response}`);
+			}
+		});
+	};
+
+	/**
+	 * Make API request to create a new filter profile.
+	 * @param profile filter profile data
+	 * @returns request response
+	 */
+	public addFilterProfile = async (profile: FilterProfile) => {
+		return fetch(`/api/${constants.API_VERSION}/filter_profiles`, {
+			method: 'POST',
+			body: JSON.stringify(profile)
+		}).then((response) => {
+			if (response.status == 200) {
+				return response.json().then((json) => {
+					return success(json as FilterProfile);
+				});
+			} else {
+				return response.json().then((json) => {
+					return error(`Could not create filter profile ${profile.name}: ${json.message}`);
+				});
+			}
+		});
+	};
+
+	/**
+	 * Make API request to delete a filter profile, including its filters.
+	 * @param id ID of the filter profile to delete
+	 * @returns request response
+	 */
+	public deleteFilterProfile = async (id: number | null) => {
+		return fetch(`/api/${constants.API_VERSION}/filter_profiles/${id}`, {
+			method: 'DELETE'
+		}).then((response) => {
+			if (response.status == 200) {
+				return success(null);
+			} else {
+				return error('Could not delete filter profile');
+			}
+		});
+	};
+
+	/**
+	 * Make API request to get the filter profiles attached to a source.
+	 * @param sourceId ID of the source
+	 * @returns request response
+	 */
+	public getSourceFilterProfiles = async (sourceId: number | null) => {
+		return fetch(`/api/${constants.API_VERSION}/sources/${sourceId}/filter_profiles`, {
+			method: 'GET'
 		}).then((response) => {
 			if (response.status == 200) {
 				return response.json().then((json) => {
-					let storedFilters: Filter[] = json as Filter[];
-					return success(storedFilters);
+					return success(json as FilterProfile[]);
 				});
 			} else {
-				return error(`Could not update filters: ${response}`);
+				return error(`Could not get filter profiles of source: ${response}`);
+			}
+		});
+	};
+
+	/**
+	 * Make API request to attach a filter profile to a source.
+	 * @param sourceId ID of the source
+	 * @param profileId ID of the filter profile
+	 * @returns request response
+	 */
+	public attachFilterProfile = async (sourceId: number | null, profileId: number | null) => {
+		return fetch(`/api/${constants.API_VERSION}/sources/${sourceId}/filter_profiles/${profileId}`, {
+			method: 'PUT'
+		}).then((response) => {
+			if (response.status == 200) {
+				return success(null);
+			} else {
+				return error('Could not attach filter profile');
+			}
+		});
+	};
+
+	/**
+	 * Make API request to detach a filter profile from a source.
+	 * @param sourceId ID of the source
+	 * @param profileId ID of the filter profile
+	 * @returns request response
+	 */
+	public detachFilterProfile = async (sourceId: number | null, profileId: number | null) => {
+		return fetch(`/api/${constants.API_VERSION}/sources/${sourceId}/filter_profiles/${profileId}`, {
+			method: 'DELETE'
+		}).then((response) => {
+			if (response.status == 200) {
+				return success(null);
+			} else {
+				return error('Could not detach filter profile');
 			}
 		});
 	};
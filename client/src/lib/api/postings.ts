@@ -1,6 +1,43 @@
 import { error, success } from '.';
 import { constants } from '../constants';
-import { Posting } from '../types/postings';
+import { Posting, PostingDuplicate, type SearchResult, type SimilarPosting, type SourceStats } from '../types/postings';
+import { RefreshJob } from '../types/jobs';
+
+// number of milliseconds between checks whether a refresh job has completed
+const JOB_POLL_INTERVAL = 2000;
+
+// number of postings requested per page when loading all postings
+const PAGE_SIZE = 500;
+
+/**
+ * Get all postings of a paginated list by following the cursor of the next page.
+ * @param url URL of the list of postings, optionally with query parameters
+ * @returns response of the last request, and the postings of all pages
+ */
+const fetchAllPages = async (url: string): Promise<[Response, Posting[]]> => {
+	let postings: Posting[] = [];
+	let cursor: string | null = null;
+
+	while (true) {
+		const params = new URLSearchParams({ limit: String(PAGE_SIZE) });
+		if (cursor != null) {
+			params.append('cursor', cursor);
+		}
+
+		const response = await fetch(`${url}${url.includes('?') ? '&' : '?'}${params}`, {
+			method: 'GET'
+		});
+		if (response.status != 200) {
+			return [response, postings];
+		}
+
+		postings = postings.concat((await response.json()) as Posting[]);
+		cursor = response.headers.get('X-Next-Cursor');
+		if (cursor == null) {
+			return [response, postings];
+		}
+	}
+};
 
 /**
  * Functions to make calls against the postings API.
@@ -27,42 +64,195 @@ export class PostingsApi {
 		});
 	};
 
+	/**
+	 * Make API call to get the duplicates of a specific posting found on other sources.
+	 * @param id ID of posting to get duplicates for
+	 * @returns request response
+	 */
+	public getPostingDuplicates = async (id: number | null) => {
+		return fetch(`/api/${constants.API_VERSION}/postings/${id}/duplicates`, {
+			method: 'GET'
+		}).then((response) => {
+			if (response.status == 200) {
+				return response.json().then((json) => {
+					return success(json as PostingDuplicate[]);
+				});
+			} else {
+				return error('Could not get posting duplicates');
+			}
+		});
+	};
+
 	/**
 	 * Make API call to refresh postings.
+	 * Refresh jobs are queued and polled until they complete.
 	 * @param source_id [optional] only refresh postings for this soruce
 	 * @returns request response
 	 */
 	public refreshPostings = async (source_id: number | null = null) => {
-		let url = `/api/${constants.API_VERSION}/postings/refresh`;
 		if (source_id != null) {
-			url += `?source_id=${source_id}`;
+			return this.refreshSource(source_id);
 		}
 
-		return fetch(url, {
-			method: 'GET'
+		const res = await this.refreshAllSources();
+		if (!res.isSuccessful) {
+			return res;
+		}
+
+		const jobsRes = await this.waitForJobs(res.data as RefreshJob[]);
+		if (!jobsRes.isSuccessful) {
+			return jobsRes;
+		}
+
+		const failed = (jobsRes.data as RefreshJob[]).filter((j) => j.status == 'failed');
+
+		return this.getUnreadPostings().then((postingsRes) => {
+			if (!postingsRes.isSuccessful) {
+				return postingsRes;
+			}
+
+			const postings = (postingsRes.data as Posting[]).map((p) => Object.assign(new Posting(), p));
+			if (failed.length > 0) {
+				// postings of the other sources were still refreshed
+				return error(`Could not refresh ${failed.length} source(s): ${failed[0].error}`, postings);
+			}
+
+			return success(postings);
+		});
+	};
+
+	/**
+	 * Make API call to queue a refresh of all sources.
+	 * @returns request response with the refresh job of every source
+	 */
+	public refreshAllSources = async () => {
+		return fetch(`/api/${constants.API_VERSION}/postings/refresh_all`, {
+			method: 'POST'
 		}).then((response) => {
-			if (response.status == 200) {
+			if (response.status == 202) {
 				return response.json().then((json) => {
-					return success(json.map((p) => Object.assign(new Posting(), p)));
+					return success((json as RefreshJob[]).map((j) => Object.assign(new RefreshJob(), j)));
 				});
 			} else {
-				return error('Could not refresh postings');
+				return error(`Could not refresh postings: ${response.statusText}`);
 			}
 		});
 	};
 
 	/**
-	 * Make API call to get all unseen postings.
+	 * Poll refresh jobs until all of them have completed.
+	 * @param jobs refresh jobs to wait for
+	 * @returns request response with the completed jobs
+	 */
+	public waitForJobs = async (jobs: RefreshJob[]) => {
+		let pending = jobs.filter((j) => j.status == 'queued' || j.status == 'running');
+		const done = jobs.filter((j) => !pending.includes(j));
+
+		while (pending.length > 0) {
+			await new Promise((resolve) => setTimeout(resolve, JOB_POLL_INTERVAL));
+
+			const stillPending: RefreshJob[] = [];
+			for (const job of pending) {
+				const jobRes = await this.getJob(job.id as number);
+				if (!jobRes.isSuccessful) {
+					return jobRes;
+				}
+
+				const updated = jobRes.data as RefreshJob;
+				if (updated.status == 'queued' || updated.status == 'running') {
+					stillPending.push(updated);
+				} else {
+					done.push(updated);
+				}
+			}
+			pending = stillPending;
+		}
+
+		return success(done);
+	};
+
+	/**
+	 * Queue a refresh of a specific source and wait for it to complete.
+	 * @param source_id ID of source to refresh
+	 * @returns request response with the postings that were added by the refresh
+	 */
+	public refreshSource = async (source_id: number) => {
+		const res = await this.enqueueRefresh(source_id);
+		if (!res.isSuccessful) {
+			return res;
+		}
+
+		const jobsRes = await this.waitForJobs([res.data as RefreshJob]);
+		if (!jobsRes.isSuccessful) {
+			return jobsRes;
+		}
+
+		const job = (jobsRes.data as RefreshJob[])[0];
+		if (job.status == 'failed') {
+			return error(`Could not refresh postings: ${job.error}`);
+		}
+
+		// return the unread postings of the source that were created by this refresh
+		const postingsRes = await this.getUnreadPostings();
+		if (!postingsRes.isSuccessful) {
+			return postingsRes;
+		}
+
+		const startedAt = new Date(job.started_at as Date);
+		return success(
+			(postingsRes.data as Posting[])
+				.filter((p) => p.source_id == source_id && new Date(p.created_at) >= startedAt)
+				.map((p) => Object.assign(new Posting(), p))
+		);
+	};
+
+	/**
+	 * Make API call to queue a refresh of a specific source.
+	 * @param source_id ID of source to refresh
 	 * @returns request response
 	 */
-	public getUnreadPostings = async () => {
-		return fetch(`/api/${constants.API_VERSION}/postings/unread`, {
+	public enqueueRefresh = async (source_id: number) => {
+		return fetch(`/api/${constants.API_VERSION}/sources/${source_id}/refresh`, {
+			method: 'POST'
+		}).then((response) => {
+			if (response.status == 202) {
+				return response.json().then((json) => {
+					return success(Object.assign(new RefreshJob(), json));
+				});
+			} else {
+				return error(`Could not refresh source: ${response.statusText}`);
+			}
+		});
+	};
+
+	/**
+	 * Make API call to get the status of a refresh job.
+	 * @param id ID of refresh job
+	 * @returns request response
+	 */
+	public getJob = async (id: number) => {
+		return fetch(`/api/${constants.API_VERSION}/jobs/${id}`, {
 			method: 'GET'
 		}).then((response) => {
 			if (response.status == 200) {
 				return response.json().then((json) => {
-					return success(json as Posting[]);
+					return success(Object.assign(new RefreshJob(), json));
 				});
+			} else {
+				return error(`Could not get refresh job: ${response.statusText}`);
+			}
+		});
+	};
+
+	/**
+	 * Make API call to get all unseen postings.
+	 * @returns request response
+	 */
+	public getUnreadPostings = async () => {
+		const url = `/api/${constants.API_VERSION}/postings/unread`;
+		return fetchAllPages(url).then(([response, postings]) => {
+			if (response.status == 200) {
+				return success(postings);
 			} else {
 				return error(`Could not get postings: ${response}`);
 			}
@@ -74,13 +264,10 @@ export class PostingsApi {
 	 * @returns request response
 	 */
 	public getBookmarkedPostings = async () => {
-		return fetch(`/api/${constants.API_VERSION}/postings/bookmarked`, {
-			method: 'GET'
-		}).then((response) => {
+		const url = `/api/${constants.API_VERSION}/postings/bookmarked`;
+		return fetchAllPages(url).then(([response, postings]) => {
 			if (response.status == 200) {
-				return response.json().then((json) => {
-					return success(json as Posting[]);
-				});
+				return success(postings);
 			} else {
 				return error('Could not get bookmarked postings');
 			}
@@ -135,15 +322,102 @@ export class PostingsApi {
 			sourceFilter = `source_id=${sourceId}&`;
 		}
 
-		return fetch(`/api/${constants.API_VERSION}/postings?${sourceFilter}read=true`, {
+		const url = `/api/${constants.API_VERSION}/postings?${sourceFilter}read=true`;
+		return fetchAllPages(url).then(([response, postings]) => {
+			if (response.status == 200) {
+				return success(postings);
+			} else {
+				return error(`Could not get read postings ${response.statusText}`);
+			}
+		});
+	};
+
+	/**
+	 * Make API call to search postings by their title, description and content.
+	 * @param query search query
+	 * @param filters [optional] filters to narrow down the results, e.g. `source_id` or `bookmarked`
+	 * @returns request response
+	 */
+	public searchPostings = async (query: string, filters: Record<string, string | number | boolean> = {}) => {
+		let params = new URLSearchParams({ q: query });
+		for (const [key, value] of Object.entries(filters)) {
+			params.append(key, String(value));
+		}
+
+		return fetch(`/api/${constants.API_VERSION}/postings/search?${params}`, {
 			method: 'GET'
 		}).then((response) => {
 			if (response.status == 200) {
 				return response.json().then((json) => {
-					return success(json as Posting[]);
+					return success(json as SearchResult[]);
 				});
 			} else {
-				return error(`Could not get read postings ${response.statusText}`);
+				return error(`Could not search postings ${response.statusText}`);
+			}
+		});
+	};
+
+	/**
+	 * Make API call to search postings by meaning, based on their embeddings.
+	 * @param query search query
+	 * @param limit [optional] maximum number of results
+	 * @returns request response
+	 */
+	public semanticSearchPostings = async (query: string, limit: number | null = null) => {
+		let params = new URLSearchParams({ q: query });
+		if (limit != null) {
+			params.append('limit', String(limit));
+		}
+
+		return fetch(`/api/${constants.API_VERSION}/postings/semantic?${params}`, {
+			method: 'GET'
+		}).then((response) => {
+			if (response.status == 200) {
+				return response.json().then((json) => {
+					return success(json as SimilarPosting[]);
+				});
+			} else {
+				return error(`Could not search postings ${response.statusText}`);
+			}
+		});
+	};
+
+	/**
+	 * Make API call to get the postings most similar to a specific posting.
+	 * @param id ID of posting to get similar postings for
+	 * @param limit [optional] maximum number of similar postings
+	 * @returns request response
+	 */
+	public getSimilarPostings = async (id: number | null, limit: number | null = null) => {
+		let params = limit != null ? `?limit=${limit}` : '';
+
+		return fetch(`/api/${constants.API_VERSION}/postings/${id}/similar${params}`, {
+			method: 'GET'
+		}).then((response) => {
+			if (response.status == 200) {
+				return response.json().then((json) => {
+					return success(json as SimilarPosting[]);
+				});
+			} else {
+				return error('Could not get similar postings');
+			}
+		});
+	};
+
+	/**
+	 * Make API call to get posting counts and the outcome of the last refresh of every source.
+	 * @returns request response
+	 */
+	public getPostingStats = async () => {
+		return fetch(`/api/${constants.API_VERSION}/postings/stats`, {
+			method: 'GET'
+		}).then((response) => {
+			if (response.status == 200) {
+				return response.json().then((json) => {
+					return success(json as SourceStats[]);
+				});
+			} else {
+				return error('Could not get posting stats');
 			}
 		});
 	};
@@ -0,0 +1,105 @@
+import { AccountsApi } from '../api/accounts';
+import type { RequestResponse } from '.';
+
+/**
+ * Handler to sign in and out, and to manage API tokens.
+ */
+export class AccountsHandler {
+	// accounts API instance
+	api: AccountsApi;
+
+	/**
+	 * Create a new accounts handler instance.
+	 */
+	constructor() {
+		this.api = new AccountsApi();
+	}
+
+	/**
+	 * Sign in with username and password.
+	 * @param username username of the account
+	 * @param password password of the account
+	 * @returns request response
+	 */
+	public login(username: string, password: string): Promise<RequestResponse> {
+		return this.api.login(username, password);
+	}
+
+	/**
+	 * Sign out of the current session.
+	 * @returns request response
+	 */
+	public logout(): Promise<RequestResponse> {
+		return this.api.logout();
+	}
+
+	/**
+	 * Check whether the first account still needs to be created.
+	 * @returns request response
+	 */
+	public isSetup(): Promise<RequestResponse> {
+		return this.api.getSetup();
+	}
+
+	/**
+	 * Create a new account and sign in with it.
+	 * @param username username of the new account
+	 * @param password password of the new account
+	 * @param setupSecret [optional] setup secret, required to create the first account
+	 * @returns request response
+	 */
+	public register(
+		username: string,
+		password: string,
+		setupSecret: string | null = null
+	): Promise<RequestResponse> {
+		return this.api.addAccount(username, password, setupSecret).then((res) => {
+			if (!res.isSuccessful) {
+				return res;
+			}
+
+			return this.login(username, password);
+		});
+	}
+
+	/**
+	 * Get the API tokens of the signed in user.
+	 * @returns request response
+	 */
+	public getTokens(): Promise<RequestResponse> {
+		return this.api.getTokens();
+	}
+
+	/**
+	 * Create a new API token.
+	 * @param name name describing what the token is used for
+	 * @returns request response
+	 */
+	public addToken(name: string): Promise<RequestResponse> {
+		return this.api.addToken(name);
+	}
+
+	/**
+	 * Revoke an API token.
+	 * @param id ID of the token
+	 * @returns request response
+	 */
+	public deleteToken(id: number | null): Promise<RequestResponse> {
+		return this.api.deleteToken(id);
+	}
+}
+
+export class Account {
+	id: number | null = null;
+	username: string | null = null;
+	created_at: string | null = null;
+}
+
+export class ApiToken {
+	id: number | null = null;
+	name: string | null = null;
+	created_at: string | null = null;
+	last_used_at: string | null = null;
+	// only returned when the token is created
+	token: string | null = null;
+}
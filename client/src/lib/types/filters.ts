@@ -9,6 +9,8 @@ import type { RequestResponse } from '.';
 export class FiltersHandler {
 	// filters data
 	filters: Filter[] = [];
+	// filter profiles data
+	profiles: FilterProfile[] = [];
 	// filters API instance
 	api: FiltersApi;
 
@@ -55,20 +57,138 @@ export class FiltersHandler {
 	}
 
 	/**
-	 * Update the filters data.
-	 * @param filters updated filters data
+	 * Fetch filter profiles data from the server.
 	 * @returns request response
 	 */
-	public updateFilters(filters: Filter[]): Promise<RequestResponse> {
-		return this.api.updateFilters(filters).then((res) => {
+	public refreshProfiles(): Promise<RequestResponse> {
+		return this.api.getFilterProfiles().then((res) => {
 			if (res.isSuccessful) {
-				this.filters = res.data as Filter[];
-				this.store();
+				this.profiles = res.data as FilterProfile[];
+			}
+
+			return res;
+		});
+	}
+
+	/**
+	 * Create a new filter profile.
+	 * @param name name of the profile
+	 * @returns request response
+	 */
+	public addProfile(name: string): Promise<RequestResponse> {
+		let profile = new FilterProfile();
+		profile.name = name;
+
+		return this.api.addFilterProfile(profile).then((res) => {
+			if (res.isSuccessful) {
+				this.profiles = [...this.profiles, res.data as FilterProfile];
 			}
 
 			return res;
 		});
 	}
+
+	/**
+	 * Delete a filter profile, including its filters.
+	 * @param id ID of the profile
+	 * @returns request response
+	 */
+	public deleteProfile(id: number | null): Promise<RequestResponse> {
+		return this.api.deleteFilterProfile(id).then((res) => {
+			if (res.isSuccessful) {
+				this.profiles = this.profiles.filter((p) => p.id != id);
+				return this.refresh();
+			}
+
+			return res;
+		});
+	}
+
+	/**
+	 * Get the IDs of the filter profiles attached to a source.
+	 * @param sourceId ID of the source
+	 * @returns request response
+	 */
+	public sourceProfiles(sourceId: number | null): Promise<RequestResponse> {
+		return this.api.getSourceFilterProfiles(sourceId).then((res) => {
+			if (res.isSuccessful) {
+				res.data = (res.data as FilterProfile[]).map((p) => p.id);
+			}
+
+			return res;
+		});
+	}
+
+	/**
+	 * Update the filter profiles attached to a source.
+	 * Profiles that are no longer selected are detached, newly selected profiles are attached.
+	 * @param sourceId ID of the source
+	 * @param profileIds IDs of the profiles that should be attached
+	 * @returns request response
+	 */
+	public async updateSourceProfiles(
+		sourceId: number | null,
+		profileIds: (number | null)[]
+	): Promise<RequestResponse> {
+		let res = await this.sourceProfiles(sourceId);
+		if (!res.isSuccessful) {
+			return res;
+		}
+
+		const attached = res.data as (number | null)[];
+
+		for (const profileId of attached.filter((id) => !profileIds.includes(id))) {
+			res = await this.api.detachFilterProfile(sourceId, profileId);
+			if (!res.isSuccessful) {
+				return res;
+			}
+		}
+
+		for (const profileId of profileIds.filter((id) => !attached.includes(id))) {
+			res = await this.api.attachFilterProfile(sourceId, profileId);
+			if (!res.isSuccessful) {
+				return res;
+			}
+		}
+
+		return res;
+	}
+
+	/**
+	 * Update the filters data.
+	 * Filters that are no longer part of the data are deleted, new filters are created and existing filters are updated.
+	 * @param filters updated filters data
+	 * @returns request response
+	 */
+	public async updateFilters(filters: Filter[]): Promise<RequestResponse> {
+		const removed = this.filters.filter((f) => !filters.find((u) => u.id == f.id));
+
+		for (const filter of removed) {
+			const res = await this.api.deleteFilter(filter.id);
+			if (!res.isSuccessful) {
+				return res;
+			}
+		}
+
+		for (const filter of filters) {
+			const res = filter.id == null ? await this.api.addFilter(filter) : await this.api.updateFilter(filter);
+			if (!res.isSuccessful) {
+				// keep the filters that have been stored so far
+				await this.refresh();
+				return res;
+			}
+		}
+
+		return this.refresh();
+	}
+
+	/**
+	 * Get the keywords of all filters, e.g. to highlight them in postings.
+	 * @returns keywords
+	 */
+	public keywords(): string[] {
+		return this.filters.flatMap((f) => ruleKeywords(f.rule));
+	}
 }
 
 /**
@@ -77,5 +197,59 @@ export class FiltersHandler {
 export class Filter {
 	id: number | null = null;
 	name: string = '';
-	value: string = '';
+	rule: Rule = { type: 'criteria', text: '' };
+	// profile the filter belongs to; filters without profile apply to all sources
+	profile_id: number | null = null;
+}
+
+/**
+ * Data container for a named set of filters that can be attached to sources.
+ */
+export class FilterProfile {
+	id: number | null = null;
+	name: string = '';
+}
+
+/**
+ * Rule postings need to match.
+ * Depending on the `type`, only some of the fields are set.
+ */
+export interface Rule {
+	// kind of rule: `all`, `any`, `keywords`, `title_regex`, `location`, `workplace`, `min_salary`, `seniority` or `criteria`
+	type: string;
+	// rules of `all` and `any` rule groups
+	rules?: Rule[];
+	// keywords postings need to mention
+	include?: string[];
+	// keywords postings must not mention
+	exclude?: string[];
+	// regular expression titles need to match
+	pattern?: string;
+	// allowed locations, workplaces or seniority levels
+	values?: string[];
+	// minimum yearly salary
+	amount?: number;
+	// currency of the minimum salary
+	currency?: string | null;
+	// free-text criteria checked by the LLM
+	text?: string;
+}
+
+/**
+ * Get the keywords of a rule that postings are expected to mention.
+ * @param rule rule
+ * @returns keywords
+ */
+export function ruleKeywords(rule: Rule): string[] {
+	switch (rule.type) {
+		case 'all':
+		case 'any':
+			return (rule.rules ?? []).flatMap(ruleKeywords);
+		case 'keywords':
+			return rule.include ?? [];
+		case 'criteria':
+			return (rule.text ?? '').split(',');
+		default:
+			return [];
+	}
 }
@@ -0,0 +1,17 @@
+/**
+ * Data container for a source refresh job.
+ */
+export class RefreshJob {
+	id: number | null = null;
+	source_id: number | null = null;
+	// one of 'queued', 'running', 'finished' or 'failed'
+	status: string = 'queued';
+	created_at: Date | null = null;
+	started_at: Date | null = null;
+	finished_at: Date | null = null;
+	error: string | null = null;
+	postings_found: number | null = null;
+	// when the job is run; delayed for jobs queued by the scheduler
+	run_at: Date | null = null;
+}
+
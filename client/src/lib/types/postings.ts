@@ -53,6 +53,53 @@ export class PostingsHandler {
 		return this.api.getPostingById(id);
 	}
 
+	/**
+	 * Get the postings of other sources that are duplicates of a specific posting.
+	 * @param id posting ID
+	 * @returns request response
+	 */
+	public duplicatesOfPosting(id: number | null): Promise<RequestResponse> {
+		return this.api.getPostingDuplicates(id);
+	}
+
+	/**
+	 * Search postings by their title, description and content.
+	 * @param query search query
+	 * @param filters [optional] filters to narrow down the results
+	 * @returns request response
+	 */
+	public search(query: string, filters: Record<string, string | number | boolean> = {}): Promise<RequestResponse> {
+		return this.api.searchPostings(query, filters);
+	}
+
+	/**
+	 * Search postings by meaning, based on their embeddings.
+	 * @param query search query
+	 * @param limit [optional] maximum number of results
+	 * @returns request response
+	 */
+	public semanticSearch(query: string, limit: number | null = null): Promise<RequestResponse> {
+		return this.api.semanticSearchPostings(query, limit);
+	}
+
+	/**
+	 * Get the postings most similar to a specific posting.
+	 * @param id posting ID
+	 * @param limit [optional] maximum number of similar postings
+	 * @returns request response
+	 */
+	public similarToPosting(id: number | null, limit: number | null = null): Promise<RequestResponse> {
+		return this.api.getSimilarPostings(id, limit);
+	}
+
+	/**
+	 * Get posting counts and the outcome of the last refresh of every source.
+	 * @returns request response
+	 */
+	public stats(): Promise<RequestResponse> {
+		return this.api.getPostingStats();
+	}
+
 	/**
 	 * Get all postings that were fetched today.
 	 * @returns postings
@@ -102,8 +149,11 @@ export class PostingsHandler {
 			}
 		} else {
 			return this.api.refreshPostings(source_id).then((res) => {
-				if (res.isSuccessful) {
-					this.postings = this.postings.concat(res.data as Posting[]);
+				// failed refreshes can still return postings of sources that were refreshed successfully
+				if (res.data != null) {
+					// skip postings that are already known, e.g. when joining a refresh that was already running
+					const ids = this.postings.map((p) => p.id);
+					this.postings = this.postings.concat((res.data as Posting[]).filter((p) => !ids.includes(p.id)));
 					this.store();
 				}
 
@@ -260,4 +310,82 @@ export class Posting {
 	content: string = '';
 	is_match: boolean | null = null;
 	match_similarity: number | null = null;
+	location: string | null = null;
+	department: string | null = null;
+	external_id: string | null = null;
+	published_at: Date | null = null;
+	canonical_url: string | null = null;
+	last_seen_at: Date | null = null;
+	closed_at: Date | null = null;
+	workplace: string | null = null;
+	employment_type: string | null = null;
+	seniority: string | null = null;
+	salary_min: number | null = null;
+	salary_max: number | null = null;
+	salary_currency: string | null = null;
+}
+
+/**
+ * Posting of another source that is a duplicate of a stored posting.
+ */
+export class PostingDuplicate {
+	id: number | null = null;
+	posting_id: number | null = null;
+	source_id: number | null = null;
+	title: string = '';
+	url: string | null = null;
+	canonical_url: string | null = null;
+	external_id: string | null = null;
+	similarity: number | null = null;
+	created_at: Date = new Date();
+}
+
+/**
+ * Posting matching a search query.
+ */
+export interface SearchResult {
+	// matching posting
+	posting: Posting;
+	// relevance of the posting for the query
+	rank: number;
+	// excerpt of the posting with matching terms wrapped in <mark> tags
+	snippet: string;
+}
+
+/**
+ * Posting similar to a search query or another posting.
+ */
+export interface SimilarPosting {
+	// similar posting
+	posting: Posting;
+	// cosine similarity of the posting embedding
+	similarity: number;
+}
+
+/**
+ * Posting counts and outcome of the last refresh of a source.
+ */
+export interface SourceStats {
+	// source ID
+	source_id: number;
+	// source name
+	name: string;
+	// number of postings
+	total: number;
+	// number of postings that have not been seen
+	unread: number;
+	// number of bookmarked postings
+	bookmarked: number;
+	// number of postings marked as a match
+	matched: number;
+	// creation time of the most recent posting
+	newest_posting_at: Date | null;
+	// time of the last refresh
+	refreshed_at: Date | null;
+	// whether the source couldn't be reached during the last refresh
+	unreachable: boolean | null;
+	// status of the last completed refresh job, either `finished` or `failed`
+	last_refresh_status: string | null;
+	// error of the last completed refresh job
+	last_refresh_error: string | null;
 }
@@ -83,4 +83,7 @@ export class Settings {
 	id: number | null = null;
 	api_key: string | null = null;
 	model: string | null = null;
+	provider: string | null = null;
+	base_url: string | null = null;
+	embedding_model: string | null = null;
 }
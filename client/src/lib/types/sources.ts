@@ -190,5 +190,11 @@ export class Source {
 	favicon: string | null = null;
 	unreachable: boolean = false;
 	deleted: boolean = false;
+	refresh_interval: number | null = null;
+	// 'browser' (default) or 'http'
+	fetch_mode: string | null = null;
+	// 'page' for scraped pages, 'feed' for RSS/Atom feeds, or the applicant tracking system, e.g. 'greenhouse'
+	// detected from the URL if not set
+	kind: string | null = null;
 	refreshing: boolean = false;
 }
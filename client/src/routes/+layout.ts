@@ -11,7 +11,12 @@ import { NotificationHandler } from '../lib/types/notifications';
 
 import type { PageLoad } from './$types';
 
-export const load: PageLoad = async () => {
+export const load: PageLoad = async ({ url }) => {
+	// data is only available once signed in
+	if (url.pathname.startsWith('/login')) {
+		return {};
+	}
+
 	let settingsHandler = new SettingsHandler();
 	let sourcesHandler = new SourcesHandler();
 	let postingsHandler = new PostingsHandler();
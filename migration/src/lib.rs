@@ -6,6 +6,23 @@ mod m20240121_000001_create_postings_table;
 mod m20240121_000001_create_filters_table;
 mod m20240121_000001_create_suggestions_table;
 mod m20240121_000001_create_embeddings_table;
+mod m20241018_000001_add_refresh_schedule_to_sources_table;
+mod m20241018_000002_add_llm_provider_to_settings_table;
+mod m20241018_000003_create_refresh_jobs_table;
+mod m20241018_000004_add_fetch_mode_to_sources_table;
+mod m20241018_000005_add_ats_fields;
+mod m20241018_000006_add_feed_fields_to_postings_table;
+mod m20241018_000007_add_posting_deduplication;
+mod m20241018_000008_add_posting_lifecycle;
+mod m20241018_000009_add_search_vector_to_postings_table;
+mod m20241018_000010_add_pgvector_to_embeddings_table;
+mod m20241018_000011_create_scoring_models_table;
+mod m20241018_000012_add_posting_attributes;
+mod m20241018_000013_add_rule_to_filters_table;
+mod m20241018_000014_create_filter_profiles_table;
+mod m20241018_000015_create_accounts_table;
+mod m20241018_000016_create_sessions_table;
+mod m20241018_000017_add_run_at_to_refresh_jobs_table;
 
 pub struct Migrator;
 
@@ -19,6 +36,23 @@ impl MigratorTrait for Migrator {
             Box::new(m20240121_000001_create_postings_table::Migration),
             Box::new(m20240121_000001_create_suggestions_table::Migration),
             Box::new(m20240121_000001_create_embeddings_table::Migration),
+            Box::new(m20241018_000001_add_refresh_schedule_to_sources_table::Migration),
+            Box::new(m20241018_000002_add_llm_provider_to_settings_table::Migration),
+            Box::new(m20241018_000003_create_refresh_jobs_table::Migration),
+            Box::new(m20241018_000004_add_fetch_mode_to_sources_table::Migration),
+            Box::new(m20241018_000005_add_ats_fields::Migration),
+            Box::new(m20241018_000006_add_feed_fields_to_postings_table::Migration),
+            Box::new(m20241018_000007_add_posting_deduplication::Migration),
+            Box::new(m20241018_000008_add_posting_lifecycle::Migration),
+            Box::new(m20241018_000009_add_search_vector_to_postings_table::Migration),
+            Box::new(m20241018_000010_add_pgvector_to_embeddings_table::Migration),
+            Box::new(m20241018_000011_create_scoring_models_table::Migration),
+            Box::new(m20241018_000012_add_posting_attributes::Migration),
+            Box::new(m20241018_000013_add_rule_to_filters_table::Migration),
+            Box::new(m20241018_000014_create_filter_profiles_table::Migration),
+            Box::new(m20241018_000015_create_accounts_table::Migration),
+            Box::new(m20241018_000016_create_sessions_table::Migration),
+            Box::new(m20241018_000017_add_run_at_to_refresh_jobs_table::Migration),
         ]
     }
 }
@@ -0,0 +1,46 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Source::Table)
+                    .add_column_if_not_exists(ColumnDef::new(Source::RefreshInterval).integer())
+                    .add_column_if_not_exists(ColumnDef::new(Source::RefreshedAt).timestamp_with_time_zone())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Source::Table)
+                    .drop_column(Source::RefreshInterval)
+                    .drop_column(Source::RefreshedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Refresh schedule of a source
+#[derive(DeriveIden)]
+enum Source {
+    /// Table
+    Table,
+
+    /// Minutes between automatic refreshes of the source
+    /// null = use the default scheduler interval
+    /// 0 = never refresh automatically
+    RefreshInterval,
+
+    /// Timestamp of the last refresh attempt
+    RefreshedAt,
+}
@@ -0,0 +1,51 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Settings::Table)
+                    .add_column_if_not_exists(ColumnDef::new(Settings::Provider).string())
+                    .add_column_if_not_exists(ColumnDef::new(Settings::BaseUrl).string())
+                    .add_column_if_not_exists(ColumnDef::new(Settings::EmbeddingModel).string())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Settings::Table)
+                    .drop_column(Settings::Provider)
+                    .drop_column(Settings::BaseUrl)
+                    .drop_column(Settings::EmbeddingModel)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// LLM provider settings
+#[derive(DeriveIden)]
+enum Settings {
+    /// Table
+    Table,
+
+    /// LLM provider backend
+    /// null = OpenAI
+    Provider,
+
+    /// Base URL of the LLM provider API
+    /// Used for OpenAI-compatible providers, such as Ollama or vLLM
+    BaseUrl,
+
+    /// Model to create embeddings with
+    EmbeddingModel,
+}
@@ -0,0 +1,78 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20240121_000001_create_sources_table::Source;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RefreshJob::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(RefreshJob::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(RefreshJob::SourceId).integer().not_null())
+                    .col(ColumnDef::new(RefreshJob::Status).string().not_null())
+                    .col(ColumnDef::new(RefreshJob::CreatedAt).timestamp_with_time_zone().default(Expr::current_timestamp()))
+                    .col(ColumnDef::new(RefreshJob::StartedAt).timestamp_with_time_zone())
+                    .col(ColumnDef::new(RefreshJob::FinishedAt).timestamp_with_time_zone())
+                    .col(ColumnDef::new(RefreshJob::Error).text())
+                    .col(ColumnDef::new(RefreshJob::PostingsFound).integer())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-refresh_job-source_id")
+                            .from(RefreshJob::Table, RefreshJob::SourceId)
+                            .to(Source::Table, Source::Id),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RefreshJob::Table).to_owned())
+            .await
+    }
+}
+
+/// Background job refreshing the postings of a source
+#[derive(DeriveIden)]
+pub enum RefreshJob {
+    /// Table
+    Table,
+
+    /// Unique identifier
+    Id,
+
+    /// ID of source that is refreshed
+    SourceId,
+
+    /// Job status
+    /// queued, running, finished or failed
+    Status,
+
+    /// Timestamp when the job was enqueued
+    CreatedAt,
+
+    /// Timestamp when the job started running
+    StartedAt,
+
+    /// Timestamp when the job finished or failed
+    FinishedAt,
+
+    /// Error message if the job failed
+    Error,
+
+    /// Number of new postings that were found
+    PostingsFound,
+}
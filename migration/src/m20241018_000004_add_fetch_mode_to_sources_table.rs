@@ -0,0 +1,36 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Source::Table)
+                    .add_column_if_not_exists(ColumnDef::new(Source::FetchMode).string())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(Table::alter().table(Source::Table).drop_column(Source::FetchMode).to_owned())
+            .await
+    }
+}
+
+/// Fetch mode of a source
+#[derive(DeriveIden)]
+enum Source {
+    /// Table
+    Table,
+
+    /// How source pages are loaded
+    /// null or "browser" = headless browser
+    /// "http" = plain HTTP request, falling back to the browser if needed
+    FetchMode,
+}
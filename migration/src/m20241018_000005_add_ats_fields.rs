@@ -0,0 +1,69 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Source::Table)
+                    .add_column_if_not_exists(ColumnDef::new(Source::Kind).string())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Posting::Table)
+                    .add_column_if_not_exists(ColumnDef::new(Posting::Location).string())
+                    .add_column_if_not_exists(ColumnDef::new(Posting::Department).string())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(Table::alter().table(Source::Table).drop_column(Source::Kind).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Posting::Table)
+                    .drop_column(Posting::Location)
+                    .drop_column(Posting::Department)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Kind of source
+#[derive(DeriveIden)]
+enum Source {
+    /// Table
+    Table,
+
+    /// How postings are retrieved from the source
+    /// null or "page" = scrape the source page
+    /// "greenhouse", "lever", "ashby", "workable" = applicant tracking system API
+    Kind,
+}
+
+/// Posting details provided by applicant tracking systems
+#[derive(DeriveIden)]
+enum Posting {
+    /// Table
+    Table,
+
+    /// Location of the job
+    Location,
+
+    /// Department or team the job is in
+    Department,
+}
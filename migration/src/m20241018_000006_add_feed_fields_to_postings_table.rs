@@ -0,0 +1,44 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Posting::Table)
+                    .add_column_if_not_exists(ColumnDef::new(Posting::ExternalId).string())
+                    .add_column_if_not_exists(ColumnDef::new(Posting::PublishedAt).timestamp_with_time_zone())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Posting::Table)
+                    .drop_column(Posting::ExternalId)
+                    .drop_column(Posting::PublishedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Posting details provided by feeds
+#[derive(DeriveIden)]
+enum Posting {
+    /// Table
+    Table,
+
+    /// Identifier of the posting on the source, e.g. the GUID of a feed item
+    ExternalId,
+
+    /// Timestamp the posting was published on the source
+    PublishedAt,
+}
@@ -0,0 +1,151 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20240121_000001_create_postings_table::Posting;
+use super::m20240121_000001_create_sources_table::Source;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Posting::Table)
+                    .add_column_if_not_exists(ColumnDef::new(PostingIdentity::CanonicalUrl).string())
+                    .to_owned(),
+            )
+            .await?;
+
+        // a posting can only be stored once per source
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-posting-source_id-canonical_url")
+                    .table(Posting::Table)
+                    .col(Posting::SourceId)
+                    .col(PostingIdentity::CanonicalUrl)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-posting-source_id-external_id")
+                    .table(Posting::Table)
+                    .col(Posting::SourceId)
+                    .col(PostingIdentity::ExternalId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(PostingDuplicate::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PostingDuplicate::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(PostingDuplicate::PostingId).integer().not_null())
+                    .col(ColumnDef::new(PostingDuplicate::SourceId).integer().not_null())
+                    .col(ColumnDef::new(PostingDuplicate::Title).string().not_null())
+                    .col(ColumnDef::new(PostingDuplicate::Url).string())
+                    .col(ColumnDef::new(PostingDuplicate::CanonicalUrl).string())
+                    .col(ColumnDef::new(PostingDuplicate::ExternalId).string())
+                    .col(ColumnDef::new(PostingDuplicate::Similarity).float())
+                    .col(ColumnDef::new(PostingDuplicate::CreatedAt).timestamp_with_time_zone().default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-posting_duplicate-posting_id")
+                            .from(PostingDuplicate::Table, PostingDuplicate::PostingId)
+                            .to(Posting::Table, Posting::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-posting_duplicate-source_id")
+                            .from(PostingDuplicate::Table, PostingDuplicate::SourceId)
+                            .to(Source::Table, Source::Id),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PostingDuplicate::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_index(Index::drop().name("idx-posting-source_id-external_id").table(Posting::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_index(Index::drop().name("idx-posting-source_id-canonical_url").table(Posting::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Posting::Table)
+                    .drop_column(PostingIdentity::CanonicalUrl)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Posting columns used to identify postings
+#[derive(DeriveIden)]
+enum PostingIdentity {
+    /// Normalized posting URL without tracking parameters and fragments
+    CanonicalUrl,
+
+    /// Identifier of the posting on the source, e.g. the GUID of a feed item
+    ExternalId,
+}
+
+/// Posting of a source that has already been stored for another source
+#[derive(DeriveIden)]
+pub enum PostingDuplicate {
+    /// Table
+    Table,
+
+    /// Unique identifier
+    Id,
+
+    /// ID of the posting this is a duplicate of
+    PostingId,
+
+    /// ID of the source the duplicate was found on
+    SourceId,
+
+    /// Title of the duplicate
+    Title,
+
+    /// Url to the duplicate
+    Url,
+
+    /// Normalized url to the duplicate
+    CanonicalUrl,
+
+    /// Identifier of the duplicate on the source
+    ExternalId,
+
+    /// Cosine similarity of the duplicate's embedding to the posting;
+    /// null if the duplicate was identified by its URL
+    Similarity,
+
+    /// Timestamp when the duplicate was found
+    CreatedAt,
+}
@@ -0,0 +1,103 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20240121_000001_create_postings_table::Posting;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Posting::Table)
+                    .add_column_if_not_exists(ColumnDef::new(PostingLifecycle::LastSeenAt).timestamp_with_time_zone())
+                    .add_column_if_not_exists(ColumnDef::new(PostingLifecycle::ClosedAt).timestamp_with_time_zone())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(PostingEvent::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PostingEvent::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(PostingEvent::PostingId).integer().not_null())
+                    .col(ColumnDef::new(PostingEvent::Kind).string().not_null())
+                    .col(ColumnDef::new(PostingEvent::CreatedAt).timestamp_with_time_zone().default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-posting_event-posting_id")
+                            .from(PostingEvent::Table, PostingEvent::PostingId)
+                            .to(Posting::Table, Posting::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // existing postings were last seen when they were extracted
+        let db = manager.get_connection();
+        db.execute_unprepared("UPDATE posting SET last_seen_at = created_at WHERE last_seen_at IS NULL")
+            .await?;
+        db.execute_unprepared("INSERT INTO posting_event (posting_id, kind, created_at) SELECT id, 'opened', created_at FROM posting")
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PostingEvent::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Posting::Table)
+                    .drop_column(PostingLifecycle::LastSeenAt)
+                    .drop_column(PostingLifecycle::ClosedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Posting columns tracking whether the posting is still listed on its source
+#[derive(DeriveIden)]
+enum PostingLifecycle {
+    /// Timestamp of the last refresh the posting was listed on its source
+    LastSeenAt,
+
+    /// Timestamp of the first refresh the posting was no longer listed on its source;
+    /// null if the posting is open
+    ClosedAt,
+}
+
+/// Change in the lifecycle of a posting
+#[derive(DeriveIden)]
+pub enum PostingEvent {
+    /// Table
+    Table,
+
+    /// Unique identifier
+    Id,
+
+    /// ID of the posting
+    PostingId,
+
+    /// Kind of event
+    /// opened, closed or reopened
+    Kind,
+
+    /// Timestamp when the event happened
+    CreatedAt,
+}
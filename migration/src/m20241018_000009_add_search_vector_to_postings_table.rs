@@ -0,0 +1,38 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // weighted full-text search document of the posting: title, then description, then content
+        // content is truncated to stay below the maximum tsvector size
+        db.execute_unprepared(
+            "ALTER TABLE posting ADD COLUMN IF NOT EXISTS search_vector tsvector GENERATED ALWAYS AS (
+                setweight(to_tsvector('english', coalesce(title, '')), 'A') ||
+                setweight(to_tsvector('english', coalesce(description, '')), 'B') ||
+                setweight(to_tsvector('english', left(coalesce(content, ''), 100000)), 'C')
+            ) STORED",
+        )
+        .await?;
+
+        db.execute_unprepared("CREATE INDEX IF NOT EXISTS \"idx-posting-search_vector\" ON posting USING GIN (search_vector)")
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared("DROP INDEX IF EXISTS \"idx-posting-search_vector\"")
+            .await?;
+        db.execute_unprepared("ALTER TABLE posting DROP COLUMN IF EXISTS search_vector")
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::Statement;
+
+/// Number of dimensions of the embeddings that are indexed, matching the default embedding model
+const INDEXED_DIMENSIONS: usize = 1536;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+impl Migration {
+    /// Returns whether the query returns any rows.
+    async fn exists(manager: &SchemaManager<'_>, sql: &str) -> Result<bool, DbErr> {
+        let db = manager.get_connection();
+        let row = db
+            .query_one(Statement::from_string(manager.get_database_backend(), sql))
+            .await?;
+
+        Ok(row.is_some())
+    }
+}
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // pgvector is optional; it is only set up if it is installed or can be installed by the current user
+        let installed = Self::exists(manager, "SELECT 1 FROM pg_extension WHERE extname = 'vector'").await?;
+        if !installed {
+            let available = Self::exists(manager, "SELECT 1 FROM pg_available_extensions WHERE name = 'vector'").await?;
+            let is_superuser = Self::exists(manager, "SELECT 1 FROM pg_roles WHERE rolname = current_user AND rolsuper").await?;
+
+            if !available || !is_superuser {
+                return Ok(());
+            }
+
+            db.execute_unprepared("CREATE EXTENSION IF NOT EXISTS vector").await?;
+        }
+
+        // copy of the embedding vector that is kept in sync by the database
+        db.execute_unprepared(
+            "ALTER TABLE embedding ADD COLUMN IF NOT EXISTS pgvector vector
+                GENERATED ALWAYS AS (CASE WHEN cardinality(vector) > 0 THEN vector::vector END) STORED",
+        )
+        .await?;
+
+        // HNSW indexes are supported since pgvector 0.5.0, older versions only support IVFFlat indexes
+        // indexes require a fixed number of dimensions, so only embeddings of the default model are indexed
+        let index_method = if Self::exists(manager, "SELECT 1 FROM pg_am WHERE amname = 'hnsw'").await? {
+            "hnsw"
+        } else {
+            "ivfflat"
+        };
+
+        db.execute_unprepared(&format!(
+            "CREATE INDEX IF NOT EXISTS \"idx-embedding-pgvector\" ON embedding
+                USING {index_method} ((pgvector::vector({INDEXED_DIMENSIONS})) vector_cosine_ops)
+                WHERE vector_dims(pgvector) = {INDEXED_DIMENSIONS}"
+        ))
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared("DROP INDEX IF EXISTS \"idx-embedding-pgvector\"")
+            .await?;
+        db.execute_unprepared("ALTER TABLE embedding DROP COLUMN IF EXISTS pgvector")
+            .await?;
+
+        Ok(())
+    }
+}
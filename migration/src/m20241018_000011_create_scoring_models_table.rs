@@ -0,0 +1,66 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ScoringModel::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ScoringModel::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ScoringModel::Weights).array(ColumnType::Float).not_null())
+                    .col(ColumnDef::new(ScoringModel::Bias).float().not_null())
+                    .col(ColumnDef::new(ScoringModel::Examples).integer().not_null())
+                    .col(ColumnDef::new(ScoringModel::HeldOut).integer().not_null())
+                    .col(ColumnDef::new(ScoringModel::Accuracy).float())
+                    .col(ColumnDef::new(ScoringModel::TrainedAt).timestamp_with_time_zone().default(Expr::current_timestamp()))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ScoringModel::Table).to_owned())
+            .await
+    }
+}
+
+/// Model scoring how well postings match, trained from liked and disliked postings
+#[derive(DeriveIden)]
+pub enum ScoringModel {
+    /// Table
+    Table,
+
+    /// Unique identifier
+    Id,
+
+    /// Logistic regression weight per embedding dimension
+    Weights,
+
+    /// Logistic regression bias
+    Bias,
+
+    /// Number of liked and disliked postings the model was trained on
+    Examples,
+
+    /// Number of liked and disliked postings held out to evaluate the model
+    HeldOut,
+
+    /// Share of held out postings that were classified correctly;
+    /// null if there weren't enough postings to hold out
+    Accuracy,
+
+    /// Timestamp when the model was trained
+    TrainedAt,
+}
@@ -0,0 +1,64 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Posting::Table)
+                    .add_column_if_not_exists(ColumnDef::new(Posting::Workplace).string())
+                    .add_column_if_not_exists(ColumnDef::new(Posting::EmploymentType).string())
+                    .add_column_if_not_exists(ColumnDef::new(Posting::Seniority).string())
+                    .add_column_if_not_exists(ColumnDef::new(Posting::SalaryMin).big_integer())
+                    .add_column_if_not_exists(ColumnDef::new(Posting::SalaryMax).big_integer())
+                    .add_column_if_not_exists(ColumnDef::new(Posting::SalaryCurrency).string())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Posting::Table)
+                    .drop_column(Posting::Workplace)
+                    .drop_column(Posting::EmploymentType)
+                    .drop_column(Posting::Seniority)
+                    .drop_column(Posting::SalaryMin)
+                    .drop_column(Posting::SalaryMax)
+                    .drop_column(Posting::SalaryCurrency)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Posting attributes extracted from the posting details by the LLM
+#[derive(DeriveIden)]
+enum Posting {
+    /// Table
+    Table,
+
+    /// Where the job is done: "remote", "hybrid" or "onsite"
+    Workplace,
+
+    /// Kind of employment: "full_time", "part_time", "contract", "internship" or "temporary"
+    EmploymentType,
+
+    /// Seniority level: "entry", "mid", "senior", "lead" or "executive"
+    Seniority,
+
+    /// Lower bound of the yearly salary range
+    SalaryMin,
+
+    /// Upper bound of the yearly salary range
+    SalaryMax,
+
+    /// ISO 4217 currency code of the salary range
+    SalaryCurrency,
+}
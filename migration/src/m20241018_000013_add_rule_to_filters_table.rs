@@ -0,0 +1,71 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Filter::Table)
+                    .add_column_if_not_exists(ColumnDef::new(Filter::Rule).json_binary())
+                    .to_owned(),
+            )
+            .await?;
+
+        // existing filters are free-text criteria checked by the LLM
+        let db = manager.get_connection();
+        db.execute_unprepared("UPDATE filter SET rule = jsonb_build_object('type', 'criteria', 'text', value) WHERE rule IS NULL")
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Filter::Table)
+                    .modify_column(ColumnDef::new(Filter::Rule).json_binary().not_null())
+                    .drop_column(Filter::Value)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Filter::Table)
+                    .add_column_if_not_exists(ColumnDef::new(Filter::Value).string())
+                    .to_owned(),
+            )
+            .await?;
+
+        let db = manager.get_connection();
+        db.execute_unprepared("UPDATE filter SET value = coalesce(rule->>'text', rule::text)")
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Filter::Table)
+                    .modify_column(ColumnDef::new(Filter::Value).string().not_null())
+                    .drop_column(Filter::Rule)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Filter columns
+#[derive(DeriveIden)]
+enum Filter {
+    /// Table
+    Table,
+
+    /// Free-text filter value, replaced by rules
+    Value,
+
+    /// Typed rule postings need to match, stored as JSON
+    Rule,
+}
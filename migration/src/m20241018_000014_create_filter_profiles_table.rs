@@ -0,0 +1,142 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20240121_000001_create_sources_table::Source;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(FilterProfile::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(FilterProfile::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(FilterProfile::Name).string().not_null().unique_key())
+                    .col(ColumnDef::new(FilterProfile::CreatedAt).timestamp_with_time_zone().default(Expr::current_timestamp()))
+                    .to_owned(),
+            )
+            .await?;
+
+        // filters without a profile apply to all sources
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Filter::Table)
+                    .add_column_if_not_exists(ColumnDef::new(Filter::ProfileId).integer())
+                    .add_foreign_key(
+                        TableForeignKey::new()
+                            .name("fk-filter-profile_id")
+                            .from_tbl(Filter::Table)
+                            .from_col(Filter::ProfileId)
+                            .to_tbl(FilterProfile::Table)
+                            .to_col(FilterProfile::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(SourceFilterProfile::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(SourceFilterProfile::SourceId).integer().not_null())
+                    .col(ColumnDef::new(SourceFilterProfile::ProfileId).integer().not_null())
+                    .primary_key(
+                        Index::create()
+                            .col(SourceFilterProfile::SourceId)
+                            .col(SourceFilterProfile::ProfileId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-source_filter_profile-source_id")
+                            .from(SourceFilterProfile::Table, SourceFilterProfile::SourceId)
+                            .to(Source::Table, Source::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-source_filter_profile-profile_id")
+                            .from(SourceFilterProfile::Table, SourceFilterProfile::ProfileId)
+                            .to(FilterProfile::Table, FilterProfile::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SourceFilterProfile::Table).to_owned())
+            .await?;
+
+        // filters of profiles would apply to all sources otherwise
+        let db = manager.get_connection();
+        db.execute_unprepared("DELETE FROM filter WHERE profile_id IS NOT NULL")
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Filter::Table)
+                    .drop_foreign_key(Alias::new("fk-filter-profile_id"))
+                    .drop_column(Filter::ProfileId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(FilterProfile::Table).to_owned())
+            .await
+    }
+}
+
+/// Named set of filters that can be attached to sources.
+#[derive(DeriveIden)]
+enum FilterProfile {
+    /// Table
+    Table,
+
+    /// Unique identifier
+    Id,
+
+    /// Unique name of the profile
+    Name,
+
+    /// Timestamp when profile was created
+    CreatedAt,
+}
+
+/// Filter columns
+#[derive(DeriveIden)]
+enum Filter {
+    /// Table
+    Table,
+
+    /// Profile the filter belongs to; filters without profile apply to all sources
+    ProfileId,
+}
+
+/// Filter profiles attached to sources.
+#[derive(DeriveIden)]
+enum SourceFilterProfile {
+    /// Table
+    Table,
+
+    /// Source the profile is attached to
+    SourceId,
+
+    /// Attached profile
+    ProfileId,
+}
@@ -0,0 +1,308 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20240121_000001_create_postings_table::Posting;
+use super::m20240121_000001_create_sources_table::Source;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Tables that are stored per account; existing rows are assigned to the account created for existing data
+const ACCOUNT_TABLES: [&str; 4] = ["settings", "filter", "filter_profile", "scoring_model"];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Account::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Account::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Account::Username).string().not_null().unique_key())
+                    .col(ColumnDef::new(Account::PasswordHash).string())
+                    .col(ColumnDef::new(Account::CreatedAt).timestamp_with_time_zone().default(Expr::current_timestamp()))
+                    .to_owned(),
+            )
+            .await?;
+
+        // existing data is assigned to an account without password, which is claimed by the first registered account
+        let db = manager.get_connection();
+        db.execute_unprepared(
+            "INSERT INTO account (username)
+            SELECT 'admin'
+            WHERE EXISTS (SELECT 1 FROM settings) OR EXISTS (SELECT 1 FROM filter) OR EXISTS (SELECT 1 FROM posting) OR EXISTS (SELECT 1 FROM source)",
+        )
+        .await?;
+
+        for table in ACCOUNT_TABLES {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new(table))
+                        .add_column_if_not_exists(ColumnDef::new(AccountRef::AccountId).integer())
+                        .add_foreign_key(
+                            TableForeignKey::new()
+                                .name(format!("fk-{table}-account_id"))
+                                .from_tbl(Alias::new(table))
+                                .from_col(AccountRef::AccountId)
+                                .to_tbl(Account::Table)
+                                .to_col(Account::Id)
+                                .on_delete(ForeignKeyAction::Cascade),
+                        )
+                        .to_owned(),
+                )
+                .await?;
+
+            db.execute_unprepared(&format!("UPDATE {table} SET account_id = (SELECT min(id) FROM account)"))
+                .await?;
+
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new(table))
+                        .modify_column(ColumnDef::new(AccountRef::AccountId).integer().not_null())
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        // every account has its own settings
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-settings-account_id")
+                    .table(Alias::new("settings"))
+                    .col(AccountRef::AccountId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // profile names only need to be unique per account
+        db.execute_unprepared("ALTER TABLE filter_profile DROP CONSTRAINT IF EXISTS filter_profile_name_key")
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-filter_profile-account_id-name")
+                    .table(Alias::new("filter_profile"))
+                    .col(AccountRef::AccountId)
+                    .col(Alias::new("name"))
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // sources are shared, but postings are extracted with the settings of the account that added the source
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Source::Table)
+                    .add_column_if_not_exists(ColumnDef::new(AccountRef::AccountId).integer())
+                    .add_foreign_key(
+                        TableForeignKey::new()
+                            .name("fk-source-account_id")
+                            .from_tbl(Source::Table)
+                            .from_col(AccountRef::AccountId)
+                            .to_tbl(Account::Table)
+                            .to_col(Account::Id)
+                            .on_delete(ForeignKeyAction::SetNull),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        db.execute_unprepared("UPDATE source SET account_id = (SELECT min(id) FROM account)")
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(PostingState::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(PostingState::AccountId).integer().not_null())
+                    .col(ColumnDef::new(PostingState::PostingId).integer().not_null())
+                    .col(ColumnDef::new(PostingState::Seen).boolean().not_null().default(false))
+                    .col(ColumnDef::new(PostingState::Bookmarked).boolean().not_null().default(false))
+                    .col(ColumnDef::new(PostingState::IsMatch).boolean())
+                    .col(ColumnDef::new(PostingState::MatchSimilarity).float())
+                    .primary_key(
+                        Index::create()
+                            .col(PostingState::AccountId)
+                            .col(PostingState::PostingId),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-posting_state-account_id")
+                            .from(PostingState::Table, PostingState::AccountId)
+                            .to(Account::Table, Account::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-posting_state-posting_id")
+                            .from(PostingState::Table, PostingState::PostingId)
+                            .to(Posting::Table, Posting::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-posting_state-posting_id")
+                    .table(PostingState::Table)
+                    .col(PostingState::PostingId)
+                    .to_owned(),
+            )
+            .await?;
+
+        db.execute_unprepared(
+            "INSERT INTO posting_state (account_id, posting_id, seen, bookmarked, is_match, match_similarity)
+            SELECT account.id, posting.id, coalesce(posting.seen, false), coalesce(posting.bookmarked, false), posting.is_match, posting.match_similarity
+            FROM posting
+            JOIN account ON account.id = (SELECT min(id) FROM account)",
+        )
+        .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Posting::Table)
+                    .drop_column(Posting::Seen)
+                    .drop_column(Posting::Bookmarked)
+                    .drop_column(Posting::IsMatch)
+                    .drop_column(Posting::MatchSimilarity)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Posting::Table)
+                    .add_column_if_not_exists(ColumnDef::new(Posting::Seen).boolean().default(false))
+                    .add_column_if_not_exists(ColumnDef::new(Posting::Bookmarked).boolean().default(false))
+                    .add_column_if_not_exists(ColumnDef::new(Posting::IsMatch).boolean())
+                    .add_column_if_not_exists(ColumnDef::new(Posting::MatchSimilarity).float())
+                    .to_owned(),
+            )
+            .await?;
+
+        // only the data of the first account is kept
+        let db = manager.get_connection();
+        db.execute_unprepared(
+            "UPDATE posting
+            SET seen = posting_state.seen, bookmarked = posting_state.bookmarked, is_match = posting_state.is_match, match_similarity = posting_state.match_similarity
+            FROM posting_state
+            WHERE posting_state.posting_id = posting.id AND posting_state.account_id = (SELECT min(id) FROM account)",
+        )
+        .await?;
+
+        manager
+            .drop_table(Table::drop().table(PostingState::Table).to_owned())
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Source::Table)
+                    .drop_foreign_key(Alias::new("fk-source-account_id"))
+                    .drop_column(AccountRef::AccountId)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(Index::drop().name("idx-filter_profile-account_id-name").table(Alias::new("filter_profile")).to_owned())
+            .await?;
+
+        manager
+            .drop_index(Index::drop().name("idx-settings-account_id").table(Alias::new("settings")).to_owned())
+            .await?;
+
+        for table in ACCOUNT_TABLES {
+            db.execute_unprepared(&format!("DELETE FROM {table} WHERE account_id <> (SELECT min(id) FROM account)"))
+                .await?;
+
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Alias::new(table))
+                        .drop_foreign_key(Alias::new(format!("fk-{table}-account_id")))
+                        .drop_column(AccountRef::AccountId)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        db.execute_unprepared("ALTER TABLE filter_profile ADD CONSTRAINT filter_profile_name_key UNIQUE (name)")
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Account::Table).to_owned())
+            .await
+    }
+}
+
+/// User account
+#[derive(DeriveIden)]
+pub enum Account {
+    /// Table
+    Table,
+
+    /// Unique identifier
+    Id,
+
+    /// Unique name the user signs in with
+    Username,
+
+    /// Argon2 hash of the password; accounts without password can't sign in
+    PasswordHash,
+
+    /// Timestamp when account was created
+    CreatedAt,
+}
+
+/// Column referencing the account that owns a row
+#[derive(DeriveIden)]
+enum AccountRef {
+    /// Owning account
+    AccountId,
+}
+
+/// Posting state of an account
+#[derive(DeriveIden)]
+enum PostingState {
+    /// Table
+    Table,
+
+    /// Account the state belongs to
+    AccountId,
+
+    /// Posting the state belongs to
+    PostingId,
+
+    /// Whether the posting has been read
+    Seen,
+
+    /// Whether the posting has been bookmarked
+    Bookmarked,
+
+    /// Whether the posting is a good match: true = good match, false = not a good match, null = neutral
+    IsMatch,
+
+    /// Match score based on the liked and disliked postings of the account
+    MatchSimilarity,
+}
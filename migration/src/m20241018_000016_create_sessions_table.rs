@@ -0,0 +1,123 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20241018_000015_create_accounts_table::Account;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Session::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Session::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Session::AccountId).integer().not_null())
+                    .col(ColumnDef::new(Session::TokenHash).string().not_null().unique_key())
+                    .col(ColumnDef::new(Session::CreatedAt).timestamp_with_time_zone().default(Expr::current_timestamp()))
+                    .col(ColumnDef::new(Session::ExpiresAt).timestamp_with_time_zone().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-session-account_id")
+                            .from(Session::Table, Session::AccountId)
+                            .to(Account::Table, Account::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(ApiToken::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ApiToken::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ApiToken::AccountId).integer().not_null())
+                    .col(ColumnDef::new(ApiToken::Name).string().not_null())
+                    .col(ColumnDef::new(ApiToken::TokenHash).string().not_null().unique_key())
+                    .col(ColumnDef::new(ApiToken::CreatedAt).timestamp_with_time_zone().default(Expr::current_timestamp()))
+                    .col(ColumnDef::new(ApiToken::LastUsedAt).timestamp_with_time_zone())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk-api_token-account_id")
+                            .from(ApiToken::Table, ApiToken::AccountId)
+                            .to(Account::Table, Account::Id)
+                            .on_delete(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ApiToken::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(Session::Table).to_owned())
+            .await
+    }
+}
+
+/// Signed in session of a user in the browser
+#[derive(DeriveIden)]
+enum Session {
+    /// Table
+    Table,
+
+    /// Unique identifier
+    Id,
+
+    /// Account that signed in
+    AccountId,
+
+    /// SHA-256 hash of the token stored in the session cookie
+    TokenHash,
+
+    /// Timestamp when the user signed in
+    CreatedAt,
+
+    /// Timestamp after which the session is no longer valid
+    ExpiresAt,
+}
+
+/// Token scripts authenticate with
+#[derive(DeriveIden)]
+enum ApiToken {
+    /// Table
+    Table,
+
+    /// Unique identifier
+    Id,
+
+    /// Account the token authenticates as
+    AccountId,
+
+    /// Name describing what the token is used for
+    Name,
+
+    /// SHA-256 hash of the token
+    TokenHash,
+
+    /// Timestamp when token was created
+    CreatedAt,
+
+    /// Timestamp when the token was last used
+    LastUsedAt,
+}
@@ -0,0 +1,64 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20241018_000003_create_refresh_jobs_table::RefreshJob;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(RefreshJob::Table)
+                    .add_column_if_not_exists(ColumnDef::new(RefreshJobSchedule::RunAt).timestamp_with_time_zone())
+                    .to_owned(),
+            )
+            .await?;
+
+        // existing jobs were due when they were enqueued
+        let db = manager.get_connection();
+        db.execute_unprepared("UPDATE refresh_job SET run_at = created_at WHERE run_at IS NULL")
+            .await?;
+
+        // only the newest active job of a source is kept, so the unique index can be created
+        db.execute_unprepared(
+            r#"UPDATE refresh_job SET status = 'failed', error = 'Superseded by a newer job', finished_at = now()
+            WHERE status IN ('queued', 'running')
+            AND id NOT IN (SELECT max(id) FROM refresh_job WHERE status IN ('queued', 'running') GROUP BY source_id)"#,
+        )
+        .await?;
+
+        // a source can only have one queued or running job at a time
+        db.execute_unprepared(
+            "CREATE UNIQUE INDEX IF NOT EXISTS \"idx-refresh_job-active-source_id\" ON refresh_job (source_id) WHERE status IN ('queued', 'running')",
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared("DROP INDEX IF EXISTS \"idx-refresh_job-active-source_id\"")
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(RefreshJob::Table)
+                    .drop_column(RefreshJobSchedule::RunAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+/// Refresh job column scheduling when the job runs
+#[derive(DeriveIden)]
+enum RefreshJobSchedule {
+    /// Timestamp after which the job is run
+    RunAt,
+}
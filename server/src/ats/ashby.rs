@@ -0,0 +1,69 @@
+// Ashby job posting API
+use crate::ats::new_posting;
+use crate::entities::posting;
+use anyhow::Result;
+
+use serde::Deserialize;
+
+/// Job board API response.
+#[derive(Debug, Deserialize)]
+struct JobBoard {
+	jobs: Vec<Job>,
+}
+
+/// Job posted on the job board.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Job {
+	title: String,
+	job_url: Option<String>,
+	location: Option<String>,
+	department: Option<String>,
+	team: Option<String>,
+	description_html: Option<String>,
+	/// Unlisted jobs are only accessible via their URL
+	is_listed: Option<bool>,
+}
+
+/// Fetch all job postings of the Ashby job board `board`.
+pub async fn fetch_postings(client: &reqwest::Client, board: &str) -> Result<Vec<posting::Model>> {
+	let url = format!("https://api.ashbyhq.com/posting-api/job-board/{board}");
+	let body = client.get(url).send().await?.error_for_status()?.text().await?;
+
+	parse_postings(&body)
+}
+
+/// Parse the job postings of an API response.
+fn parse_postings(body: &str) -> Result<Vec<posting::Model>> {
+	let job_board: JobBoard = serde_json::from_str(body)?;
+
+	Ok(job_board
+		.jobs
+		.into_iter()
+		.filter(|job| job.is_listed.unwrap_or(true))
+		.map(|job| new_posting(job.title, job.job_url, job.location, job.department.or(job.team), &job.description_html.unwrap_or_default()))
+		.collect())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_recorded_job_board() {
+		let postings = parse_postings(include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/ats/ashby.json"))).unwrap();
+
+		// unlisted jobs are skipped
+		let titles: Vec<&str> = postings.iter().map(|p| p.title.as_str()).collect();
+		assert_eq!(titles, vec!["Data Scientist", "Support Specialist"]);
+
+		let posting = &postings[0];
+		assert_eq!(posting.url.as_deref(), Some("https://jobs.ashbyhq.com/acme/5f1c2d3e-4b5a-6978-8a9b-0c1d2e3f4a5b"));
+		assert_eq!(posting.location.as_deref(), Some("New York, NY"));
+		assert_eq!(posting.department.as_deref(), Some("Data"));
+		assert!(posting.content.as_deref().unwrap().contains("recommendations"));
+
+		// the team is used if there is no department
+		assert_eq!(postings[1].department.as_deref(), Some("Customer Success"));
+	}
+}
@@ -0,0 +1,92 @@
+// Greenhouse job board API
+use crate::ats::{new_posting, unescape_html};
+use crate::entities::posting;
+use anyhow::Result;
+
+use serde::Deserialize;
+
+/// Job board API response.
+#[derive(Debug, Deserialize)]
+struct JobBoard {
+	jobs: Vec<Job>,
+}
+
+/// Job posted on the job board.
+#[derive(Debug, Deserialize)]
+struct Job {
+	title: String,
+	absolute_url: Option<String>,
+	location: Option<Location>,
+	#[serde(default)]
+	departments: Vec<Department>,
+	/// HTML description, escaped with HTML entities
+	content: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Location {
+	name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Department {
+	name: String,
+}
+
+/// Fetch all job postings of the Greenhouse job board `board`.
+pub async fn fetch_postings(client: &reqwest::Client, board: &str, eu: bool) -> Result<Vec<posting::Model>> {
+	let host = if eu { "boards-api.eu.greenhouse.io" } else { "boards-api.greenhouse.io" };
+	let url = format!("https://{host}/v1/boards/{board}/jobs?content=true");
+	let body = client.get(url).send().await?.error_for_status()?.text().await?;
+
+	parse_postings(&body)
+}
+
+/// Parse the job postings of an API response.
+fn parse_postings(body: &str) -> Result<Vec<posting::Model>> {
+	let job_board: JobBoard = serde_json::from_str(body)?;
+
+	Ok(job_board
+		.jobs
+		.into_iter()
+		.map(|job| {
+			let department = job.departments.into_iter().map(|d| d.name).collect::<Vec<String>>().join(", ");
+			let description = unescape_html(&job.content.unwrap_or_default());
+			new_posting(job.title, job.absolute_url, job.location.and_then(|l| l.name), Some(department), &description)
+		})
+		.collect())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_recorded_job_board() {
+		let postings = parse_postings(include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/ats/greenhouse.json"))).unwrap();
+		assert_eq!(postings.len(), 2);
+
+		let posting = &postings[0];
+		assert_eq!(posting.title, "Senior Backend Engineer");
+		assert_eq!(posting.url.as_deref(), Some("https://boards.greenhouse.io/acme/jobs/4012345006"));
+		assert_eq!(posting.location.as_deref(), Some("Berlin, Germany"));
+		assert_eq!(posting.department.as_deref(), Some("Engineering, Platform"));
+
+		// the escaped HTML description is converted to markdown
+		let content = posting.content.as_deref().unwrap();
+		assert!(content.contains("Senior Backend Engineer"));
+		assert!(content.contains("PostgreSQL"));
+		assert!(!content.contains("&lt;") && !content.contains("<p>"));
+
+		let posting = &postings[1];
+		assert_eq!(posting.title, "Office Manager");
+		assert_eq!(posting.location, None);
+		assert_eq!(posting.department, None);
+		assert_eq!(posting.content, None);
+	}
+
+	#[test]
+	fn rejects_unexpected_response() {
+		assert!(parse_postings(r#"{"error": "not found"}"#).is_err());
+	}
+}
@@ -0,0 +1,96 @@
+// Lever postings API
+use crate::ats::new_posting;
+use crate::entities::posting;
+use anyhow::Result;
+
+use serde::Deserialize;
+
+/// Job posting returned by the postings API.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Job {
+	/// Job title
+	text: String,
+	hosted_url: Option<String>,
+	#[serde(default)]
+	categories: Categories,
+	/// HTML description
+	description: Option<String>,
+	/// Sections of the description, e.g. requirements
+	#[serde(default)]
+	lists: Vec<List>,
+	/// HTML closing of the description
+	additional: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Categories {
+	location: Option<String>,
+	department: Option<String>,
+	team: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct List {
+	text: String,
+	/// HTML list items
+	content: String,
+}
+
+/// Fetch all job postings of the Lever job board `board`.
+pub async fn fetch_postings(client: &reqwest::Client, board: &str, eu: bool) -> Result<Vec<posting::Model>> {
+	let host = if eu { "api.eu.lever.co" } else { "api.lever.co" };
+	let url = format!("https://{host}/v0/postings/{board}?mode=json");
+	let body = client.get(url).send().await?.error_for_status()?.text().await?;
+
+	parse_postings(&body)
+}
+
+/// Parse the job postings of an API response.
+fn parse_postings(body: &str) -> Result<Vec<posting::Model>> {
+	let jobs: Vec<Job> = serde_json::from_str(body)?;
+
+	Ok(jobs
+		.into_iter()
+		.map(|job| {
+			// combine all parts of the description
+			let mut description = job.description.unwrap_or_default();
+			for list in job.lists {
+				description.push_str(&format!("<h3>{}</h3><ul>{}</ul>", list.text, list.content));
+			}
+			description.push_str(&job.additional.unwrap_or_default());
+
+			let department = job.categories.department.or(job.categories.team);
+			new_posting(job.text, job.hosted_url, job.categories.location, department, &description)
+		})
+		.collect())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_recorded_postings() {
+		let postings = parse_postings(include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/ats/lever.json"))).unwrap();
+		assert_eq!(postings.len(), 2);
+
+		let posting = &postings[0];
+		assert_eq!(posting.title, "Site Reliability Engineer");
+		assert_eq!(posting.url.as_deref(), Some("https://jobs.lever.co/acme/0b4f7c3e-5b7a-4c61-9d7e-1f2a3b4c5d6e"));
+		assert_eq!(posting.location.as_deref(), Some("Remote - Europe"));
+		assert_eq!(posting.department.as_deref(), Some("Engineering"));
+
+		// lists and the closing are part of the description
+		let content = posting.content.as_deref().unwrap();
+		assert!(content.contains("Site Reliability Engineer"));
+		assert!(content.contains("Requirements"));
+		assert!(content.contains("Kubernetes"));
+		assert!(content.contains("flexible working hours"));
+
+		// the team is used if there is no department
+		let posting = &postings[1];
+		assert_eq!(posting.department.as_deref(), Some("Design"));
+		assert_eq!(posting.content, None);
+	}
+}
@@ -0,0 +1,204 @@
+// Applicant tracking system (ATS) integrations
+pub mod ashby;
+pub mod greenhouse;
+pub mod lever;
+pub mod workable;
+
+use crate::entities::posting;
+use anyhow::anyhow;
+use anyhow::Result;
+
+use html2md::parse_html;
+use scraper::Html;
+use std::time::Duration;
+use url::Url;
+
+/// Applicant tracking systems with public job board APIs.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Ats {
+	Greenhouse,
+	Lever,
+	Ashby,
+	Workable,
+}
+
+impl Ats {
+	/// All supported applicant tracking systems.
+	pub const ALL: [Ats; 4] = [Ats::Greenhouse, Ats::Lever, Ats::Ashby, Ats::Workable];
+
+	/// Returns the source kind the applicant tracking system is stored as.
+	pub fn kind(&self) -> &'static str {
+		match self {
+			Ats::Greenhouse => "greenhouse",
+			Ats::Lever => "lever",
+			Ats::Ashby => "ashby",
+			Ats::Workable => "workable",
+		}
+	}
+
+	/// Returns the applicant tracking system of a source kind, if the source is hosted on one.
+	pub fn from_kind(kind: &Option<String>) -> Option<Self> {
+		Ats::ALL.into_iter().find(|ats| Some(ats.kind()) == kind.as_deref())
+	}
+
+	/// Detect the applicant tracking system a job board URL is hosted on.
+	pub fn detect(url: &str) -> Option<Self> {
+		Ats::ALL.into_iter().find(|ats| ats.board(url).is_some())
+	}
+
+	/// Returns the identifier of the company's job board from its URL,
+	/// e.g. `acme` for `https://boards.greenhouse.io/acme`.
+	pub fn board(&self, url: &str) -> Option<String> {
+		let url = Url::parse(url).ok()?;
+		let host = url.host_str()?;
+		let first_segment = url.path_segments().and_then(|mut s| s.next()).filter(|s| !s.is_empty()).map(|s| s.to_string());
+
+		match self {
+			Ats::Greenhouse => match host {
+				// embedded job boards reference the board via query parameter
+				"boards.greenhouse.io" | "job-boards.greenhouse.io" | "boards.eu.greenhouse.io" | "job-boards.eu.greenhouse.io" => {
+					url.query_pairs().find(|(k, _)| k == "for").map(|(_, v)| v.to_string()).or(first_segment.filter(|s| s != "embed"))
+				}
+				_ => None,
+			},
+			Ats::Lever => match host {
+				"jobs.lever.co" | "jobs.eu.lever.co" => first_segment,
+				_ => None,
+			},
+			Ats::Ashby => match host {
+				"jobs.ashbyhq.com" => first_segment,
+				_ => None,
+			},
+			Ats::Workable => match host {
+				"apply.workable.com" => first_segment.filter(|s| s != "api"),
+				// legacy job boards are hosted on a subdomain
+				_ => host.strip_suffix(".workable.com").filter(|s| !s.contains('.') && *s != "www").map(|s| s.to_string()),
+			},
+		}
+	}
+
+	/// Fetch all open job postings from the job board at `url`.
+	pub async fn fetch_postings(&self, url: &str) -> Result<Vec<posting::Model>> {
+		let board = self.board(url).ok_or(anyhow!("{url} is not a {} job board", self.kind()))?;
+		let client = reqwest::Client::builder().timeout(Duration::from_secs(30)).build()?;
+
+		match self {
+			Ats::Greenhouse => greenhouse::fetch_postings(&client, &board, is_eu(url)).await,
+			Ats::Lever => lever::fetch_postings(&client, &board, is_eu(url)).await,
+			Ats::Ashby => ashby::fetch_postings(&client, &board).await,
+			Ats::Workable => workable::fetch_postings(&client, &board).await,
+		}
+	}
+}
+
+/// Returns whether the job board is hosted in the EU region of the applicant tracking system.
+fn is_eu(url: &str) -> bool {
+	Url::parse(url).ok().and_then(|u| u.host_str().map(|h| h.contains(".eu."))).unwrap_or(false)
+}
+
+/// Create a new posting from the information provided by the applicant tracking system.
+/// The posting description is converted from HTML to markdown.
+fn new_posting(title: String, url: Option<String>, location: Option<String>, department: Option<String>, description_html: &str) -> posting::Model {
+	posting::Model {
+		id: 0,
+		title: title.trim().to_string(),
+		description: None,
+		url,
+		created_at: None,
+		source_id: None,
+		content: Some(parse_html(description_html)).filter(|c| !c.trim().is_empty()),
+		location: location.filter(|l| !l.trim().is_empty()),
+		department: department.filter(|d| !d.trim().is_empty()),
+		external_id: None,
+		published_at: None,
+		canonical_url: None,
+		last_seen_at: None,
+		closed_at: None,
+		workplace: None,
+		employment_type: None,
+		seniority: None,
+		salary_min: None,
+		salary_max: None,
+		salary_currency: None,
+	}
+}
+
+/// Decode HTML that has been escaped with HTML entities, e.g. `&lt;p&gt;`.
+fn unescape_html(escaped: &str) -> String {
+	Html::parse_fragment(escaped).root_element().text().collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn greenhouse_board() {
+		assert_eq!(Ats::Greenhouse.board("https://boards.greenhouse.io/acme"), Some("acme".to_string()));
+		assert_eq!(Ats::Greenhouse.board("https://job-boards.greenhouse.io/acme/jobs/4012345006"), Some("acme".to_string()));
+		assert_eq!(Ats::Greenhouse.board("https://boards.eu.greenhouse.io/acme"), Some("acme".to_string()));
+		assert_eq!(Ats::Greenhouse.board("https://job-boards.eu.greenhouse.io/acme"), Some("acme".to_string()));
+
+		// embedded job boards
+		assert_eq!(Ats::Greenhouse.board("https://boards.greenhouse.io/embed/job_board?for=acme"), Some("acme".to_string()));
+		assert_eq!(
+			Ats::Greenhouse.board("https://boards.eu.greenhouse.io/embed/job_board?for=acme&b=https%3A%2F%2Facme.com"),
+			Some("acme".to_string())
+		);
+		assert_eq!(Ats::Greenhouse.board("https://boards.greenhouse.io/embed/job_board"), None);
+
+		assert_eq!(Ats::Greenhouse.board("https://boards.greenhouse.io/"), None);
+		assert_eq!(Ats::Greenhouse.board("https://greenhouse.io/acme"), None);
+	}
+
+	#[test]
+	fn lever_board() {
+		assert_eq!(Ats::Lever.board("https://jobs.lever.co/acme"), Some("acme".to_string()));
+		assert_eq!(Ats::Lever.board("https://jobs.eu.lever.co/acme/0b4f7c3e-5b7a-4c61-9d7e-1f2a3b4c5d6e"), Some("acme".to_string()));
+		assert_eq!(Ats::Lever.board("https://jobs.lever.co/"), None);
+	}
+
+	#[test]
+	fn ashby_board() {
+		assert_eq!(Ats::Ashby.board("https://jobs.ashbyhq.com/acme"), Some("acme".to_string()));
+		assert_eq!(Ats::Ashby.board("https://jobs.ashbyhq.com/acme/5f1c2d3e-4b5a-6978-8a9b-0c1d2e3f4a5b"), Some("acme".to_string()));
+		assert_eq!(Ats::Ashby.board("https://ashbyhq.com/acme"), None);
+	}
+
+	#[test]
+	fn workable_board() {
+		assert_eq!(Ats::Workable.board("https://apply.workable.com/acme/"), Some("acme".to_string()));
+		assert_eq!(Ats::Workable.board("https://apply.workable.com/api/v1/widget/accounts/acme"), None);
+
+		// legacy job boards
+		assert_eq!(Ats::Workable.board("https://acme.workable.com"), Some("acme".to_string()));
+		assert_eq!(Ats::Workable.board("https://www.workable.com"), None);
+		assert_eq!(Ats::Workable.board("https://jobs.acme.workable.com"), None);
+	}
+
+	#[test]
+	fn detect() {
+		assert_eq!(Ats::detect("https://boards.greenhouse.io/embed/job_board?for=acme"), Some(Ats::Greenhouse));
+		assert_eq!(Ats::detect("https://jobs.eu.lever.co/acme"), Some(Ats::Lever));
+		assert_eq!(Ats::detect("https://jobs.ashbyhq.com/acme"), Some(Ats::Ashby));
+		assert_eq!(Ats::detect("https://apply.workable.com/acme/"), Some(Ats::Workable));
+		assert_eq!(Ats::detect("https://acme.com/careers"), None);
+		assert_eq!(Ats::detect("not a url"), None);
+	}
+
+	#[test]
+	fn eu_region() {
+		assert!(is_eu("https://job-boards.eu.greenhouse.io/acme"));
+		assert!(is_eu("https://jobs.eu.lever.co/acme"));
+		assert!(!is_eu("https://jobs.lever.co/acme"));
+	}
+
+	#[test]
+	fn kind() {
+		for ats in Ats::ALL {
+			assert_eq!(Ats::from_kind(&Some(ats.kind().to_string())), Some(ats));
+		}
+		assert_eq!(Ats::from_kind(&Some("page".to_string())), None);
+		assert_eq!(Ats::from_kind(&None), None);
+	}
+}
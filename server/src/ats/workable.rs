@@ -0,0 +1,76 @@
+// Workable job board API
+use crate::ats::new_posting;
+use crate::entities::posting;
+use anyhow::Result;
+
+use serde::Deserialize;
+
+/// Account API response.
+#[derive(Debug, Deserialize)]
+struct Account {
+	jobs: Vec<Job>,
+}
+
+/// Job posted by the account.
+#[derive(Debug, Deserialize)]
+struct Job {
+	title: String,
+	url: Option<String>,
+	department: Option<String>,
+	city: Option<String>,
+	state: Option<String>,
+	country: Option<String>,
+	telecommuting: Option<bool>,
+	/// HTML description
+	description: Option<String>,
+}
+
+/// Fetch all job postings of the Workable account `board`.
+pub async fn fetch_postings(client: &reqwest::Client, board: &str) -> Result<Vec<posting::Model>> {
+	let url = format!("https://www.workable.com/api/accounts/{board}?details=true");
+	let body = client.get(url).send().await?.error_for_status()?.text().await?;
+
+	parse_postings(&body)
+}
+
+/// Parse the job postings of an API response.
+fn parse_postings(body: &str) -> Result<Vec<posting::Model>> {
+	let account: Account = serde_json::from_str(body)?;
+
+	Ok(account
+		.jobs
+		.into_iter()
+		.map(|job| {
+			let mut location = [job.city, job.state, job.country].into_iter().flatten().filter(|l| !l.is_empty()).collect::<Vec<String>>().join(", ");
+			if job.telecommuting.unwrap_or(false) {
+				location = if location.is_empty() { "Remote".to_string() } else { format!("{location} (Remote)") };
+			}
+
+			new_posting(job.title, job.url, Some(location), job.department, &job.description.unwrap_or_default())
+		})
+		.collect())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_recorded_account() {
+		let postings = parse_postings(include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/ats/workable.json"))).unwrap();
+		assert_eq!(postings.len(), 2);
+
+		let posting = &postings[0];
+		assert_eq!(posting.title, "Frontend Developer");
+		assert_eq!(posting.url.as_deref(), Some("https://apply.workable.com/j/A1B2C3D4E5"));
+		assert_eq!(posting.location.as_deref(), Some("Amsterdam, North Holland, Netherlands (Remote)"));
+		assert_eq!(posting.department.as_deref(), Some("Engineering"));
+		assert!(posting.content.as_deref().unwrap().contains("Svelte"));
+
+		// remote jobs without a location
+		let posting = &postings[1];
+		assert_eq!(posting.location.as_deref(), Some("Remote"));
+		assert_eq!(posting.department, None);
+		assert_eq!(posting.content, None);
+	}
+}
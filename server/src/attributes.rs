@@ -0,0 +1,123 @@
+/// Structured attributes of job postings, such as the location or salary, extracted from the posting details.
+use crate::entities::{posting, settings};
+use crate::llm::{llm_provider, Task};
+
+use anyhow::Result;
+use chrono::{FixedOffset, NaiveDate};
+use serde::Deserialize;
+
+/// Maximum number of characters of the posting details that are sent to the LLM
+const ATTRIBUTES_MAX_CHARS: usize = 12000;
+
+/// Where a job can be done
+pub const WORKPLACES: [&str; 3] = ["remote", "hybrid", "onsite"];
+
+/// Kinds of employment
+pub const EMPLOYMENT_TYPES: [&str; 5] = ["full_time", "part_time", "contract", "internship", "temporary"];
+
+/// Seniority levels, from least to most senior
+pub const SENIORITIES: [&str; 5] = ["entry", "mid", "senior", "lead", "executive"];
+
+/// Attributes of a job posting as returned by the LLM.
+/// Attributes that aren't mentioned in the posting are `None`.
+#[derive(Debug, Default, Deserialize)]
+pub struct PostingAttributes {
+	/// Location of the job
+	location: Option<String>,
+
+	/// Where the job is done, one of `WORKPLACES`
+	workplace: Option<String>,
+
+	/// Kind of employment, one of `EMPLOYMENT_TYPES`
+	employment_type: Option<String>,
+
+	/// Seniority level, one of `SENIORITIES`
+	seniority: Option<String>,
+
+	/// Department or team the job is in
+	department: Option<String>,
+
+	/// Lower bound of the yearly salary
+	salary_min: Option<f64>,
+
+	/// Upper bound of the yearly salary
+	salary_max: Option<f64>,
+
+	/// ISO 4217 currency code of the salary
+	salary_currency: Option<String>,
+
+	/// Date the posting was published, as `YYYY-MM-DD`
+	posted_at: Option<String>,
+}
+
+impl PostingAttributes {
+	/// Set the attributes of the posting that aren't known yet, e.g. because they weren't provided by the applicant
+	/// tracking system. Values the LLM returned in an unexpected format are ignored.
+	pub fn apply(self, posting: &mut posting::Model) {
+		posting.location = posting.location.take().or(non_empty(self.location));
+		posting.department = posting.department.take().or(non_empty(self.department));
+		posting.workplace = posting.workplace.take().or(one_of(self.workplace, &WORKPLACES));
+		posting.employment_type = posting.employment_type.take().or(one_of(self.employment_type, &EMPLOYMENT_TYPES));
+		posting.seniority = posting.seniority.take().or(one_of(self.seniority, &SENIORITIES));
+
+		if posting.salary_min.is_none() && posting.salary_max.is_none() {
+			let salary_min = self.salary_min.filter(|s| *s > 0.0).map(|s| s.round() as i64);
+			let salary_max = self.salary_max.filter(|s| *s > 0.0).map(|s| s.round() as i64);
+
+			posting.salary_min = match (salary_min, salary_max) {
+				(Some(min), Some(max)) => Some(min.min(max)),
+				(min, max) => min.or(max),
+			};
+			posting.salary_max = match (salary_min, salary_max) {
+				(Some(min), Some(max)) => Some(min.max(max)),
+				(min, max) => max.or(min),
+			};
+
+			if posting.salary_min.is_some() {
+				posting.salary_currency = self
+					.salary_currency
+					.map(|c| c.trim().to_uppercase())
+					.filter(|c| c.len() == 3 && c.chars().all(|c| c.is_ascii_alphabetic()));
+			}
+		}
+
+		if posting.published_at.is_none() {
+			posting.published_at = self
+				.posted_at
+				.and_then(|d| NaiveDate::parse_from_str(d.trim(), "%Y-%m-%d").ok())
+				.and_then(|d| d.and_hms_opt(0, 0, 0))
+				.and_then(|d| d.and_local_timezone(FixedOffset::east_opt(0).unwrap()).single());
+		}
+	}
+}
+
+/// Returns the value, unless it is empty.
+fn non_empty(value: Option<String>) -> Option<String> {
+	value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+/// Returns the value if it is one of the `allowed` values.
+fn one_of(value: Option<String>, allowed: &[&str]) -> Option<String> {
+	value.map(|v| v.trim().to_lowercase()).filter(|v| allowed.contains(&v.as_str()))
+}
+
+/// Use the configured LLM provider to extract the attributes of a posting from its details.
+///
+/// Returns `None` if the details of the posting are unknown.
+pub async fn extract_attributes(settings: &settings::Model, posting: &posting::Model) -> Result<Option<PostingAttributes>> {
+	let content = match posting.content.as_ref().or(posting.description.as_ref()) {
+		Some(content) if !content.trim().is_empty() => content,
+		_ => return Ok(None),
+	};
+
+	let llm = llm_provider(settings, Task::PostingAttributes);
+
+	// limit the size of the posting details sent to the LLM
+	let content: String = content.chars().take(ATTRIBUTES_MAX_CHARS).collect();
+	let message = format!("Title: {}\n\n{content}", posting.title);
+
+	match llm.run(&[message]).await?.into_iter().next() {
+		Some(item) => Ok(Some(serde_json::from_value(item)?)),
+		None => Ok(None),
+	}
+}
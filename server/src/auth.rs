@@ -0,0 +1,328 @@
+/// Authentication of the user making a request.
+use crate::entities::{prelude::*, *};
+use crate::error::{Error, ErrorBody, Result};
+use crate::pool::Db;
+
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use base64::engine::general_purpose::{STANDARD as BASE64, URL_SAFE_NO_PAD};
+use base64::Engine;
+use chrono::FixedOffset;
+use rand::RngCore;
+use rocket::http::{Cookie, SameSite, Status};
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket::response::{self, Responder, Response};
+use rocket::serde::json::Json;
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::sea_query::Expr;
+use sea_orm::*;
+use sea_orm_rocket::Connection;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// Minimum number of characters of a password
+pub const MIN_PASSWORD_CHARS: usize = 8;
+
+/// Name of the cookie storing the session token
+pub const SESSION_COOKIE: &str = "session";
+
+/// Number of days a session stays valid after signing in
+const SESSION_DAYS: i64 = 30;
+
+/// Prefix of API tokens, so they can be told apart from other secrets
+pub const API_TOKEN_PREFIX: &str = "jf_";
+
+/// Number of random bytes of session and API tokens
+const TOKEN_BYTES: usize = 32;
+
+/// Realm of the `WWW-Authenticate` challenge
+const REALM: &str = "Jobs Feed";
+
+/// Number of seconds a successful password verification is remembered
+const VERIFIED_PASSWORD_SECONDS: u64 = 600;
+
+/// Maximum number of remembered password verifications
+const VERIFIED_PASSWORDS_MAX: usize = 1000;
+
+/// Passwords that have recently been verified, and when, so that feed readers polling with basic authentication don't
+/// cost an Argon2 hash on every request. Keyed by `verification_digest`.
+static VERIFIED_PASSWORDS: std::sync::Mutex<BTreeMap<String, Instant>> = std::sync::Mutex::new(BTreeMap::new());
+
+/// Random key of the verification digests; generated once per process, so digests can't be used to guess passwords.
+static VERIFICATION_KEY: OnceLock<String> = OnceLock::new();
+
+/// Account of the authenticated user making the request.
+///
+/// Routes that take a `User` require the request to be authenticated in one of three ways:
+/// * with an API token in the `Authorization: Bearer <token>` header, for scripts
+/// * with the session cookie that is set when signing in via `POST /login`, for the UI
+/// * with the username and password of an account, or an API token as password, via HTTP basic authentication, e.g.
+///   for feed readers
+///
+/// Requests without valid credentials are rejected with `401`.
+#[derive(Clone, Debug)]
+pub struct User {
+	/// Account ID
+	pub id: i32,
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for User {
+	type Error = Error;
+
+	async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+		let db = match req.guard::<Connection<'_, Db>>().await {
+			Outcome::Success(conn) => conn.into_inner(),
+			_ => return Outcome::Error((Status::InternalServerError, Error::Database(DbErr::Custom("No database connection".to_string())))),
+		};
+
+		match authenticate_request(db, req).await {
+			Ok(Some(account_id)) => Outcome::Success(User { id: account_id }),
+			Ok(None) => Outcome::Error((Status::Unauthorized, Error::Unauthorized("Authentication required".to_string()))),
+			Err(e) => Outcome::Error((e.status(), e)),
+		}
+	}
+}
+
+/// Returns the ID of the account the request is authenticated as, or `None` if the request doesn't contain valid
+/// credentials.
+async fn authenticate_request(db: &DatabaseConnection, req: &Request<'_>) -> Result<Option<i32>> {
+	if let Some(header) = req.headers().get_one("Authorization") {
+		if let Some(token) = header.strip_prefix("Bearer ") {
+			return api_token_account(db, token.trim()).await;
+		}
+
+		if let Some((username, password)) = basic_credentials(header) {
+			// feed readers can send an API token as password, which doesn't need to be verified with Argon2
+			if password.starts_with(API_TOKEN_PREFIX) {
+				if let Some(account_id) = api_token_account(db, &password).await? {
+					return Ok(Some(account_id));
+				}
+			}
+
+			return Ok(authenticate(db, &username, &password).await?.map(|account| account.id));
+		}
+	}
+
+	match req.cookies().get(SESSION_COOKIE) {
+		Some(cookie) => session_account(db, cookie.value()).await,
+		None => Ok(None),
+	}
+}
+
+/// Parse the username and password of a basic `Authorization` header.
+fn basic_credentials(header: &str) -> Option<(String, String)> {
+	let encoded = header.strip_prefix("Basic ")?;
+	let decoded = String::from_utf8(BASE64.decode(encoded.trim()).ok()?).ok()?;
+	let (username, password) = decoded.split_once(':')?;
+
+	Some((username.to_string(), password.to_string()))
+}
+
+/// Returns the ID of the account the API token belongs to, and records that the token has been used.
+async fn api_token_account(db: &DatabaseConnection, token: &str) -> Result<Option<i32>> {
+	let api_token = match ApiToken::find().filter(api_token::Column::TokenHash.eq(token_hash(token))).one(db).await? {
+		Some(api_token) => api_token,
+		None => return Ok(None),
+	};
+
+	ApiToken::update_many()
+		.col_expr(api_token::Column::LastUsedAt, Expr::value(now()))
+		.filter(api_token::Column::Id.eq(api_token.id))
+		.exec(db)
+		.await?;
+
+	Ok(Some(api_token.account_id))
+}
+
+/// Returns the ID of the account that signed in with the session token, if the session hasn't expired.
+async fn session_account(db: &DatabaseConnection, token: &str) -> Result<Option<i32>> {
+	Ok(Session::find()
+		.filter(session::Column::TokenHash.eq(token_hash(token)))
+		.filter(session::Column::ExpiresAt.gt(now()))
+		.one(db)
+		.await?
+		.map(|session| session.account_id))
+}
+
+/// Returns the account with the username if the password matches.
+/// Accounts without password can't be authenticated. Successful verifications are remembered for a few minutes.
+pub async fn authenticate(db: &DatabaseConnection, username: &str, password: &str) -> Result<Option<account::Model>> {
+	let account = match Account::find().filter(account::Column::Username.eq(username.trim())).one(db).await? {
+		Some(account) => account,
+		None => return Ok(None),
+	};
+
+	let hash = match &account.password_hash {
+		Some(hash) => hash.clone(),
+		None => return Ok(None),
+	};
+
+	let digest = verification_digest(password, &hash);
+	if recently_verified(&digest) {
+		return Ok(Some(account));
+	}
+
+	// hashing is CPU bound, so it doesn't run on the async runtime
+	let password = password.to_string();
+	let verified = tokio::task::spawn_blocking(move || verify_password(&password, &hash))
+		.await
+		.map_err(|e| Error::internal(format!("Could not verify password: {e}")))?;
+
+	if !verified {
+		return Ok(None);
+	}
+
+	remember_verified(digest);
+
+	Ok(Some(account))
+}
+
+/// Returns the keyed SHA-256 digest of a password and the Argon2 hash it is verified against.
+/// Changing the password changes the hash, so verifications of the previous password are no longer remembered.
+fn verification_digest(password: &str, hash: &str) -> String {
+	let key = VERIFICATION_KEY.get_or_init(generate_token);
+
+	format!(
+		"{:x}",
+		Sha256::new().chain_update(key).chain_update([0]).chain_update(hash).chain_update([0]).chain_update(password).finalize()
+	)
+}
+
+/// Returns whether the password with the verification digest has been verified recently.
+fn recently_verified(digest: &str) -> bool {
+	let verified = VERIFIED_PASSWORDS.lock().unwrap();
+
+	verified.get(digest).is_some_and(|at| at.elapsed() < Duration::from_secs(VERIFIED_PASSWORD_SECONDS))
+}
+
+/// Remember that the password with the verification digest has been verified.
+/// Expired verifications are removed; once the maximum is reached, new verifications aren't remembered.
+fn remember_verified(digest: String) {
+	let mut verified = VERIFIED_PASSWORDS.lock().unwrap();
+	verified.retain(|_, at| at.elapsed() < Duration::from_secs(VERIFIED_PASSWORD_SECONDS));
+
+	if verified.len() < VERIFIED_PASSWORDS_MAX {
+		verified.insert(digest, Instant::now());
+	}
+}
+
+/// Returns the Argon2 hash of the password, including a random salt.
+pub async fn hash_password(password: &str) -> Result<String> {
+	let password = password.to_string();
+
+	tokio::task::spawn_blocking(move || {
+		let salt = SaltString::generate(&mut OsRng);
+		Argon2::default().hash_password(password.as_bytes(), &salt).map(|hash| hash.to_string())
+	})
+	.await
+	.map_err(|e| Error::internal(format!("Could not hash password: {e}")))?
+	.map_err(|e| Error::internal(format!("Could not hash password: {e}")))
+}
+
+/// Returns whether the password matches the Argon2 hash.
+fn verify_password(password: &str, hash: &str) -> bool {
+	match PasswordHash::new(hash) {
+		Ok(hash) => Argon2::default().verify_password(password.as_bytes(), &hash).is_ok(),
+		Err(_) => false,
+	}
+}
+
+/// Returns a new random token.
+pub fn generate_token() -> String {
+	let mut bytes = [0u8; TOKEN_BYTES];
+	rand::rngs::OsRng.fill_bytes(&mut bytes);
+
+	URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Returns the SHA-256 hash of a token.
+/// Tokens are random, so unlike passwords they don't need a salted hash and can be looked up by their hash.
+pub fn token_hash(token: &str) -> String {
+	format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+/// Start a new session for the account; expired sessions of the account are removed.
+///
+/// Returns the cookie storing the session token.
+pub async fn create_session(db: &DatabaseConnection, account_id: i32) -> Result<Cookie<'static>> {
+	Session::delete_many()
+		.filter(session::Column::AccountId.eq(account_id))
+		.filter(session::Column::ExpiresAt.lte(now()))
+		.exec(db)
+		.await?;
+
+	let token = generate_token();
+	let new_session = session::ActiveModel {
+		id: NotSet,
+		account_id: Set(account_id),
+		token_hash: Set(token_hash(&token)),
+		created_at: Set(Some(now())),
+		expires_at: Set(now() + chrono::Duration::days(SESSION_DAYS)),
+	};
+	new_session.insert(db).await?;
+
+	Ok(Cookie::build((SESSION_COOKIE, token))
+		.path("/")
+		.http_only(true)
+		.same_site(SameSite::Lax)
+		.max_age(rocket::time::Duration::days(SESSION_DAYS))
+		.build())
+}
+
+/// End the session with the token.
+pub async fn delete_session(db: &DatabaseConnection, token: &str) -> Result<()> {
+	Session::delete_many().filter(session::Column::TokenHash.eq(token_hash(token))).exec(db).await?;
+
+	Ok(())
+}
+
+/// Returns the current timestamp.
+fn now() -> DateTimeWithTimeZone {
+	chrono::offset::Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap())
+}
+
+/// Error response asking the client to authenticate via HTTP basic authentication.
+pub struct Challenge;
+
+impl<'r> Responder<'r, 'static> for Challenge {
+	fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+		let body = ErrorBody {
+			error: "unauthorized".to_string(),
+			message: "Authentication required".to_string(),
+		};
+
+		let mut res = Response::build_from(Json(body).respond_to(req)?).status(Status::Unauthorized).finalize();
+		res.set_raw_header("WWW-Authenticate", format!("Basic realm=\"{REALM}\", charset=\"UTF-8\""));
+
+		Ok(res)
+	}
+}
+
+/// Ask feed readers for credentials if a request isn't authenticated.
+#[catch(401)]
+pub fn unauthorized(_req: &Request) -> Challenge {
+	Challenge
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn verifications_are_remembered_per_password_hash() {
+		let hash = "$argon2id$v=19$m=19456,t=2,p=1$c2FsdHNhbHQ$aGFzaA";
+		let digest = verification_digest("correct horse", hash);
+		assert!(!recently_verified(&digest));
+
+		remember_verified(digest.clone());
+		assert!(recently_verified(&digest));
+
+		assert!(!recently_verified(&verification_digest("wrong horse", hash)));
+		// the password has been changed
+		assert!(!recently_verified(&verification_digest("correct horse", "$argon2id$v=19$m=19456,t=2,p=1$b3RoZXI$aGFzaA")));
+	}
+}
@@ -0,0 +1,145 @@
+/// Shared pool of headless browser instances.
+use crate::error::Error;
+
+use headless_chrome::{Browser, LaunchOptionsBuilder, Tab};
+use serde::Deserialize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Browser pool configuration.
+///
+/// Read from the `browser` section of `Rocket.toml`; missing values fall back to the defaults.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct BrowserConfig {
+	/// Number of headless browser instances
+	pub instances: usize,
+
+	/// Maximum number of tabs that are open at the same time across all browser instances
+	pub max_tabs: usize,
+
+	/// Number of seconds without any browser activity after which a browser instance is considered dead
+	pub idle_timeout: u64,
+}
+
+impl Default for BrowserConfig {
+	fn default() -> Self {
+		BrowserConfig {
+			instances: 2,
+			max_tabs: 8,
+			idle_timeout: 600,
+		}
+	}
+}
+
+/// Pool of headless browser instances that hands out tabs.
+///
+/// Browsers are launched when the first tab is requested, and relaunched if their process has died.
+#[derive(Clone)]
+pub struct BrowserPool {
+	/// Browser pool configuration
+	config: BrowserConfig,
+
+	/// Browser instances; `None` if the instance hasn't been launched yet or needs to be relaunched
+	browsers: Arc<Mutex<Vec<Option<Browser>>>>,
+
+	/// Limits the number of open tabs
+	tabs: Arc<Semaphore>,
+
+	/// Index of the browser instance the next tab is opened in
+	next: Arc<AtomicUsize>,
+}
+
+/// Tab handed out by the browser pool.
+///
+/// The tab is closed once it is dropped.
+pub struct PooledTab {
+	/// Browser tab
+	tab: Arc<Tab>,
+
+	/// Keeps the tab counted towards the tab limit while it is open
+	_permit: OwnedSemaphorePermit,
+}
+
+impl PooledTab {
+	/// Returns the browser tab.
+	pub fn tab(&self) -> &Arc<Tab> {
+		&self.tab
+	}
+}
+
+impl Drop for PooledTab {
+	fn drop(&mut self) {
+		// closing the tab blocks until the browser responds, so it is moved off the async runtime if possible
+		let tab = self.tab.clone();
+		match tokio::runtime::Handle::try_current() {
+			Ok(handle) => {
+				handle.spawn_blocking(move || tab.close(true));
+			}
+			Err(_) => {
+				let _ = tab.close(true);
+			}
+		}
+	}
+}
+
+impl BrowserPool {
+	/// Create and return a new browser pool.
+	pub fn new(config: BrowserConfig) -> Self {
+		let instances = config.instances.max(1);
+		let max_tabs = config.max_tabs.max(1);
+
+		BrowserPool {
+			config,
+			browsers: Arc::new(Mutex::new(vec![None; instances])),
+			tabs: Arc::new(Semaphore::new(max_tabs)),
+			next: Arc::new(AtomicUsize::new(0)),
+		}
+	}
+
+	/// Open a new tab.
+	/// Waits until fewer than the maximum number of tabs are open.
+	///
+	/// Opening tabs and launching browsers block, so they run on a blocking thread. The lock on the browser instances is
+	/// only held to look up and store instances, so callers don't wait for each other's browser launches.
+	pub async fn new_tab(&self) -> Result<PooledTab, Error> {
+		let permit = self.tabs.clone().acquire_owned().await.map_err(Error::browser)?;
+		let index = self.next.fetch_add(1, Ordering::Relaxed) % self.config.instances.max(1);
+
+		// reuse the running browser instance if a tab can be opened
+		let running = self.browsers.lock().unwrap()[index].clone();
+		if let Some(browser) = running {
+			if let Ok(tab) = tokio::task::spawn_blocking(move || browser.new_tab()).await.map_err(Error::browser)? {
+				return Ok(PooledTab { tab, _permit: permit });
+			}
+
+			warn!("Browser instance {index} is not responding; relaunching");
+		}
+
+		// browser hasn't been launched yet or its process has died
+		let pool = self.clone();
+		let (browser, tab) = tokio::task::spawn_blocking(move || {
+			let browser = pool.launch()?;
+			let tab = browser.new_tab().map_err(Error::browser)?;
+			Ok::<_, Error>((browser, tab))
+		})
+		.await
+		.map_err(Error::browser)??;
+		self.browsers.lock().unwrap()[index] = Some(browser);
+
+		Ok(PooledTab { tab, _permit: permit })
+	}
+
+	/// Launch a new headless browser instance.
+	fn launch(&self) -> Result<Browser, Error> {
+		let opt = LaunchOptionsBuilder::default()
+			.headless(true)
+			.idle_browser_timeout(Duration::from_secs(self.config.idle_timeout))
+			.build()
+			.map_err(Error::browser)?;
+
+		Browser::new(opt).map_err(Error::browser)
+	}
+}
@@ -10,11 +10,44 @@ pub struct Model {
 	#[serde(skip_deserializing)]
 	pub id: i32,
 	pub name: String,
-	pub value: String,
 	pub created_at: Option<DateTimeWithTimeZone>,
+	#[sea_orm(column_type = "JsonBinary")]
+	pub rule: Json,
+	pub profile_id: Option<i32>,
+	#[serde(skip_deserializing)]
+	pub account_id: i32,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-pub enum Relation {}
+pub enum Relation {
+	#[sea_orm(
+		belongs_to = "super::account::Entity",
+		from = "Column::AccountId",
+		to = "super::account::Column::Id",
+		on_update = "NoAction",
+		on_delete = "Cascade"
+	)]
+	Account,
+	#[sea_orm(
+		belongs_to = "super::filter_profile::Entity",
+		from = "Column::ProfileId",
+		to = "super::filter_profile::Column::Id",
+		on_update = "NoAction",
+		on_delete = "Cascade"
+	)]
+	FilterProfile,
+}
+
+impl Related<super::account::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::Account.def()
+	}
+}
+
+impl Related<super::filter_profile::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::FilterProfile.def()
+	}
+}
 
 impl ActiveModelBehavior for ActiveModel {}
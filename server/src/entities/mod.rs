@@ -2,9 +2,19 @@
 
 pub mod prelude;
 
+pub mod account;
+pub mod api_token;
 pub mod embedding;
 pub mod filter;
+pub mod filter_profile;
 pub mod posting;
+pub mod posting_duplicate;
+pub mod posting_event;
+pub mod posting_state;
+pub mod refresh_job;
+pub mod scoring_model;
+pub mod session;
 pub mod settings;
 pub mod source;
+pub mod source_filter_profile;
 pub mod suggestion;
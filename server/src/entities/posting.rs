@@ -13,20 +13,34 @@ pub struct Model {
 	pub description: Option<String>,
 	pub url: Option<String>,
 	pub created_at: Option<DateTimeWithTimeZone>,
-	pub seen: Option<bool>,
 	pub source_id: Option<i32>,
-	pub bookmarked: Option<bool>,
 	#[sea_orm(column_type = "Text", nullable)]
 	pub content: Option<String>,
-	pub is_match: Option<bool>,
-	#[sea_orm(column_type = "Float", nullable)]
-	pub match_similarity: Option<f32>,
+	pub location: Option<String>,
+	pub department: Option<String>,
+	pub external_id: Option<String>,
+	pub published_at: Option<DateTimeWithTimeZone>,
+	pub canonical_url: Option<String>,
+	pub last_seen_at: Option<DateTimeWithTimeZone>,
+	pub closed_at: Option<DateTimeWithTimeZone>,
+	pub workplace: Option<String>,
+	pub employment_type: Option<String>,
+	pub seniority: Option<String>,
+	pub salary_min: Option<i64>,
+	pub salary_max: Option<i64>,
+	pub salary_currency: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
 	#[sea_orm(has_many = "super::embedding::Entity")]
 	Embedding,
+	#[sea_orm(has_many = "super::posting_duplicate::Entity")]
+	PostingDuplicate,
+	#[sea_orm(has_many = "super::posting_event::Entity")]
+	PostingEvent,
+	#[sea_orm(has_many = "super::posting_state::Entity")]
+	PostingState,
 	#[sea_orm(
 		belongs_to = "super::source::Entity",
 		from = "Column::SourceId",
@@ -43,6 +57,24 @@ impl Related<super::embedding::Entity> for Entity {
 	}
 }
 
+impl Related<super::posting_duplicate::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::PostingDuplicate.def()
+	}
+}
+
+impl Related<super::posting_event::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::PostingEvent.def()
+	}
+}
+
+impl Related<super::posting_state::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::PostingState.def()
+	}
+}
+
 impl Related<super::source::Entity> for Entity {
 	fn to() -> RelationDef {
 		Relation::Source.def()
@@ -1,8 +1,18 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.12.10
 
+pub use super::account::Entity as Account;
+pub use super::api_token::Entity as ApiToken;
 pub use super::embedding::Entity as Embedding;
 pub use super::filter::Entity as Filter;
+pub use super::filter_profile::Entity as FilterProfile;
 pub use super::posting::Entity as Posting;
+pub use super::posting_duplicate::Entity as PostingDuplicate;
+pub use super::posting_event::Entity as PostingEvent;
+pub use super::posting_state::Entity as PostingState;
+pub use super::refresh_job::Entity as RefreshJob;
+pub use super::scoring_model::Entity as ScoringModel;
+pub use super::session::Entity as Session;
 pub use super::settings::Entity as Settings;
 pub use super::source::Entity as Source;
+pub use super::source_filter_profile::Entity as SourceFilterProfile;
 pub use super::suggestion::Entity as Suggestion;
@@ -11,9 +11,30 @@ pub struct Model {
 	pub id: i32,
 	pub api_key: Option<String>,
 	pub model: Option<String>,
+	pub provider: Option<String>,
+	pub base_url: Option<String>,
+	pub embedding_model: Option<String>,
+	#[sea_orm(unique)]
+	#[serde(skip_deserializing)]
+	pub account_id: i32,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
-pub enum Relation {}
+pub enum Relation {
+	#[sea_orm(
+		belongs_to = "super::account::Entity",
+		from = "Column::AccountId",
+		to = "super::account::Column::Id",
+		on_update = "NoAction",
+		on_delete = "Cascade"
+	)]
+	Account,
+}
+
+impl Related<super::account::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::Account.def()
+	}
+}
 
 impl ActiveModelBehavior for ActiveModel {}
@@ -19,22 +19,76 @@ pub struct Model {
 	pub favicon: Option<String>,
 	pub unreachable: Option<bool>,
 	pub deleted: bool,
+	pub refresh_interval: Option<i32>,
+	pub refreshed_at: Option<DateTimeWithTimeZone>,
+	pub fetch_mode: Option<String>,
+	pub kind: Option<String>,
+	#[serde(skip_deserializing)]
+	pub account_id: Option<i32>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+	#[sea_orm(
+		belongs_to = "super::account::Entity",
+		from = "Column::AccountId",
+		to = "super::account::Column::Id",
+		on_update = "NoAction",
+		on_delete = "SetNull"
+	)]
+	Account,
 	#[sea_orm(has_many = "super::posting::Entity")]
 	Posting,
+	#[sea_orm(has_many = "super::posting_duplicate::Entity")]
+	PostingDuplicate,
+	#[sea_orm(has_many = "super::refresh_job::Entity")]
+	RefreshJob,
+	#[sea_orm(has_many = "super::source_filter_profile::Entity")]
+	SourceFilterProfile,
 	#[sea_orm(has_many = "super::suggestion::Entity")]
 	Suggestion,
 }
 
+impl Related<super::account::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::Account.def()
+	}
+}
+
 impl Related<super::posting::Entity> for Entity {
 	fn to() -> RelationDef {
 		Relation::Posting.def()
 	}
 }
 
+impl Related<super::posting_duplicate::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::PostingDuplicate.def()
+	}
+}
+
+impl Related<super::refresh_job::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::RefreshJob.def()
+	}
+}
+
+impl Related<super::source_filter_profile::Entity> for Entity {
+	fn to() -> RelationDef {
+		Relation::SourceFilterProfile.def()
+	}
+}
+
+impl Related<super::filter_profile::Entity> for Entity {
+	fn to() -> RelationDef {
+		super::source_filter_profile::Relation::FilterProfile.def()
+	}
+
+	fn via() -> Option<RelationDef> {
+		Some(super::source_filter_profile::Relation::Source.def().rev())
+	}
+}
+
 impl Related<super::suggestion::Entity> for Entity {
 	fn to() -> RelationDef {
 		Relation::Suggestion.def()
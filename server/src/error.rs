@@ -0,0 +1,146 @@
+/// Errors returned by API routes.
+use rocket::http::Status;
+use rocket::request::Request;
+use rocket::response::{self, Responder, Response};
+use rocket::serde::json::Json;
+use sea_orm::DbErr;
+use serde::Serialize;
+use std::fmt::Display;
+
+/// Result type used across the server.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Error that can occur while handling a request.
+///
+/// Every variant is mapped to an HTTP status code and returned as a JSON error body.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	/// Database query failed
+	#[error("Database error: {0}")]
+	Database(#[from] DbErr),
+
+	/// Request to the LLM provider failed or returned an unexpected response
+	#[error("LLM error: {0}")]
+	Llm(String),
+
+	/// Headless browser could not open or parse a page
+	#[error("Browser error: {0}")]
+	Browser(String),
+
+	/// Requested entity does not exist
+	#[error("{0}")]
+	NotFound(String),
+
+	/// Request can't be processed, e.g. because required settings are missing
+	#[error("{0}")]
+	BadRequest(String),
+
+	/// Request contains invalid values
+	#[error("{0}")]
+	Validation(String),
+
+	/// Request isn't authenticated, or the credentials are invalid
+	#[error("{0}")]
+	Unauthorized(String),
+
+	/// Authenticated user isn't allowed to change the requested entity
+	#[error("{0}")]
+	Forbidden(String),
+
+	/// Unexpected server failure, e.g. a background task panicked
+	#[error("Internal error: {0}")]
+	Internal(String),
+}
+
+impl Error {
+	/// Create a new LLM error.
+	pub fn llm(err: impl Display) -> Self {
+		Error::Llm(err.to_string())
+	}
+
+	/// Create a new browser error.
+	pub fn browser(err: impl Display) -> Self {
+		Error::Browser(err.to_string())
+	}
+
+	/// Create a new internal error.
+	pub fn internal(err: impl Display) -> Self {
+		Error::Internal(err.to_string())
+	}
+
+	/// Returns the HTTP status code of the error.
+	pub fn status(&self) -> Status {
+		match self {
+			Error::Database(_) | Error::Internal(_) => Status::InternalServerError,
+			Error::Llm(_) | Error::Browser(_) => Status::BadGateway,
+			Error::NotFound(_) => Status::NotFound,
+			Error::BadRequest(_) => Status::BadRequest,
+			Error::Validation(_) => Status::UnprocessableEntity,
+			Error::Unauthorized(_) => Status::Unauthorized,
+			Error::Forbidden(_) => Status::Forbidden,
+		}
+	}
+
+	/// Returns the message that is shown to clients.
+	/// Database errors can contain queries and constraint names, so only a generic message is returned for them.
+	/// Internal errors aren't caused by the request, so their details are only logged.
+	pub fn message(&self) -> String {
+		match self {
+			Error::Database(_) => "Internal database error".to_string(),
+			Error::Internal(_) => "Internal server error".to_string(),
+			_ => self.to_string(),
+		}
+	}
+
+	/// Returns a short, machine-readable error kind.
+	pub fn kind(&self) -> &'static str {
+		match self {
+			Error::Database(_) => "database",
+			Error::Llm(_) => "llm",
+			Error::Browser(_) => "browser",
+			Error::NotFound(_) => "not_found",
+			Error::BadRequest(_) => "bad_request",
+			Error::Validation(_) => "validation",
+			Error::Unauthorized(_) => "unauthorized",
+			Error::Forbidden(_) => "forbidden",
+			Error::Internal(_) => "internal",
+		}
+	}
+}
+
+/// JSON body of error responses.
+#[derive(Debug, Serialize)]
+pub struct ErrorBody {
+	/// Error kind
+	pub error: String,
+
+	/// Human-readable error message
+	pub message: String,
+}
+
+impl<'r> Responder<'r, 'static> for Error {
+	fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+		let status = self.status();
+		if status.code >= 500 {
+			error!("{} {}: {}", req.method(), req.uri(), self);
+		}
+
+		let body = ErrorBody {
+			error: self.kind().to_string(),
+			message: self.message(),
+		};
+
+		Response::build_from(Json(body).respond_to(req)?).status(status).ok()
+	}
+}
+
+/// Return a JSON error body for errors that are raised by Rocket itself, e.g. malformed request bodies.
+#[catch(default)]
+pub fn default_catcher(status: Status, _req: &Request) -> (Status, Json<ErrorBody>) {
+	let body = ErrorBody {
+		error: status.reason_lossy().to_lowercase().replace(' ', "_"),
+		message: status.reason_lossy().to_string(),
+	};
+
+	(status, Json(body))
+}
@@ -1,24 +1,29 @@
 /// Source parsing and posting extraction.
-use crate::openai::embeddings::Embeddings;
-use crate::util::base_url;
-use crate::{
-	entities::{prelude::*, *},
-	openai::assistant::{Assistant, AssistantType},
-};
+use crate::ats::Ats;
+use crate::attributes::extract_attributes;
+use crate::browser::BrowserPool;
+use crate::entities::{prelude::*, *};
+use crate::error::Error;
+use crate::fetch::{FetchMode, HttpFetcher};
+use crate::lifecycle::{record_event, update_listed_postings, Listing, PostingEventKind};
+use crate::llm::{embedding_provider, get_similarity, llm_provider, Task};
+use crate::rules::{AccountFilterRules, FilterRules};
+use crate::scoring::Scorer;
+use crate::similarity::{nearest_other_source_postings, VectorBackend};
+use crate::util::{base_url, canonical_url};
 use anyhow::Result;
 
-use chrono;
 use chrono::FixedOffset;
 use chrono::Utc;
-use headless_chrome::{Browser, LaunchOptionsBuilder, Tab};
+use headless_chrome::Tab;
 use html2md::parse_html;
 use sea_orm::entity::prelude::*;
 use sea_orm::*;
 use similar::{ChangeTag, TextDiff};
 use std::sync::Arc;
-use std::time::Duration;
 
-use std::cmp::min;
+use std::collections::HashMap;
+use std::fmt;
 use url::Url;
 
 /// maximum number of characters per message sent to OpenAI API
@@ -30,6 +35,22 @@ const MAX_EXTRACT_CHARS: usize = 10000000;
 /// maximum number of characters to create embedding vector from
 const EMBEDDING_MAX_CHARS: usize = 8000;
 
+/// minimum similarity of the embeddings of postings of different sources to consider them duplicates
+const DUPLICATE_MIN_SIMILARITY: f32 = 0.95;
+
+/// maximum age in days of postings of other sources that new postings are compared with to find duplicates
+const DUPLICATE_MAX_AGE_DAYS: i64 = 60;
+
+/// maximum number of the most similar postings of other sources that are checked for duplicates if pgvector is installed
+const DUPLICATE_MAX_CANDIDATES: u64 = 10;
+
+/// Result of refreshing a single source.
+#[derive(Clone, Debug)]
+pub struct RefreshOutcome {
+	/// Job postings that were newly stored
+	pub postings: Vec<posting::Model>,
+}
+
 /// Represents a source that is being processed.
 #[derive(Clone, Default, Debug)]
 struct ParsedSource {
@@ -38,13 +59,15 @@ struct ParsedSource {
 	parsed_pages: Vec<ParsedPage>,
 }
 
-impl ParsedSource {
-	/// Return a string representation of the parsed page.
-	pub fn to_string(&self) -> String {
-		let contents: Vec<String> = self.parsed_pages.iter().map(|p| p.content.to_string()).collect();
-		contents.join("\n")
+impl fmt::Display for ParsedSource {
+	/// Write the text content of all parsed pages.
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let contents: Vec<&str> = self.parsed_pages.iter().map(|p| p.content.as_str()).collect();
+		write!(f, "{}", contents.join("\n"))
 	}
+}
 
+impl ParsedSource {
 	/// Return the parsed page based on the character index offset.
 	pub fn get_page_for_index(&self, index: Option<usize>) -> Option<&ParsedPage> {
 		// iterate through parsed pages until we get to the index offset based on the text content
@@ -59,7 +82,7 @@ impl ParsedSource {
 			}
 		}
 
-		return None;
+		None
 	}
 
 	/// Add a parsed page.
@@ -74,6 +97,7 @@ impl ParsedSource {
 		self.parsed_pages.push(ParsedPage {
 			content: content.clone(),
 			url: url.clone(),
+			links: vec![],
 		})
 	}
 
@@ -102,105 +126,43 @@ struct ParsedPage {
 
 	/// Page URL
 	url: String,
+
+	/// Absolute URLs of the links on the page
+	links: Vec<String>,
 }
 
-/// Handler for extracting postings from a specific source.
+/// Returns the absolute URLs of all links on the page opened in the tab.
+fn page_links(tab: &Arc<Tab>) -> Vec<String> {
+	tab.evaluate("Array.from(document.querySelectorAll('a[href]')).map(a => a.href).join('\\n')", false)
+		.ok()
+		.and_then(|result| result.value)
+		.and_then(|value| value.as_str().map(|links| links.lines().map(|l| l.to_string()).collect()))
+		.unwrap_or_default()
+}
+
+/// Parses pages of a source in a browser tab.
+///
+/// Browser calls block, so the parser is moved to a blocking thread instead of running on the async runtime.
 #[derive(Clone)]
-pub struct PostingsExtractor {
+struct PageParser {
 	/// Source URL
 	url: String,
 
-	/// Source ID
-	source_id: i32,
-
 	/// CSS selector to use to fetch job postings from specific page element
 	selector: Option<String>,
 
 	/// CSS selector pointing to pagination page element
 	pagination: Option<String>,
-
-	/// Configured filters that should be used to determine relevant job postings
-	filters: Vec<filter::Model>,
-
-	/// App settings
-	settings: settings::Model,
-
-	/// Headless browser handler
-	browser: Browser,
-
-	/// Processed page content
-	parsed_content: ParsedSource,
-
-	/// Previously cached content for source
-	cached_content: Option<String>,
-
-	/// Job postings that were extracted from source
-	extracted_postings: Option<Vec<posting::Model>>,
-
-	/// Whether the source URL could be opened
-	unreachable: bool,
 }
 
-impl PostingsExtractor {
-	/// Create and return a new posting handler instance.
-	pub fn new(url: String, source_id: i32, settings: settings::Model, selector: Option<String>, pagination: Option<String>, filters: Vec<filter::Model>, cached_content: Option<String>) -> Self {
-		// open a headles browser instance
-		let opt = LaunchOptionsBuilder::default().headless(true).idle_browser_timeout(Duration::from_millis(240_000)).build().unwrap();
-		let browser = Browser::new(opt).unwrap();
-
-		PostingsExtractor {
-			url,
-			source_id,
-			selector,
-			pagination,
-			cached_content,
-			filters,
-			settings,
-			parsed_content: ParsedSource::default(),
-			extracted_postings: None,
-			browser,
-			unreachable: false,
-		}
-	}
-
-	/// Start extracting job postings from the source.
-	pub async fn extract(&mut self, db: &DatabaseConnection) -> Result<Vec<posting::Model>> {
-		// parse the source content
-		self.parse_source_content().await?;
-		// use the previously cached content to determine content that has been added since last extraction
-		let content_diff = self.new_source_content();
-
-		if !content_diff.parsed_pages.is_empty() {
-			// extract job postings from the new page content
-			let postings = self.extract_postings(&content_diff, db).await?;
-			self.extracted_postings = Some(postings.clone());
-			return Ok(postings);
-		}
-
-		// close the headless browser
-		self.close_tabs()?;
-
-		return Ok(vec![]);
-	}
-
-	/// Parse the text content of the source and store.
-	async fn parse_source_content(&mut self) -> Result<()> {
-		let tab = self.browser.new_tab()?;
-
+impl PageParser {
+	/// Parse the text content of the source.
+	///
+	/// Returns the parsed content, or `None` if the source URL could not be opened.
+	fn parse_source_content(&self, tab: &Arc<Tab>) -> Result<Option<ParsedSource>> {
 		// open the source URL
-		match tab.navigate_to(&self.url) {
-			Err(_) => {
-				self.unreachable = true;
-				return Ok(());
-			}
-			_ => {}
-		}
-		match tab.wait_until_navigated() {
-			Err(_) => {
-				self.unreachable = true;
-				return Ok(());
-			}
-			_ => {}
+		if tab.navigate_to(&self.url).is_err() || tab.wait_until_navigated().is_err() {
+			return Ok(None);
 		}
 
 		let head = tab.wait_for_element("head")?.get_content()?;
@@ -210,37 +172,22 @@ impl PostingsExtractor {
 			let parsed_page = ParsedPage {
 				content: tab.wait_for_element("body")?.get_inner_text()?,
 				url: tab.get_url(),
+				links: vec![],
 			};
-			self.parsed_content = ParsedSource { parsed_pages: vec![parsed_page] };
+			Ok(Some(ParsedSource { parsed_pages: vec![parsed_page] }))
 		} else {
 			// HTML content, parse all relevant pages
-			let parsed_pages = self.parse_source_pages(tab, &ParsedPage::default())?;
-			self.parsed_content = ParsedSource { parsed_pages };
+			let parsed_pages = self.parse_source_pages(tab.clone(), &ParsedPage::default())?;
+			Ok(Some(ParsedSource { parsed_pages }))
 		}
-
-		return Ok(());
-	}
-
-	/// Close all browser tabs.
-	fn close_tabs(&self) -> Result<()> {
-		self.browser.get_tabs().lock().unwrap().iter().for_each(|t| {
-			let _ = t.close(true);
-		});
-		Ok(())
 	}
 
 	/// Parses and returns the pages related to the source.
 	fn parse_source_pages(&self, tab: Arc<Tab>, prev_content: &ParsedPage) -> Result<Vec<ParsedPage>> {
 		// select relevant part of the page to get postings from
 		let selector = match &self.selector {
-			Some(s) => {
-				if s.trim() != "" {
-					s
-				} else {
-					"body"
-				}
-			}
-			None => "body",
+			Some(s) if s.trim() != "" => s,
+			_ => "body",
 		};
 
 		match tab.wait_for_element(selector) {
@@ -250,9 +197,10 @@ impl PostingsExtractor {
 				let parsed_page = ParsedPage {
 					content: content.clone(),
 					url: tab.get_url(),
+					links: page_links(&tab),
 				};
 
-				if &content == &prev_content.content {
+				if content == prev_content.content {
 					// current page has already been parsed; stop recursion
 					return Ok(vec![]);
 				}
@@ -266,7 +214,7 @@ impl PostingsExtractor {
 								if let Some(base_url) = base_url(Url::parse(&tab.get_url())?) {
 									let a_attributes = pagination_element.attributes.unwrap_or(vec![]);
 									// get the link URL
-									let next_page_url_index = a_attributes.clone().into_iter().position(|r| r == "href".to_string());
+									let next_page_url_index = a_attributes.clone().into_iter().position(|r| r == "href");
 									if let Some(next_page_url_index) = next_page_url_index {
 										// open the next page and parse
 										let paginated_url = base_url.join(&a_attributes[next_page_url_index + 1])?;
@@ -303,87 +251,15 @@ impl PostingsExtractor {
 					}
 				}
 
-				return Ok(vec![parsed_page]);
+				Ok(vec![parsed_page])
 			}
 			Err(err) => Err(err),
 		}
 	}
 
-	/// Determine which content has been newly added compared to the cached source content.
-	fn new_source_content(&self) -> ParsedSource {
-		let cached_content = match &self.cached_content {
-			Some(c) => c,
-			None => "",
-		};
-
-		let parsed = self.parsed_content.to_string();
-		let content_diff = TextDiff::from_lines(cached_content, &parsed);
-		let mut new_content: ParsedSource = ParsedSource::default();
-
-		// compare cache with source content and keep added changes
-		for change in content_diff.iter_all_changes() {
-			match change.tag() {
-				ChangeTag::Insert => {
-					let c = change.as_str().unwrap().to_string();
-					if let Some(page) = self.parsed_content.get_page_for_index(change.new_index()) {
-						new_content.add_content(&c, &page.url)
-					} else {
-						new_content.add_content(&c, &self.url)
-					}
-				}
-				_ => {}
-			};
-		}
-
-		// limit the source content length to save cost and performance
-		return new_content.limit_content(MAX_EXTRACT_CHARS);
-	}
-
-	/// Extracts and returns job postings fetched from the source.
-	async fn extract_postings(&mut self, content: &ParsedSource, db: &DatabaseConnection) -> Result<Vec<posting::Model>> {
-		let mut postings: Vec<posting::Model> = vec![];
-
-		for page in &content.parsed_pages {
-			// limit the size of the page content for every message sent to the OpenAI assistant
-			let mut content_chunks = self.chunk_message(&page.content);
-
-			// use OpenAI assistant to extract job postings
-			let chatgpt_result = self.chatgpt_extract_postings(&mut content_chunks).await?;
-
-			for response in chatgpt_result {
-				let parsed_response: Vec<posting::Model> = serde_json::from_str(&response)?;
-				let posting_titles: Vec<&String> = parsed_response.iter().map(|p| &p.title).collect();
-
-				// filter postings that were seen recently
-				let existing_postings = Posting::find()
-					.filter(posting::Column::SourceId.eq(self.source_id))
-					.filter(posting::Column::Title.is_in(posting_titles))
-					.filter(posting::Column::CreatedAt.gte(chrono::offset::Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap()) - chrono::Duration::days(30)))
-					.all(db)
-					.await
-					.expect("Could not get existing postings")
-					.into_iter()
-					.collect::<Vec<_>>();
-
-				for mut posting in parsed_response {
-					if existing_postings.iter().find(|ep| ep.title == posting.title).is_none() {
-						if page.content.contains(&posting.title) {
-							// add additional posting information
-							self.add_posting_details(&mut posting, &page)?;
-							postings.push(posting);
-						}
-					}
-				}
-			}
-		}
-
-		Ok(postings)
-	}
-
 	/// Extract information from a page related to a specific job posting.
-	fn add_posting_details(&self, posting: &mut posting::Model, page: &ParsedPage) -> Result<()> {
+	fn add_posting_details(&self, tab: &Arc<Tab>, posting: &mut posting::Model, page: &ParsedPage) -> Result<()> {
 		// open the URL of the page the job posting was found on
-		let tab = self.browser.new_tab()?;
 		tab.navigate_to(&page.url)?;
 		tab.wait_until_navigated()?;
 
@@ -404,17 +280,17 @@ impl PostingsExtractor {
 					for el in elements_with_text {
 						if &tab.get_url() != tab_url {
 							// go back to the page job posting was extracted from
-							tab.navigate_to(&tab_url)?;
+							tab.navigate_to(tab_url)?;
 							tab.wait_until_navigated()?;
 						}
 
 						match el.tag_name.as_str() {
 							"A" => {
 								// element is a link
-								if let Some(base_url) = base_url(Url::parse(&tab_url)?) {
+								if let Some(base_url) = base_url(Url::parse(tab_url)?) {
 									// get the job posting specific URL
 									let a_attributes = el.attributes.unwrap_or(vec![]);
-									let next_page_url_index = a_attributes.clone().into_iter().position(|r| r == "href".to_string());
+									let next_page_url_index = a_attributes.clone().into_iter().position(|r| r == "href");
 									if let Some(next_page_url_index) = next_page_url_index {
 										// open the posting URL
 										let paginated_url = base_url.join(&a_attributes[next_page_url_index + 1])?;
@@ -453,18 +329,338 @@ impl PostingsExtractor {
 							return Ok(());
 						}
 					}
-					return Ok(());
-				}
-				_ => {
-					return Ok(());
+					Ok(())
 				}
+				_ => Ok(()),
 			},
-			_ => return Ok(()),
+			_ => Ok(()),
+		}
+	}
+}
+
+/// Handler for extracting postings from a specific source.
+#[derive(Clone)]
+pub struct PostingsExtractor {
+	/// Source URL
+	url: String,
+
+	/// Source ID
+	source_id: i32,
+
+	/// CSS selector to use to fetch job postings from specific page element
+	selector: Option<String>,
+
+	/// CSS selector pointing to pagination page element
+	pagination: Option<String>,
+
+	/// Rules of the filters of every account that determine relevant job postings
+	filters: AccountFilterRules,
+
+	/// Settings postings are extracted with
+	settings: settings::Model,
+
+	/// Pool of headless browsers pages are opened in
+	browsers: BrowserPool,
+
+	/// Processed page content
+	parsed_content: ParsedSource,
+
+	/// Previously cached content for source
+	cached_content: Option<String>,
+
+	/// Job postings that were extracted from source
+	extracted_postings: Option<Vec<posting::Model>>,
+
+	/// Whether the source URL could be opened
+	unreachable: bool,
+
+	/// Fetcher for loading pages via HTTP; only set if the source uses the HTTP fetch mode
+	http: Option<HttpFetcher>,
+
+	/// Whether the source is an RSS or Atom feed
+	is_feed: bool,
+
+	/// Postings currently listed on the source; only set if the source could be parsed
+	listing: Option<Listing>,
+}
+
+impl PostingsExtractor {
+	/// Create and return a new posting handler instance for the source.
+	/// Pages are opened in tabs of the browser pool.
+	pub fn new(source: &source::Model, settings: settings::Model, filters: AccountFilterRules, browsers: BrowserPool) -> Result<Self, Error> {
+		let http = match FetchMode::from_source(&source.fetch_mode) {
+			FetchMode::Http => Some(HttpFetcher::new().map_err(Error::browser)?),
+			FetchMode::Browser => None,
+		};
+
+		Ok(PostingsExtractor {
+			url: source.url.clone(),
+			source_id: source.id,
+			selector: source.selector.clone(),
+			pagination: source.pagination.clone(),
+			cached_content: source.content.clone(),
+			filters,
+			settings,
+			parsed_content: ParsedSource::default(),
+			extracted_postings: None,
+			browsers,
+			unreachable: false,
+			http,
+			is_feed: source.kind.as_deref() == Some("feed"),
+			listing: None,
+		})
+	}
+
+	/// Start extracting job postings from the source.
+	pub async fn extract(&mut self, db: &DatabaseConnection) -> Result<Vec<posting::Model>, Error> {
+		if self.is_feed {
+			// feed items don't need to be extracted from the page content
+			let postings = self.extract_feed_postings(db).await?;
+			self.extracted_postings = Some(postings.clone());
+			return Ok(postings);
+		}
+
+		// parse the source content
+		// the browser is only used if the source can't be fetched via HTTP
+		if !self.fetch_source_content().await {
+			let tab = self.browsers.new_tab().await?;
+			let parser = self.page_parser();
+			// the tab is closed before extracting postings, so it doesn't count towards the tab limit
+			let parsed_content = tokio::task::spawn_blocking(move || parser.parse_source_content(tab.tab()))
+				.await
+				.map_err(Error::browser)?
+				.map_err(Error::browser)?;
+
+			match parsed_content {
+				Some(parsed_content) => self.parsed_content = parsed_content,
+				None => self.unreachable = true,
+			}
+		}
+
+		let content = self.parsed_content.to_string();
+		if !self.unreachable && !content.trim().is_empty() {
+			let links = self.parsed_content.parsed_pages.iter().flat_map(|p| p.links.iter());
+			self.listing = Some(Listing::content(&content, links));
+		}
+
+		// use the previously cached content to determine content that has been added since last extraction
+		let content_diff = self.new_source_content();
+
+		if !content_diff.parsed_pages.is_empty() {
+			// extract job postings from the new page content
+			let postings = self.extract_postings(&content_diff, db).await?;
+			self.extracted_postings = Some(postings.clone());
+			return Ok(postings);
+		}
+
+		Ok(vec![])
+	}
+
+	/// Extracts and returns job postings from the items of the source feed.
+	/// Items that have been stored before are identified by their GUID; new items are checked for relevance using the LLM.
+	async fn extract_feed_postings(&mut self, db: &DatabaseConnection) -> Result<Vec<posting::Model>, Error> {
+		let http = HttpFetcher::new().map_err(Error::browser)?;
+		let items = match http.fetch_feed(&self.url).await {
+			Ok(items) => items,
+			Err(e) => {
+				warn!("Could not fetch feed of source {}: {e}", self.source_id);
+				self.unreachable = true;
+				return Ok(vec![]);
+			}
+		};
+
+		// feeds only contain the most recent items, so items that are no longer in the feed aren't closed
+		self.listing = Some(Listing::from_postings(&items, false));
+
+		// skip items that have been stored before
+		let guids: Vec<&String> = items.iter().filter_map(|i| i.external_id.as_ref()).collect();
+		let existing_guids: Vec<Option<String>> = Posting::find()
+			.select_only()
+			.column(posting::Column::ExternalId)
+			.filter(posting::Column::SourceId.eq(self.source_id))
+			.filter(posting::Column::ExternalId.is_in(guids))
+			.into_tuple()
+			.all(db)
+			.await?;
+
+		let mut new_items: Vec<posting::Model> = vec![];
+		for item in items {
+			if !existing_guids.contains(&item.external_id) && !new_items.iter().any(|i| i.external_id == item.external_id) {
+				new_items.push(item);
+			}
+		}
+
+		// skip items that don't match the filter rules that can be evaluated without the LLM
+		new_items.retain(|i| !self.filters.excludes(i));
+
+		if new_items.is_empty() {
+			return Ok(vec![]);
+		}
+
+		if !self.filters.has_criteria() {
+			// there are no criteria the LLM needs to check
+			return Ok(new_items);
+		}
+
+		// use the LLM to determine which items are related to the criteria of each account
+		let titles = new_items.iter().map(|i| i.title.clone()).collect::<Vec<String>>().join("\n");
+		let content_chunks = self.chunk_message(&titles);
+		self.chatgpt_extract_postings(&content_chunks).await.map_err(Error::llm)?;
+
+		// items the LLM didn't return don't match the criteria of any account
+		for item in &new_items {
+			self.filters.record_criteria(&item.title, &[]);
+		}
+
+		new_items.retain(|i| !self.filters.excludes(i));
+		Ok(new_items)
+	}
+
+	/// Returns a parser for opening the source pages in a browser tab.
+	fn page_parser(&self) -> PageParser {
+		PageParser {
+			url: self.url.clone(),
+			selector: self.selector.clone(),
+			pagination: self.pagination.clone(),
+		}
+	}
+
+	/// Returns the postings currently listed on the source, or `None` if the source couldn't be parsed.
+	pub fn listing(&self) -> Option<&Listing> {
+		self.listing.as_ref()
+	}
+
+	/// Fetch the text content of the source via HTTP and store, if the source uses the HTTP fetch mode.
+	///
+	/// Returns whether the content could be fetched without a browser.
+	async fn fetch_source_content(&mut self) -> bool {
+		let http = match &self.http {
+			Some(http) => http,
+			None => return false,
+		};
+
+		match http.fetch_pages(&self.url, self.selector.as_deref(), self.pagination.as_deref()).await {
+			Ok(Some(pages)) => {
+				let parsed_pages = pages
+					.into_iter()
+					.map(|p| ParsedPage {
+						content: p.content,
+						url: p.url,
+						links: p.links,
+					})
+					.collect();
+				self.parsed_content = ParsedSource { parsed_pages };
+				true
+			}
+			Ok(None) => {
+				info!("Source {} requires a browser; falling back to browser", self.source_id);
+				false
+			}
+			Err(e) => {
+				warn!("Could not fetch source {} via HTTP; falling back to browser: {e}", self.source_id);
+				false
+			}
+		}
+	}
+
+	/// Determine which content has been newly added compared to the cached source content.
+	fn new_source_content(&self) -> ParsedSource {
+		let cached_content = match &self.cached_content {
+			Some(c) => c,
+			None => "",
+		};
+
+		let parsed = self.parsed_content.to_string();
+		let content_diff = TextDiff::from_lines(cached_content, &parsed);
+		let mut new_content: ParsedSource = ParsedSource::default();
+
+		// compare cache with source content and keep added changes
+		for change in content_diff.iter_all_changes() {
+			if change.tag() == ChangeTag::Insert {
+				let c = change.as_str().unwrap().to_string();
+				if let Some(page) = self.parsed_content.get_page_for_index(change.new_index()) {
+					new_content.add_content(&c, &page.url)
+				} else {
+					new_content.add_content(&c, &self.url)
+				}
+			}
+		}
+
+		// limit the source content length to save cost and performance
+		new_content.limit_content(MAX_EXTRACT_CHARS)
+	}
+
+	/// Extracts and returns job postings fetched from the source.
+	async fn extract_postings(&mut self, content: &ParsedSource, db: &DatabaseConnection) -> Result<Vec<posting::Model>, Error> {
+		let mut postings: Vec<posting::Model> = vec![];
+
+		for page in &content.parsed_pages {
+			// limit the size of the page content for every request sent to the LLM
+			let content_chunks = self.chunk_message(&page.content);
+
+			// use the LLM to extract job postings
+			let parsed_response = self.chatgpt_extract_postings(&content_chunks).await.map_err(Error::llm)?;
+			let posting_titles: Vec<&String> = parsed_response.iter().map(|p| &p.title).collect();
+
+			// filter postings that were seen recently
+			let existing_postings = Posting::find()
+				.filter(posting::Column::SourceId.eq(self.source_id))
+				.filter(posting::Column::Title.is_in(posting_titles))
+				.filter(posting::Column::CreatedAt.gte(chrono::offset::Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap()) - chrono::Duration::days(30)))
+				.all(db)
+				.await?;
+
+			for mut posting in parsed_response {
+				// skip postings whose title doesn't match the filter rules before fetching their details
+				if self.filters.excludes(&posting) {
+					continue;
+				}
+
+				if !existing_postings.iter().any(|ep| ep.title == posting.title) && page.content.contains(&posting.title) && !postings.iter().any(|p| p.title == posting.title) {
+					// add additional posting information
+					// the browser is only used if the details can't be fetched via HTTP
+					if !self.fetch_posting_details(&mut posting, page).await {
+						let tab = self.browsers.new_tab().await?;
+						let parser = self.page_parser();
+						let page = page.clone();
+						posting = tokio::task::spawn_blocking(move || parser.add_posting_details(tab.tab(), &mut posting, &page).map(|_| posting))
+							.await
+							.map_err(Error::browser)?
+							.map_err(Error::browser)?;
+					}
+					postings.push(posting);
+				}
+			}
+		}
+
+		Ok(postings)
+	}
+
+	/// Fetch information related to a specific job posting via HTTP, if the source uses the HTTP fetch mode.
+	///
+	/// Returns whether the information could be fetched without a browser.
+	async fn fetch_posting_details(&self, posting: &mut posting::Model, page: &ParsedPage) -> bool {
+		let http = match &self.http {
+			Some(http) => http,
+			None => return false,
+		};
+
+		match http.fetch_posting_details(&page.url, self.selector.as_deref(), &posting.title).await {
+			Ok(Some((url, content))) => {
+				posting.url = Some(url);
+				posting.content = Some(content);
+				true
+			}
+			Ok(None) => false,
+			Err(e) => {
+				warn!("Could not fetch details of posting {} via HTTP; falling back to browser: {e}", posting.title);
+				false
+			}
 		}
 	}
 
 	/// Split the input message into smaller messages with a maximum size.
-	fn chunk_message(&self, message: &String) -> Vec<String> {
+	fn chunk_message(&self, message: &str) -> Vec<String> {
 		message
 			.chars()
 			.collect::<Vec<char>>()
@@ -473,109 +669,452 @@ impl PostingsExtractor {
 			.collect::<Vec<String>>()
 	}
 
-	/// Use OpenAI assistant to extract job postings from the source content.
-	async fn chatgpt_extract_postings(&mut self, message_parts: &mut Vec<String>) -> Result<Vec<String>> {
-		// create a new assistant
-		let mut assistant = Assistant::new(
-			&self.settings.api_key.clone().unwrap_or("".to_string()),
-			&self.settings.model.clone().unwrap_or("".to_string()),
-			AssistantType::JobsFeed,
-		)
-		.await?;
+	/// Use the configured LLM provider to extract job postings from the source content.
+	/// Each content chunk is sent as a separate request. The criteria of the accounts that each posting matches are
+	/// recorded in the filters.
+	async fn chatgpt_extract_postings(&mut self, content_chunks: &[String]) -> Result<Vec<posting::Model>> {
+		// create a new LLM provider instance
+		let llm = llm_provider(&self.settings, Task::JobsFeed);
 
-		// get filters
-		let criteria = self
-			.filters
-			.iter()
-			.fold("".to_string(), |cur: String, next: &filter::Model| cur + &format!("{}: {}", next.name, next.value));
+		// get the numbered criteria of the accounts' filters; all other filter rules are evaluated without the LLM
+		let criteria = self.filters.criteria();
 
-		// create the prompt
-		let last_message = format!(
-			"Criteria: {criteria} Provide a single response. \
-            Response format: [{{\"title\": \"\"}}]. \
-			Extract a complete list of job posting titles from the provided inputs that are related to the provided criteria. \
-            Only return complete and valid JSON."
-		);
-		message_parts.push(last_message);
+		let mut postings: Vec<posting::Model> = vec![];
 
-		let response = assistant.run(message_parts).await?;
+		for chunk in content_chunks {
+			// create the prompt
+			let message = format!(
+				"Criteria:\n{criteria}\n\
+				Extract a complete list of job posting titles from the following input. For each posting, list the numbers of the criteria it is related to.\n\n\
+				{chunk}"
+			);
+
+			for item in llm.run(&[message]).await? {
+				let numbers: Vec<usize> = item.get("criteria").and_then(|c| serde_json::from_value(c.clone()).ok()).unwrap_or_default();
+				let posting: posting::Model = serde_json::from_value(item)?;
+				self.filters.record_criteria(&posting.title, &numbers);
+				postings.push(posting);
+			}
+		}
 
-		Ok(response)
+		Ok(postings)
 	}
 
 	/// Saves extracted job postings to the database.
-	pub async fn save(&self, db: &DatabaseConnection) -> Result<()> {
-		let settings = Settings::find().one(db).await?.expect("No settings stored");
+	///
+	/// Returns the postings that were inserted.
+	pub async fn save(&self, db: &DatabaseConnection, backend: VectorBackend) -> Result<Vec<posting::Model>, Error> {
+		// feed items are taken as is, without the LLM
+		let postings = save_postings(
+			db,
+			backend,
+			self.source_id,
+			self.extracted_postings.clone().unwrap_or(vec![]),
+			&self.settings,
+			&self.filters,
+			!self.is_feed,
+		)
+		.await?;
 
-		// get a set of postings that were previously "liked"
-		let liked_postings: Vec<Vec<f32>> = Embedding::find()
-			.select_only()
-			.column(embedding::Column::Vector)
-			.filter(posting::Column::IsMatch.eq(true))
-			.join(JoinType::InnerJoin, embedding::Relation::Posting.def())
-			.order_by_desc(posting::Column::CreatedAt)
-			.limit(50)
-			.into_tuple()
-			.all(db)
+		// update the source
+		Source::update_many()
+			.col_expr(source::Column::Content, Expr::value(self.parsed_content.to_string()))
+			.col_expr(source::Column::Unreachable, Expr::value(self.unreachable))
+			.filter(source::Column::Id.eq(self.source_id))
+			.exec(db)
 			.await?;
 
-		// get a set of postings that were previously "disliked"
-		let disliked_postings: Vec<Vec<f32>> = Embedding::find()
-			.select_only()
-			.column(embedding::Column::Vector)
-			.filter(posting::Column::IsMatch.eq(false))
-			.join(JoinType::InnerJoin, embedding::Relation::Posting.def())
-			.order_by_desc(posting::Column::CreatedAt)
-			.limit(50)
-			.into_tuple()
-			.all(db)
-			.await?;
+		Ok(postings)
+	}
+}
+
+/// Saves job postings of a source to the database.
+/// For every posting an embedding is created to compute how similar it is to the postings each account previously
+/// liked and disliked.
+///
+/// Postings are stored once for all accounts, but are only shown to the accounts whose filter rules they match.
+/// Postings that don't match the filter rules of any account, or that have already been stored for the source, are skipped. Postings that have already been stored for another
+/// source, identified by their URL or by a nearly identical embedding, are linked to the existing posting as duplicates
+/// instead of being inserted.
+///
+/// Structured attributes are only extracted by the LLM if `llm_attributes` is set; postings of feeds and applicant
+/// tracking systems are saved with the attributes they were provided with.
+///
+/// Returns the postings that were inserted.
+pub async fn save_postings(
+	db: &DatabaseConnection,
+	backend: VectorBackend,
+	source_id: i32,
+	postings: Vec<posting::Model>,
+	settings: &settings::Model,
+	filters: &AccountFilterRules,
+	llm_attributes: bool,
+) -> Result<Vec<posting::Model>, Error> {
+	let now = chrono::offset::Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+
+	// scores postings based on the postings each account previously "liked" and "disliked"
+	let mut scorers = HashMap::new();
+	for account_id in filters.account_ids() {
+		scorers.insert(account_id, Scorer::load(db, backend, account_id).await?);
+	}
+
+	// postings of other sources created before are not considered as duplicates
+	let duplicates_since = now - chrono::Duration::days(DUPLICATE_MAX_AGE_DAYS);
+
+	// without pgvector, recent postings of other sources that new postings could be duplicates of are compared in memory
+	let duplicate_candidates: Vec<(i32, String, Vec<f32>)> = match backend {
+		VectorBackend::InMemory => {
+			Embedding::find()
+				.select_only()
+				.column(posting::Column::Id)
+				.column(posting::Column::Title)
+				.column(embedding::Column::Vector)
+				.join(JoinType::InnerJoin, embedding::Relation::Posting.def())
+				.join(JoinType::InnerJoin, posting::Relation::Source.def())
+				.filter(posting::Column::SourceId.ne(source_id))
+				.filter(source::Column::Deleted.eq(false))
+				.filter(posting::Column::CreatedAt.gte(duplicates_since))
+				.into_tuple()
+				.all(db)
+				.await?
+		}
+		_ => vec![],
+	};
+
+	// create new embeddings handler
+	let embedding = embedding_provider(settings);
+
+	let mut saved_postings = vec![];
 
-		// create new embeddings handler
-		let embedding = Embeddings::new(&settings.api_key.unwrap());
-
-		// for each newly extracted posting compute the similarity scores to determine if they would be a good match
-		for posting in self.extracted_postings.clone().unwrap_or(vec![]) {
-			// use the posting content and title as input for getting the embedding vector
-			let content = posting.content.clone();
-			let title = posting.title.clone();
-			let mut active_posting: posting::ActiveModel = posting.into();
-			active_posting.id = NotSet;
-			active_posting.created_at = Set(Some(chrono::offset::Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap())));
-			active_posting.seen = Set(Some(false));
-			active_posting.source_id = Set(Some(self.source_id));
-			let embedding_content = content.unwrap_or(title.to_string());
-
-			// limit the content that is used to create the embedding
-			let end_index = embedding_content.char_indices().map(|(i, _)| i).nth(min(EMBEDDING_MAX_CHARS, embedding_content.len() - 1)).unwrap_or(0);
-			let embedding_vector = embedding.create(&&embedding_content[..end_index].to_string()).await?;
-
-			// compute similarity to "like"d and "dislike"d postings to compute a similarity score
-			let like_similarity = embedding.get_similarity(&embedding_vector, &liked_postings);
-			let dislike_similarity = embedding.get_similarity(&embedding_vector, &disliked_postings);
-			active_posting.match_similarity = Set(Some(like_similarity - dislike_similarity));
-
-			// store posting
-			let inserted_posting = active_posting.insert(db).await?;
-
-			// store embedding
-			let active_embedding = embedding::ActiveModel {
-				id: NotSet,
-				posting_id: Set(Some(inserted_posting.id)),
-				vector: Set(Some(embedding_vector)),
+	// for each newly extracted posting compute the similarity scores to determine if they would be a good match
+	for mut posting in postings {
+		posting.canonical_url = posting.url.as_deref().and_then(canonical_url);
+
+		if is_known_posting(db, source_id, &posting).await? {
+			continue;
+		}
+
+		// the same posting URL might have been stored for another source
+		if let Some(original) = find_url_duplicate(db, source_id, &posting).await? {
+			save_duplicate(db, source_id, &posting, original.id, None).await?;
+			continue;
+		}
+
+		// skip postings that don't match the filter rules
+		if filters.excludes(&posting) {
+			continue;
+		}
+
+		// extract structured attributes, such as the location and salary, from the posting details
+		if llm_attributes {
+			match extract_attributes(settings, &posting).await {
+				Ok(Some(attributes)) => attributes.apply(&mut posting),
+				Ok(None) => {}
+				Err(e) => warn!("Could not extract attributes of posting {}: {e}", posting.title),
+			}
+
+			// rules on attributes such as the salary can only be evaluated once the attributes are known
+			if filters.excludes(&posting) {
+				continue;
+			}
+		}
+
+		// use the posting content and title as input for getting the embedding vector
+		// postings of feeds and applicant tracking systems can come without content
+		let title = posting.title.clone();
+		let embedding_content = posting.content.clone().filter(|content| !content.trim().is_empty()).unwrap_or(title.to_string());
+
+		// limit the content that is used to create the embedding
+		let end_index = embedding_content.char_indices().nth(EMBEDDING_MAX_CHARS).map(|(i, _)| i).unwrap_or(embedding_content.len());
+		let embedding_vector = embedding.create(&embedding_content[..end_index]).await.map_err(Error::llm)?;
+
+		let similar_postings = match backend {
+			VectorBackend::InMemory => duplicate_candidates
+				.iter()
+				.map(|(id, candidate_title, vector)| (*id, candidate_title.clone(), get_similarity(&embedding_vector, std::slice::from_ref(vector))))
+				.collect(),
+			_ => nearest_other_source_postings(db, backend, source_id, &embedding_vector, duplicates_since, DUPLICATE_MAX_CANDIDATES).await?,
+		};
+
+		if let Some((original_id, similarity)) = find_similar_duplicate(&title, &similar_postings) {
+			save_duplicate(db, source_id, &posting, original_id, Some(similarity)).await?;
+			continue;
+		}
+
+		let account_ids = filters.matching_accounts(&posting);
+
+		let mut active_posting: posting::ActiveModel = posting.into();
+		active_posting.id = NotSet;
+		active_posting.created_at = Set(Some(now));
+		active_posting.source_id = Set(Some(source_id));
+		active_posting.last_seen_at = Set(Some(now));
+
+		// store posting
+		let inserted_posting = active_posting.insert(db).await?;
+
+		// show the posting as unread to every account whose filters it matches
+		for account_id in account_ids {
+			// compute how well the posting matches based on "like"d and "dislike"d postings of the account
+			let match_similarity = match scorers.get(&account_id) {
+				Some(scorer) => Some(scorer.score(db, &embedding_vector).await?),
+				None => None,
 			};
 
-			active_embedding.insert(db).await?;
+			let state = posting_state::ActiveModel {
+				account_id: Set(account_id),
+				posting_id: Set(inserted_posting.id),
+				seen: Set(false),
+				bookmarked: Set(false),
+				is_match: Set(None),
+				match_similarity: Set(match_similarity),
+			};
+			state.insert(db).await?;
 		}
 
-		// update the source
-		let _ = Source::update_many()
-			.col_expr(source::Column::Content, Expr::value(self.parsed_content.to_string().clone()))
-			.col_expr(source::Column::Unreachable, Expr::value(self.unreachable.clone()))
-			.filter(source::Column::Id.eq(self.source_id))
+		// store embedding
+		let active_embedding = embedding::ActiveModel {
+			id: NotSet,
+			posting_id: Set(Some(inserted_posting.id)),
+			vector: Set(Some(embedding_vector)),
+		};
+
+		active_embedding.insert(db).await?;
+		record_event(db, inserted_posting.id, PostingEventKind::Opened).await?;
+		saved_postings.push(inserted_posting);
+	}
+
+	Ok(saved_postings)
+}
+
+/// Returns whether the posting has already been stored for the source, either as posting or as duplicate.
+/// Postings are identified by their canonical URL or their external ID.
+async fn is_known_posting(db: &DatabaseConnection, source_id: i32, posting: &posting::Model) -> Result<bool, Error> {
+	if posting.canonical_url.is_none() && posting.external_id.is_none() {
+		return Ok(false);
+	}
+
+	let mut posting_condition = Condition::any();
+	let mut duplicate_condition = Condition::any();
+
+	if let Some(url) = &posting.canonical_url {
+		// postings stored before URLs were normalized don't have a canonical URL
+		posting_condition = posting_condition.add(posting::Column::CanonicalUrl.eq(url)).add(posting::Column::Url.eq(posting.url.clone()));
+		duplicate_condition = duplicate_condition.add(posting_duplicate::Column::CanonicalUrl.eq(url));
+	}
+
+	if let Some(external_id) = &posting.external_id {
+		posting_condition = posting_condition.add(posting::Column::ExternalId.eq(external_id));
+		duplicate_condition = duplicate_condition.add(posting_duplicate::Column::ExternalId.eq(external_id));
+	}
+
+	let postings = Posting::find().filter(posting::Column::SourceId.eq(source_id)).filter(posting_condition).count(db).await?;
+	let duplicates = PostingDuplicate::find()
+		.filter(posting_duplicate::Column::SourceId.eq(source_id))
+		.filter(duplicate_condition)
+		.count(db)
+		.await?;
+
+	Ok(postings + duplicates > 0)
+}
+
+/// Find the posting of another active source that has the same canonical URL as the posting.
+async fn find_url_duplicate(db: &DatabaseConnection, source_id: i32, posting: &posting::Model) -> Result<Option<posting::Model>, Error> {
+	let url = match &posting.canonical_url {
+		Some(url) => url,
+		None => return Ok(None),
+	};
+
+	Ok(Posting::find()
+		.inner_join(Source)
+		.filter(posting::Column::CanonicalUrl.eq(url))
+		.filter(posting::Column::SourceId.ne(source_id))
+		.filter(source::Column::Deleted.eq(false))
+		.order_by_asc(posting::Column::Id)
+		.one(db)
+		.await?)
+}
+
+/// Set the canonical URL of postings that were stored before URLs were normalized, so that new postings are identified
+/// as duplicates of them. Postings whose canonical URL is already used by another posting of the same source keep
+/// their URL only.
+///
+/// Returns the number of updated postings.
+pub async fn backfill_canonical_urls(db: &DatabaseConnection) -> Result<u64, Error> {
+	let postings: Vec<(i32, String)> = Posting::find()
+		.select_only()
+		.column(posting::Column::Id)
+		.column(posting::Column::Url)
+		.filter(posting::Column::CanonicalUrl.is_null())
+		.filter(posting::Column::Url.is_not_null())
+		.order_by_asc(posting::Column::Id)
+		.into_tuple()
+		.all(db)
+		.await?;
+
+	let mut updated = 0;
+	for (id, url) in postings {
+		let canonical = match canonical_url(&url) {
+			Some(canonical) => canonical,
+			None => continue,
+		};
+
+		let result = Posting::update_many()
+			.col_expr(posting::Column::CanonicalUrl, Expr::value(canonical))
+			.filter(posting::Column::Id.eq(id))
 			.exec(db)
 			.await;
+		match result {
+			Ok(_) => updated += 1,
+			// an older posting of the source has the same canonical URL
+			Err(e) if matches!(e.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => continue,
+			Err(e) => return Err(e.into()),
+		}
+	}
+
+	Ok(updated)
+}
+
+/// Find the posting of another source that the posting with `title` and `embedding_vector` is a duplicate of.
+/// Postings are considered duplicates if their embeddings are nearly identical and one title contains the other.
+///
+/// `candidates` are the IDs, titles and embedding similarities of postings of other sources.
+///
+/// Returns the ID of the duplicated posting and the similarity of both embeddings.
+fn find_similar_duplicate(title: &str, candidates: &[(i32, String, f32)]) -> Option<(i32, f32)> {
+	let title = title.trim().to_lowercase();
+
+	candidates
+		.iter()
+		.filter(|(_, candidate_title, _)| {
+			let candidate_title = candidate_title.trim().to_lowercase();
+			candidate_title.contains(&title) || title.contains(&candidate_title)
+		})
+		.map(|(id, _, similarity)| (*id, *similarity))
+		.filter(|(_, similarity)| *similarity >= DUPLICATE_MIN_SIMILARITY)
+		.max_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+/// Link the posting found on the source to the posting `original_id` of another source.
+async fn save_duplicate(db: &DatabaseConnection, source_id: i32, posting: &posting::Model, original_id: i32, similarity: Option<f32>) -> Result<(), Error> {
+	let duplicate = posting_duplicate::ActiveModel {
+		id: NotSet,
+		posting_id: Set(original_id),
+		source_id: Set(source_id),
+		title: Set(posting.title.clone()),
+		url: Set(posting.url.clone()),
+		canonical_url: Set(posting.canonical_url.clone()),
+		external_id: Set(posting.external_id.clone()),
+		similarity: Set(similarity),
+		created_at: Set(Some(chrono::offset::Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap()))),
+	};
+
+	duplicate.insert(db).await?;
+
+	Ok(())
+}
+
+/// Returns the rules of the filters of every account that apply to the source: filters without a profile and the
+/// filters of the profiles the account attached to the source.
+async fn source_filters(db: &DatabaseConnection, source: &source::Model) -> Result<AccountFilterRules, Error> {
+	let accounts = Account::find().order_by_asc(account::Column::Id).all(db).await?;
+	let filters = Filter::find().order_by_asc(filter::Column::Id).all(db).await?;
+	let profiles = source.find_related(FilterProfile).order_by_asc(filter_profile::Column::Id).all(db).await?;
+
+	let account_rules = accounts
+		.into_iter()
+		.map(|account| {
+			let account_filters: Vec<filter::Model> = filters.iter().filter(|f| f.account_id == account.id).cloned().collect();
+			let account_profiles: Vec<filter_profile::Model> = profiles.iter().filter(|p| p.account_id == account.id).cloned().collect();
+			let rules = FilterRules::from_filters(&account_filters, &account_profiles);
+
+			(account.id, rules)
+		})
+		.collect();
 
-		Ok(())
+	Ok(AccountFilterRules::new(account_rules))
+}
+
+/// Returns the settings postings of the source are extracted with, which are the settings of the account that added
+/// the source. Other accounts' settings, and API keys, are never used for the source.
+pub async fn source_settings(db: &DatabaseConnection, source: &source::Model) -> Result<settings::Model, Error> {
+	let account_id = source.account_id.ok_or(Error::BadRequest(format!(
+		"Source {} has no owner whose settings could be used; update the source to take it over",
+		source.id
+	)))?;
+
+	Settings::find()
+		.filter(settings::Column::AccountId.eq(account_id))
+		.one(db)
+		.await?
+		.ok_or(Error::BadRequest(format!("The user that added source {} has no settings stored", source.id)))
+}
+
+/// Refresh a single source by extracting new job postings and saving them.
+/// Pages are opened in tabs of the browser pool.
+///
+/// Returns the job postings that were newly stored.
+pub async fn refresh_source(db: &DatabaseConnection, source: &source::Model, browsers: &BrowserPool, backend: VectorBackend) -> Result<RefreshOutcome, Error> {
+	// record the refresh attempt, so failing sources don't get retried right away
+	Source::update_many()
+		.col_expr(source::Column::RefreshedAt, Expr::value(chrono::offset::Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap())))
+		.filter(source::Column::Id.eq(source.id))
+		.exec(db)
+		.await?;
+
+	// sources hosted on applicant tracking systems provide postings via their API
+	if let Some(ats) = Ats::from_kind(&source.kind) {
+		return refresh_ats_source(db, source, ats, backend).await;
+	}
+
+	let filters = source_filters(db, source).await?;
+	let settings = source_settings(db, source).await?;
+
+	// start extraction process
+	let mut extractor = PostingsExtractor::new(source, settings, filters, browsers.clone())?;
+	extractor.extract(db).await?;
+
+	// save extracted postings to database
+	let postings = extractor.save(db, backend).await?;
+
+	// close postings that are no longer listed on the source
+	if let Some(listing) = extractor.listing() {
+		update_listed_postings(db, source.id, listing).await?;
 	}
+
+	Ok(RefreshOutcome { postings })
+}
+
+/// Refresh a source hosted on an applicant tracking system by fetching its postings from the job board API.
+/// Postings are taken as is, without extracting them via the LLM; only filter rules that don't need the LLM are applied.
+///
+/// Returns the job postings that were newly added to the job board.
+async fn refresh_ats_source(db: &DatabaseConnection, source: &source::Model, ats: Ats, backend: VectorBackend) -> Result<RefreshOutcome, Error> {
+	let (fetched_postings, unreachable) = match ats.fetch_postings(&source.url).await {
+		Ok(postings) => (postings, false),
+		Err(e) => {
+			warn!("Could not fetch postings of source {} from {}: {e}", source.id, ats.kind());
+			(vec![], true)
+		}
+	};
+
+	// close postings that are no longer listed on the job board
+	// job boards that can't be reached don't list any postings
+	let listing = Listing::from_postings(&fetched_postings, true);
+
+	// job board postings have stable URLs, so postings that have been stored before are identified by their URL
+	let filters = source_filters(db, source).await?;
+	let settings = source_settings(db, source).await?;
+	let postings = save_postings(db, backend, source.id, fetched_postings, &settings, &filters, false).await?;
+
+	if !unreachable {
+		update_listed_postings(db, source.id, &listing).await?;
+	}
+
+	Source::update_many()
+		.col_expr(source::Column::Unreachable, Expr::value(unreachable))
+		.filter(source::Column::Id.eq(source.id))
+		.exec(db)
+		.await?;
+
+	Ok(RefreshOutcome { postings })
 }
@@ -0,0 +1,308 @@
+/// Lightweight fetching of source pages via plain HTTP requests, without a headless browser.
+use crate::entities::posting;
+use crate::util::base_url;
+use anyhow::anyhow;
+use anyhow::Result;
+
+use html2md::parse_html;
+use reqwest::header::CONTENT_TYPE;
+use scraper::{ElementRef, Html, Node, Selector};
+use std::collections::HashSet;
+use std::time::Duration;
+use url::Url;
+
+/// maximum number of paginated pages that are fetched per source
+const MAX_PAGES: usize = 50;
+
+/// HTML elements whose text content isn't visible on the page
+const HIDDEN_ELEMENTS: [&str; 4] = ["script", "style", "noscript", "template"];
+
+/// How source pages are loaded.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FetchMode {
+	/// Pages are rendered in a headless browser
+	Browser,
+
+	/// Pages are fetched via HTTP requests and parsed without executing JavaScript
+	Http,
+}
+
+impl FetchMode {
+	/// Returns the fetch mode stored for a source; defaults to the browser.
+	pub fn from_source(fetch_mode: &Option<String>) -> Self {
+		match fetch_mode.as_deref() {
+			Some("http") => FetchMode::Http,
+			_ => FetchMode::Browser,
+		}
+	}
+}
+
+/// Page fetched via HTTP.
+#[derive(Clone, Debug)]
+pub struct FetchedPage {
+	/// Page URL
+	pub url: String,
+
+	/// Textual page content
+	pub content: String,
+
+	/// Absolute URLs of the links in the page content
+	pub links: Vec<String>,
+}
+
+/// Next page of a paginated source.
+enum NextPage {
+	/// Source isn't paginated, or this is the last page
+	None,
+
+	/// URL of the next page
+	Url(String),
+
+	/// Pagination element is not a link and needs to be clicked in a browser
+	RequiresBrowser,
+}
+
+/// Fetches and parses source pages via HTTP.
+#[derive(Clone)]
+pub struct HttpFetcher {
+	/// HTTP client
+	client: reqwest::Client,
+}
+
+impl HttpFetcher {
+	/// Create and return a new HTTP fetcher.
+	pub fn new() -> Result<Self> {
+		let client = reqwest::Client::builder().timeout(Duration::from_secs(30)).build()?;
+
+		Ok(HttpFetcher { client })
+	}
+
+	/// Fetch the page at `url`.
+	///
+	/// Returns the final URL after redirects, whether the response is JSON, and the response body.
+	async fn get(&self, url: &str) -> Result<(String, bool, String)> {
+		let res = self.client.get(url).send().await?.error_for_status()?;
+		let final_url = res.url().to_string();
+		let is_json = res.headers().get(CONTENT_TYPE).and_then(|c| c.to_str().ok()).map(|c| c.contains("json")).unwrap_or(false);
+
+		Ok((final_url, is_json, res.text().await?))
+	}
+
+	/// Fetch the source and all its paginated pages.
+	/// Like in the browser, only the content of the element matching `selector` is used, and pagination links are followed.
+	///
+	/// Returns `None` if the source can't be parsed without a browser, e.g. because its content is rendered by JavaScript.
+	pub async fn fetch_pages(&self, url: &str, selector: Option<&str>, pagination: Option<&str>) -> Result<Option<Vec<FetchedPage>>> {
+		let mut pages: Vec<FetchedPage> = vec![];
+		let mut visited: HashSet<String> = HashSet::new();
+		let mut next_url = url.to_string();
+
+		while pages.len() < MAX_PAGES && visited.insert(next_url.clone()) {
+			let (page_url, is_json, body) = self.get(&next_url).await?;
+
+			if is_json {
+				// raw JSON content, return as is
+				pages.push(FetchedPage {
+					url: page_url,
+					content: body,
+					links: vec![],
+				});
+				return Ok(Some(pages));
+			}
+
+			let (content, links, next_page) = match parse_page(&body, &page_url, selector, pagination)? {
+				Some(parsed) => parsed,
+				// selected element doesn't exist without running JavaScript
+				None => return Ok(None),
+			};
+
+			if pages.last().map(|p| p.content == content).unwrap_or(false) {
+				// current page has already been parsed
+				break;
+			}
+
+			pages.push(FetchedPage { url: page_url, content, links });
+
+			match next_page {
+				NextPage::Url(url) => next_url = url,
+				NextPage::None => break,
+				NextPage::RequiresBrowser => return Ok(None),
+			}
+		}
+
+		Ok(Some(pages))
+	}
+
+	/// Fetch the RSS or Atom feed at `url`.
+	///
+	/// Returns a posting for every feed item; items are identified by their GUID.
+	pub async fn fetch_feed(&self, url: &str) -> Result<Vec<posting::Model>> {
+		let res = self.client.get(url).send().await?.error_for_status()?;
+		let feed = feed_rs::parser::parse(res.bytes().await?.as_ref())?;
+
+		Ok(feed
+			.entries
+			.into_iter()
+			.filter_map(|entry| {
+				let title = entry.title.map(|t| t.content.trim().to_string()).filter(|t| !t.is_empty())?;
+				let description = entry.summary.map(|s| parse_html(&s.content)).filter(|s| !s.trim().is_empty());
+				let content = entry.content.and_then(|c| c.body).map(|c| parse_html(&c)).filter(|c| !c.trim().is_empty());
+				let published_at = entry.published.or(entry.updated).map(|d| d.fixed_offset());
+
+				Some(posting::Model {
+					id: 0,
+					title,
+					content: content.or(description.clone()),
+					description,
+					url: entry.links.first().map(|l| l.href.clone()),
+					created_at: None,
+					source_id: None,
+					location: None,
+					department: None,
+					external_id: Some(entry.id),
+					published_at,
+					canonical_url: None,
+					last_seen_at: None,
+					closed_at: None,
+					workplace: None,
+					employment_type: None,
+					seniority: None,
+					salary_min: None,
+					salary_max: None,
+					salary_currency: None,
+				})
+			})
+			.collect())
+	}
+
+	/// Find the link to the details of the job posting with the provided `title` on the page at `page_url`, and fetch the details.
+	///
+	/// Returns the URL and markdown content of the posting details, or `None` if the posting isn't linked and
+	/// the details need to be opened in a browser.
+	pub async fn fetch_posting_details(&self, page_url: &str, selector: Option<&str>, title: &str) -> Result<Option<(String, String)>> {
+		let (page_url, is_json, body) = self.get(page_url).await?;
+		if is_json {
+			return Ok(None);
+		}
+
+		let posting_url = match find_posting_link(&body, &page_url, selector, title)? {
+			Some(url) => url,
+			None => return Ok(None),
+		};
+
+		if posting_url == page_url {
+			return Ok(None);
+		}
+
+		let (posting_url, is_json, body) = self.get(&posting_url).await?;
+		if is_json {
+			return Ok(Some((posting_url, body)));
+		}
+
+		let document = Html::parse_document(&body);
+		let content = document.select(&parse_selector("body")?).next().map(|b| parse_html(&b.html())).unwrap_or_default();
+
+		Ok(Some((posting_url, content)))
+	}
+}
+
+/// Parse a CSS selector.
+fn parse_selector(selector: &str) -> Result<Selector> {
+	Selector::parse(selector).map_err(|e| anyhow!("Invalid selector {selector}: {e}"))
+}
+
+/// Returns the selector used to find the source content; defaults to the page body.
+fn content_selector(selector: Option<&str>) -> &str {
+	match selector {
+		Some(s) if s.trim() != "" => s,
+		_ => "body",
+	}
+}
+
+/// Resolve a link on a page to an absolute URL, the same way links are resolved in the browser.
+fn resolve_link(page_url: &str, href: &str) -> Result<Option<String>> {
+	match base_url(Url::parse(page_url)?) {
+		Some(base_url) => Ok(Some(base_url.join(href)?.to_string())),
+		None => Ok(None),
+	}
+}
+
+/// Parse the text content, the links and the next page of an HTML page.
+///
+/// Returns `None` if the element matching `selector` doesn't exist.
+fn parse_page(body: &str, page_url: &str, selector: Option<&str>, pagination: Option<&str>) -> Result<Option<(String, Vec<String>, NextPage)>> {
+	let document = Html::parse_document(body);
+
+	let container = match document.select(&parse_selector(content_selector(selector))?).next() {
+		Some(el) => el,
+		None => return Ok(None),
+	};
+	let content = inner_text(container);
+	let links = container
+		.select(&parse_selector("a[href]")?)
+		.filter_map(|a| a.value().attr("href"))
+		.filter_map(|href| resolve_link(page_url, href).ok().flatten())
+		.collect();
+
+	let mut next_page = NextPage::None;
+	if let Some(pagination) = pagination.filter(|p| p.trim() != "") {
+		if let Some(el) = document.select(&parse_selector(pagination)?).next() {
+			next_page = match (el.value().name(), el.value().attr("href")) {
+				("a", Some(href)) => match resolve_link(page_url, href)? {
+					Some(url) => NextPage::Url(url),
+					None => NextPage::None,
+				},
+				_ => NextPage::RequiresBrowser,
+			};
+		}
+	}
+
+	Ok(Some((content, links, next_page)))
+}
+
+/// Find the link of the element containing the job posting `title`.
+fn find_posting_link(body: &str, page_url: &str, selector: Option<&str>, title: &str) -> Result<Option<String>> {
+	let document = Html::parse_document(body);
+
+	let container = match document.select(&parse_selector(content_selector(selector))?).next() {
+		Some(el) => el,
+		None => return Ok(None),
+	};
+
+	// elements that directly contain the title text
+	let elements_with_text = container
+		.descendants()
+		.filter(|n| n.value().as_text().map(|t| t.contains(title)).unwrap_or(false))
+		.filter_map(|n| n.parent().and_then(ElementRef::wrap));
+
+	for el in elements_with_text {
+		// the title is either a link itself or nested inside of a link
+		let link = std::iter::once(el).chain(el.ancestors().filter_map(ElementRef::wrap)).find(|e| e.value().name() == "a");
+
+		if let Some(href) = link.and_then(|l| l.value().attr("href")) {
+			return resolve_link(page_url, href);
+		}
+	}
+
+	Ok(None)
+}
+
+/// Returns the visible text of an element, with text of separate nodes on separate lines.
+fn inner_text(element: ElementRef) -> String {
+	element
+		.descendants()
+		.filter_map(|n| match n.value() {
+			Node::Text(text) => {
+				let hidden = n.ancestors().filter_map(ElementRef::wrap).any(|e| HIDDEN_ELEMENTS.contains(&e.value().name()));
+				let text = text.trim();
+				if hidden || text.is_empty() {
+					None
+				} else {
+					Some(text.to_string())
+				}
+			}
+			_ => None,
+		})
+		.collect::<Vec<String>>()
+		.join("\n")
+}
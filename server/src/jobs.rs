@@ -0,0 +1,280 @@
+/// Asynchronous refresh jobs.
+use crate::browser::BrowserPool;
+use crate::entities::{prelude::*, *};
+use crate::error::Error;
+use crate::extract::refresh_source;
+use crate::similarity::VectorBackend;
+
+use chrono::{FixedOffset, Utc};
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::sea_query::Expr;
+use sea_orm::*;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, Semaphore};
+
+/// Job worker configuration.
+///
+/// Read from the `jobs` section of `Rocket.toml`; missing values fall back to the defaults.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct JobsConfig {
+	/// Maximum number of refresh jobs that run at the same time
+	pub max_concurrent: usize,
+}
+
+impl Default for JobsConfig {
+	fn default() -> Self {
+		JobsConfig { max_concurrent: 4 }
+	}
+}
+
+/// Status of a refresh job.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JobStatus {
+	/// Job is waiting to be run
+	Queued,
+
+	/// Job is currently running
+	Running,
+
+	/// Job finished successfully
+	Finished,
+
+	/// Job failed
+	Failed,
+}
+
+impl JobStatus {
+	/// Returns the representation of the status that is stored in the database.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			JobStatus::Queued => "queued",
+			JobStatus::Running => "running",
+			JobStatus::Finished => "finished",
+			JobStatus::Failed => "failed",
+		}
+	}
+}
+
+/// Returns the current timestamp.
+fn now() -> DateTimeWithTimeZone {
+	Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap())
+}
+
+/// Queue for refresh jobs.
+///
+/// Jobs are stored in the `refresh_job` table and processed in the background by the `JobWorker`.
+#[derive(Clone)]
+pub struct JobQueue {
+	/// Database connection
+	db: DatabaseConnection,
+
+	/// Sends IDs of queued jobs to the worker
+	sender: mpsc::UnboundedSender<i32>,
+}
+
+impl JobQueue {
+	/// Create and return a new job queue, and the worker processing its jobs.
+	/// Jobs open pages in tabs of the browser pool.
+	pub fn new(db: DatabaseConnection, config: JobsConfig, browsers: BrowserPool, backend: VectorBackend) -> (Self, JobWorker) {
+		let (sender, receiver) = mpsc::unbounded_channel();
+
+		let worker = JobWorker {
+			db: db.clone(),
+			browsers,
+			backend,
+			receiver,
+			permits: Arc::new(Semaphore::new(config.max_concurrent.max(1))),
+		};
+
+		(JobQueue { db, sender }, worker)
+	}
+
+	/// Enqueue a job that refreshes the source with the provided ID.
+	/// If the source has already been queued with a delay, e.g. by the scheduler, the job is run right away instead.
+	///
+	/// Returns the created job, or the existing job if the source is already queued or being refreshed.
+	pub async fn enqueue(&self, source_id: i32) -> Result<refresh_job::Model, Error> {
+		let job = self.enqueue_delayed(source_id, Duration::ZERO).await?;
+
+		if job.status == JobStatus::Queued.as_str() && job.run_at.is_some_and(|run_at| run_at > now()) {
+			let mut active_job: refresh_job::ActiveModel = job.into();
+			active_job.run_at = Set(Some(now()));
+			let job = active_job.update(&self.db).await?;
+			self.send(job.id, Duration::ZERO);
+
+			return Ok(job);
+		}
+
+		Ok(job)
+	}
+
+	/// Enqueue a job for every active source.
+	/// Jobs run in parallel, up to the configured maximum number of concurrent jobs.
+	///
+	/// Returns the created jobs, or the existing jobs of sources that are already queued or being refreshed.
+	pub async fn enqueue_all(&self) -> Result<Vec<refresh_job::Model>, Error> {
+		let sources = Source::find().filter(source::Column::Deleted.eq(false)).order_by_asc(source::Column::Id).all(&self.db).await?;
+
+		let mut jobs = vec![];
+		for source in sources {
+			jobs.push(self.enqueue(source.id).await?);
+		}
+
+		Ok(jobs)
+	}
+
+	/// Enqueue a job that refreshes the source with the provided ID, once the delay has passed.
+	///
+	/// Returns the created job, or the existing job if the source is already queued or being refreshed.
+	pub async fn enqueue_delayed(&self, source_id: i32, delay: Duration) -> Result<refresh_job::Model, Error> {
+		if let Some(job) = self.active_job(source_id).await? {
+			return Ok(job);
+		}
+
+		let job = refresh_job::ActiveModel {
+			id: NotSet,
+			source_id: Set(source_id),
+			status: Set(JobStatus::Queued.as_str().to_string()),
+			created_at: Set(Some(now())),
+			started_at: Set(None),
+			finished_at: Set(None),
+			error: Set(None),
+			postings_found: Set(None),
+			run_at: Set(Some(now() + chrono::Duration::from_std(delay).unwrap_or_default())),
+		}
+		.insert(&self.db)
+		.await;
+
+		let job = match job {
+			Ok(job) => job,
+			// the source has been queued by a concurrent request since it was checked
+			Err(e) if matches!(e.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => {
+				return self.active_job(source_id).await?.ok_or(Error::Database(e));
+			}
+			Err(e) => return Err(e.into()),
+		};
+
+		self.send(job.id, delay);
+
+		Ok(job)
+	}
+
+	/// Returns the queued or running job of the source with the provided ID.
+	async fn active_job(&self, source_id: i32) -> Result<Option<refresh_job::Model>, Error> {
+		Ok(RefreshJob::find()
+			.filter(refresh_job::Column::SourceId.eq(source_id))
+			.filter(refresh_job::Column::Status.is_in([JobStatus::Queued.as_str(), JobStatus::Running.as_str()]))
+			.one(&self.db)
+			.await?)
+	}
+
+	/// Hand the job with the provided ID to the worker once the delay has passed.
+	fn send(&self, job_id: i32, delay: Duration) {
+		let sender = self.sender.clone();
+		tokio::spawn(async move {
+			tokio::time::sleep(delay).await;
+			let _ = sender.send(job_id);
+		});
+	}
+
+	/// Mark jobs that were interrupted by a server restart as failed.
+	/// Needs to run before the scheduler and worker are started, so that no new jobs are affected.
+	pub async fn fail_interrupted_jobs(&self) -> Result<(), Error> {
+		RefreshJob::update_many()
+			.col_expr(refresh_job::Column::Status, Expr::value(JobStatus::Failed.as_str()))
+			.col_expr(refresh_job::Column::Error, Expr::value("Interrupted by server restart"))
+			.col_expr(refresh_job::Column::FinishedAt, Expr::value(now()))
+			.filter(refresh_job::Column::Status.is_in([JobStatus::Queued.as_str(), JobStatus::Running.as_str()]))
+			.exec(&self.db)
+			.await?;
+
+		Ok(())
+	}
+}
+
+/// Worker that runs queued refresh jobs in the background.
+pub struct JobWorker {
+	/// Database connection
+	db: DatabaseConnection,
+
+	/// Pool of headless browsers used by refreshes
+	browsers: BrowserPool,
+
+	/// Backend computing the similarity of embeddings
+	backend: VectorBackend,
+
+	/// Receives IDs of queued jobs
+	receiver: mpsc::UnboundedReceiver<i32>,
+
+	/// Limits the number of jobs running at the same time
+	permits: Arc<Semaphore>,
+}
+
+impl JobWorker {
+	/// Start processing jobs in the background.
+	pub fn start(mut self) {
+		tokio::spawn(async move {
+			while let Some(job_id) = self.receiver.recv().await {
+				// wait until fewer than the maximum number of jobs are running
+				let permit = match self.permits.clone().acquire_owned().await {
+					Ok(permit) => permit,
+					Err(_) => return,
+				};
+
+				let db = self.db.clone();
+				let browsers = self.browsers.clone();
+				let backend = self.backend;
+				tokio::spawn(async move {
+					if let Err(e) = run_job(&db, &browsers, backend, job_id).await {
+						error!("Could not run refresh job {job_id}: {e}");
+					}
+					drop(permit);
+				});
+			}
+		});
+	}
+}
+
+/// Run a single refresh job and store its outcome.
+/// Jobs that are no longer queued or not due yet are skipped, e.g. when a delayed job has already been run by a manual refresh.
+async fn run_job(db: &DatabaseConnection, browsers: &BrowserPool, backend: VectorBackend, job_id: i32) -> Result<(), Error> {
+	let started = RefreshJob::update_many()
+		.col_expr(refresh_job::Column::Status, Expr::value(JobStatus::Running.as_str()))
+		.col_expr(refresh_job::Column::StartedAt, Expr::value(now()))
+		.filter(refresh_job::Column::Id.eq(job_id))
+		.filter(refresh_job::Column::Status.eq(JobStatus::Queued.as_str()))
+		.filter(refresh_job::Column::RunAt.lte(now()))
+		.exec(db)
+		.await?;
+
+	if started.rows_affected == 0 {
+		return Ok(());
+	}
+
+	let job = RefreshJob::find_by_id(job_id).one(db).await?.ok_or(Error::NotFound(format!("Refresh job {job_id} does not exist")))?;
+	let mut active_job: refresh_job::ActiveModel = job.clone().into();
+
+	let result = match Source::find_by_id(job.source_id).filter(source::Column::Deleted.eq(false)).one(db).await? {
+		Some(source) => refresh_source(db, &source, browsers, backend).await,
+		None => Err(Error::NotFound(format!("Source {} does not exist", job.source_id))),
+	};
+
+	match result {
+		Ok(outcome) => {
+			active_job.status = Set(JobStatus::Finished.as_str().to_string());
+			active_job.postings_found = Set(Some(outcome.postings.len() as i32));
+		}
+		Err(e) => {
+			warn!("Refresh job {job_id} failed: {e}");
+			active_job.status = Set(JobStatus::Failed.as_str().to_string());
+			active_job.error = Set(Some(e.message()));
+		}
+	}
+	active_job.finished_at = Set(Some(now()));
+	active_job.update(db).await?;
+
+	Ok(())
+}
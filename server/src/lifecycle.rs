@@ -0,0 +1,348 @@
+/// Tracking whether postings are still listed on their source.
+use crate::entities::{prelude::*, *};
+use crate::error::Error;
+use crate::util::canonical_url;
+
+use chrono::FixedOffset;
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::sea_query::Expr;
+use sea_orm::*;
+use std::collections::HashSet;
+
+/// ID, title, URL, external ID and closing timestamp of a stored posting.
+type StoredPosting = (i32, String, Option<String>, Option<String>, Option<DateTimeWithTimeZone>);
+
+/// Kind of change in the lifecycle of a posting.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PostingEventKind {
+	/// Posting was found on the source for the first time
+	Opened,
+
+	/// Posting is no longer listed on the source
+	Closed,
+
+	/// Closed posting is listed on the source again
+	Reopened,
+}
+
+impl PostingEventKind {
+	/// Returns the name of the event kind as stored in the database.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			PostingEventKind::Opened => "opened",
+			PostingEventKind::Closed => "closed",
+			PostingEventKind::Reopened => "reopened",
+		}
+	}
+}
+
+/// Postings that are currently listed on a source.
+#[derive(Clone, Debug)]
+pub enum Listing {
+	/// Content of the source pages; postings are listed if their URL is linked on the pages or their title appears in
+	/// the text content
+	Content {
+		/// Normalized text content of the source pages
+		text: String,
+
+		/// Canonical URLs of the links on the source pages
+		urls: HashSet<String>,
+	},
+
+	/// Postings fetched from a feed or job board API; postings are identified by their canonical URL or external ID
+	Postings {
+		/// Canonical URLs of the listed postings
+		urls: HashSet<String>,
+
+		/// External IDs of the listed postings
+		external_ids: HashSet<String>,
+
+		/// Whether all postings of the source are listed; feeds only list the most recent postings
+		complete: bool,
+	},
+}
+
+impl Listing {
+	/// Create a listing from the text content and the links of the source pages.
+	pub fn content<'a>(content: &str, links: impl Iterator<Item = &'a String>) -> Self {
+		Listing::Content {
+			text: normalize_text(content),
+			urls: links.filter_map(|l| canonical_url(l)).collect(),
+		}
+	}
+
+	/// Create a listing from the postings fetched from a source.
+	/// If the postings are `complete`, postings that aren't listed are closed.
+	pub fn from_postings(postings: &[posting::Model], complete: bool) -> Self {
+		Listing::Postings {
+			urls: postings.iter().filter_map(|p| p.url.as_deref().and_then(canonical_url)).collect(),
+			external_ids: postings.iter().filter_map(|p| p.external_id.clone()).collect(),
+			complete,
+		}
+	}
+
+	/// Returns whether the posting is listed.
+	fn contains(&self, title: &str, url: &Option<String>, external_id: &Option<String>) -> bool {
+		let canonical = url.as_deref().and_then(canonical_url);
+
+		match self {
+			Listing::Content { text, urls } => {
+				let listed_url = canonical.map(|u| urls.contains(&u)).unwrap_or(false);
+				let title = normalize_text(title);
+				listed_url || (!title.is_empty() && text.contains(&title))
+			}
+			Listing::Postings { urls, external_ids, .. } => {
+				let listed_url = canonical.map(|u| urls.contains(&u)).unwrap_or(false);
+				let listed_id = external_id.as_ref().map(|id| external_ids.contains(id)).unwrap_or(false);
+				listed_url || listed_id
+			}
+		}
+	}
+
+	/// Returns whether a posting that isn't listed is no longer available on the source.
+	/// Titles of postings on source pages are extracted by the LLM and might be reworded, so these postings are only
+	/// closed if they have a URL that is no longer linked.
+	fn closes(&self, url: &Option<String>) -> bool {
+		match self {
+			Listing::Content { .. } => url.as_deref().and_then(canonical_url).is_some(),
+			Listing::Postings { complete, .. } => *complete,
+		}
+	}
+}
+
+/// Returns the text in lowercase, with punctuation and repeated whitespace replaced by a single space.
+/// Titles extracted by the LLM often differ in casing and punctuation from the text on the source.
+fn normalize_text(text: &str) -> String {
+	text.to_lowercase().split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).collect::<Vec<&str>>().join(" ")
+}
+
+/// Changes in the lifecycle of the postings of a source.
+#[derive(Debug, Default, PartialEq)]
+struct Transitions {
+	/// Open postings that are still listed
+	listed: Vec<i32>,
+
+	/// Open postings that are no longer listed
+	closed: Vec<i32>,
+
+	/// Closed postings that are listed again
+	reopened: Vec<i32>,
+}
+
+/// Determine which of the stored postings are still listed, need to be closed or need to be reopened.
+fn transitions(listing: &Listing, postings: Vec<StoredPosting>) -> Transitions {
+	let mut transitions = Transitions::default();
+
+	for (id, title, url, external_id, closed_at) in postings {
+		match (listing.contains(&title, &url, &external_id), closed_at) {
+			(true, None) => transitions.listed.push(id),
+			(true, Some(_)) => transitions.reopened.push(id),
+			// postings missing from incomplete listings might still be open
+			(false, None) if listing.closes(&url) => transitions.closed.push(id),
+			(false, _) => {}
+		}
+	}
+
+	transitions
+}
+
+/// Returns the current timestamp.
+fn now() -> DateTimeWithTimeZone {
+	chrono::offset::Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap())
+}
+
+/// Record a lifecycle event of a posting.
+pub async fn record_event(db: &DatabaseConnection, posting_id: i32, kind: PostingEventKind) -> Result<(), Error> {
+	let event = posting_event::ActiveModel {
+		id: NotSet,
+		posting_id: Set(posting_id),
+		kind: Set(kind.as_str().to_string()),
+		created_at: Set(Some(now())),
+	};
+
+	event.insert(db).await?;
+
+	Ok(())
+}
+
+/// Compare the postings of a source with the postings currently listed on the source.
+///
+/// Listed postings are marked as seen; open postings that are no longer listed are closed, and closed postings that
+/// are listed again are reopened. Postings are only closed if the listing contains all postings of the source, and
+/// postings on source pages only if their URL is no longer linked.
+pub async fn update_listed_postings(db: &DatabaseConnection, source_id: i32, listing: &Listing) -> Result<(), Error> {
+	let postings: Vec<StoredPosting> = Posting::find()
+		.select_only()
+		.column(posting::Column::Id)
+		.column(posting::Column::Title)
+		.column(posting::Column::Url)
+		.column(posting::Column::ExternalId)
+		.column(posting::Column::ClosedAt)
+		.filter(posting::Column::SourceId.eq(source_id))
+		.into_tuple()
+		.all(db)
+		.await?;
+
+	let Transitions {
+		listed: mut listed_ids,
+		closed: closed_ids,
+		reopened: reopened_ids,
+	} = transitions(listing, postings);
+
+	let now = now();
+
+	if !closed_ids.is_empty() {
+		Posting::update_many()
+			.col_expr(posting::Column::ClosedAt, Expr::value(now))
+			.filter(posting::Column::Id.is_in(closed_ids.clone()))
+			.exec(db)
+			.await?;
+	}
+
+	if !reopened_ids.is_empty() {
+		Posting::update_many()
+			.col_expr(posting::Column::ClosedAt, Expr::value(Option::<DateTimeWithTimeZone>::None))
+			.filter(posting::Column::Id.is_in(reopened_ids.clone()))
+			.exec(db)
+			.await?;
+	}
+
+	listed_ids.extend(reopened_ids.iter());
+	if !listed_ids.is_empty() {
+		Posting::update_many()
+			.col_expr(posting::Column::LastSeenAt, Expr::value(now))
+			.filter(posting::Column::Id.is_in(listed_ids))
+			.exec(db)
+			.await?;
+	}
+
+	let events: Vec<posting_event::ActiveModel> = closed_ids
+		.into_iter()
+		.map(|id| (id, PostingEventKind::Closed))
+		.chain(reopened_ids.into_iter().map(|id| (id, PostingEventKind::Reopened)))
+		.map(|(id, kind)| posting_event::ActiveModel {
+			id: NotSet,
+			posting_id: Set(id),
+			kind: Set(kind.as_str().to_string()),
+			created_at: Set(Some(now)),
+		})
+		.collect();
+
+	if !events.is_empty() {
+		PostingEvent::insert_many(events).exec(db).await?;
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn stored(id: i32, title: &str, url: Option<&str>, external_id: Option<&str>, closed: bool) -> StoredPosting {
+		(
+			id,
+			title.to_string(),
+			url.map(|u| u.to_string()),
+			external_id.map(|e| e.to_string()),
+			if closed { Some(now()) } else { None },
+		)
+	}
+
+	fn posting(title: &str, url: Option<&str>, external_id: Option<&str>) -> posting::Model {
+		serde_json::from_value(serde_json::json!({ "title": title, "url": url, "external_id": external_id })).unwrap()
+	}
+
+	#[test]
+	fn content_matches_normalized_titles() {
+		let listing = Listing::content("Open positions\nSenior Software Engineer, Backend (m/f/d)\nOffice Manager", [].iter());
+
+		assert!(listing.contains("Senior Software Engineer - Backend", &None, &None));
+		assert!(listing.contains("office manager", &None, &None));
+		assert!(!listing.contains("Product Designer", &None, &None));
+		assert!(!listing.contains("", &None, &None));
+	}
+
+	#[test]
+	fn content_matches_linked_urls() {
+		let links = ["https://acme.com/jobs/42/?utm_source=careers".to_string()];
+		let listing = Listing::content("Open positions", links.iter());
+
+		// the LLM title doesn't appear on the page, but the posting is linked
+		assert!(listing.contains("Staff Engineer", &Some("https://acme.com/jobs/42#apply".to_string()), &None));
+		assert!(!listing.contains("Staff Engineer", &Some("https://acme.com/jobs/43".to_string()), &None));
+	}
+
+	#[test]
+	fn postings_match_urls_and_external_ids() {
+		let listing = Listing::from_postings(&[posting("A", Some("https://acme.com/jobs/1?gh_src=x"), None), posting("B", None, Some("guid-2"))], true);
+
+		assert!(listing.contains("Other title", &Some("https://acme.com/jobs/1".to_string()), &None));
+		assert!(listing.contains("Other title", &None, &Some("guid-2".to_string())));
+		assert!(!listing.contains("A", &None, &Some("guid-1".to_string())));
+	}
+
+	#[test]
+	fn complete_listing_closes_and_reopens_postings() {
+		let listing = Listing::from_postings(&[posting("A", Some("https://acme.com/jobs/1"), None), posting("B", Some("https://acme.com/jobs/2"), None)], true);
+
+		let postings = vec![
+			stored(1, "A", Some("https://acme.com/jobs/1"), None, false),
+			stored(2, "B", Some("https://acme.com/jobs/2"), None, true),
+			stored(3, "C", Some("https://acme.com/jobs/3"), None, false),
+			stored(4, "D", Some("https://acme.com/jobs/4"), None, true),
+		];
+
+		assert_eq!(
+			transitions(&listing, postings),
+			Transitions {
+				listed: vec![1],
+				closed: vec![3],
+				reopened: vec![2],
+			}
+		);
+	}
+
+	#[test]
+	fn incomplete_listing_doesnt_close_postings() {
+		let listing = Listing::from_postings(&[posting("A", None, Some("guid-1")), posting("B", None, Some("guid-2"))], false);
+
+		let postings = vec![
+			stored(1, "A", None, Some("guid-1"), false),
+			stored(2, "B", None, Some("guid-2"), true),
+			stored(3, "C", None, Some("guid-3"), false),
+		];
+
+		assert_eq!(
+			transitions(&listing, postings),
+			Transitions {
+				listed: vec![1],
+				closed: vec![],
+				reopened: vec![2],
+			}
+		);
+	}
+
+	#[test]
+	fn content_listing_closes_postings_that_are_no_longer_linked() {
+		let links = ["https://acme.com/jobs/1".to_string()];
+		let listing = Listing::content("Backend Engineer", links.iter());
+
+		let postings = vec![
+			stored(1, "Senior Backend Engineer", Some("https://acme.com/jobs/1"), None, false),
+			stored(2, "Frontend Engineer", Some("https://acme.com/jobs/2"), None, false),
+			stored(3, "Backend Engineer", None, None, true),
+			// the title might have been reworded by the LLM
+			stored(4, "Engineer, Platform", None, None, false),
+		];
+
+		assert_eq!(
+			transitions(&listing, postings),
+			Transitions {
+				listed: vec![1],
+				closed: vec![2],
+				reopened: vec![3],
+			}
+		);
+	}
+}
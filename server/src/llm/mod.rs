@@ -0,0 +1,201 @@
+/// LLM provider abstraction.
+use crate::entities::settings;
+use crate::openai::chat::{ChatCompletions, CHAT_MODEL};
+use crate::openai::embeddings::{Embeddings, EMBEDDING_MODEL};
+use crate::openai::BASE_URL;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::cmp;
+
+/// Task an LLM is used for.
+pub enum Task {
+	/// Extract job postings from provided inputs.
+	JobsFeed,
+
+	/// Get similar source suggestions based on provided inputs.
+	JobsSuggestion,
+
+	/// Extract structured attributes from the details of a job posting.
+	PostingAttributes,
+}
+
+impl Task {
+	/// Returns name of the task, used to name the response schema.
+	pub fn name(&self) -> &'static str {
+		match self {
+			Task::JobsFeed => "jobs_feed",
+			Task::JobsSuggestion => "jobs_suggestion",
+			Task::PostingAttributes => "posting_attributes",
+		}
+	}
+
+	/// Returns prompt to be used based on the task.
+	pub fn instructions(&self) -> &'static str {
+		match self {
+			Task::JobsFeed => {
+				"Extract a complete list of job posting titles from the provided inputs, and the numbers of the provided criteria each posting is related to. \
+			Only return postings that are in the input. Do not miss any posting!"
+			}
+			Task::JobsSuggestion => "Return a list of 10 career websites of companies similar to the company provided as input.",
+			Task::PostingAttributes => {
+				"Extract the attributes of the job posting provided as input. Return a single item. \
+			Use null for attributes that are not mentioned in the posting; do not guess."
+			}
+		}
+	}
+
+	/// Returns the JSON schema of a single item in the response.
+	pub fn item_schema(&self) -> Value {
+		match self {
+			Task::JobsFeed => json!({
+				"type": "object",
+				"properties": {
+					"title": { "type": "string" },
+					"criteria": { "type": "array", "items": { "type": "integer" }, "description": "Numbers of the criteria the posting is related to" }
+				},
+				"required": ["title", "criteria"],
+				"additionalProperties": false
+			}),
+			Task::JobsSuggestion => json!({
+				"type": "object",
+				"properties": {
+					"name": { "type": "string" },
+					"url": { "type": "string" }
+				},
+				"required": ["name", "url"],
+				"additionalProperties": false
+			}),
+			Task::PostingAttributes => json!({
+				"type": "object",
+				"properties": {
+					"location": { "type": ["string", "null"] },
+					"workplace": { "type": ["string", "null"], "enum": ["remote", "hybrid", "onsite", null] },
+					"employment_type": { "type": ["string", "null"], "enum": ["full_time", "part_time", "contract", "internship", "temporary", null] },
+					"seniority": { "type": ["string", "null"], "enum": ["entry", "mid", "senior", "lead", "executive", null] },
+					"department": { "type": ["string", "null"] },
+					"salary_min": { "type": ["number", "null"], "description": "Lower bound of the yearly salary" },
+					"salary_max": { "type": ["number", "null"], "description": "Upper bound of the yearly salary" },
+					"salary_currency": { "type": ["string", "null"], "description": "ISO 4217 currency code" },
+					"posted_at": { "type": ["string", "null"], "description": "Date the posting was published, as YYYY-MM-DD" }
+				},
+				"required": ["location", "workplace", "employment_type", "seniority", "department", "salary_min", "salary_max", "salary_currency", "posted_at"],
+				"additionalProperties": false
+			}),
+		}
+	}
+
+	/// Returns the JSON schema the response needs to follow.
+	/// Structured outputs require an object at the root, so items are wrapped into an `items` list.
+	pub fn response_schema(&self) -> Value {
+		json!({
+			"type": "object",
+			"properties": {
+				"items": {
+					"type": "array",
+					"items": self.item_schema()
+				}
+			},
+			"required": ["items"],
+			"additionalProperties": false
+		})
+	}
+}
+
+/// LLM provider backends that can be selected in the settings.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Provider {
+	/// OpenAI API
+	OpenAI,
+
+	/// Any API compatible with the OpenAI chat completions API, e.g. Ollama, llama.cpp server or vLLM
+	OpenAICompatible,
+}
+
+impl Provider {
+	/// Returns the provider configured in the settings.
+	/// Defaults to OpenAI.
+	pub fn from_settings(settings: &settings::Model) -> Self {
+		match settings.provider.as_deref() {
+			Some("openai_compatible") => Provider::OpenAICompatible,
+			_ => Provider::OpenAI,
+		}
+	}
+}
+
+/// Large language model that processes prompts.
+#[async_trait]
+pub trait LlmProvider: Send + Sync {
+	/// Run the LLM on the provided messages as a single request.
+	///
+	/// Returns the items of the structured response.
+	async fn run(&self, messages: &[String]) -> Result<Vec<Value>>;
+
+	/// Returns available LLM models.
+	async fn get_models(&self) -> Result<Vec<String>>;
+}
+
+/// Model that turns text into embedding vectors.
+#[async_trait]
+pub trait EmbeddingProvider: Send + Sync {
+	/// Create embedding for text input.
+	///
+	/// Returns the embedding vector.
+	async fn create(&self, input: &str) -> Result<Vec<f32>>;
+}
+
+/// Returns the base URL of the configured provider API.
+fn base_url(settings: &settings::Model) -> String {
+	match (Provider::from_settings(settings), &settings.base_url) {
+		(Provider::OpenAICompatible, Some(url)) if !url.trim().is_empty() => url.trim().to_string(),
+		_ => BASE_URL.to_string(),
+	}
+}
+
+/// Create and return the LLM provider configured in the settings for a specific task.
+pub fn llm_provider(settings: &settings::Model, task: Task) -> Box<dyn LlmProvider> {
+	let api_key = settings.api_key.clone().unwrap_or_default();
+	let model = match &settings.model {
+		Some(model) if !model.trim().is_empty() => model.clone(),
+		_ => CHAT_MODEL.to_string(),
+	};
+
+	Box::new(ChatCompletions::new(&api_key, &base_url(settings), &model, task))
+}
+
+/// Create and return the embedding provider configured in the settings.
+pub fn embedding_provider(settings: &settings::Model) -> Box<dyn EmbeddingProvider> {
+	let api_key = settings.api_key.clone().unwrap_or_default();
+	let model = match &settings.embedding_model {
+		Some(model) if !model.trim().is_empty() => model.clone(),
+		_ => EMBEDDING_MODEL.to_string(),
+	};
+
+	Box::new(Embeddings::new(&api_key, &base_url(settings), &model))
+}
+
+/// Compute the similarity between one embedding vector and a set of embedding vectors.
+/// Similarity score based on https://www.simonwenkel.com/notes/ai/metrics/cosine_distance.html
+///
+/// Returns the similarity score.
+pub fn get_similarity(vec_a: &[f32], vecs_b: &[Vec<f32>]) -> f32 {
+	vecs_b
+		.iter()
+		.map(|vec_b| {
+			let mut a_dot_b: f32 = 0.0;
+			let mut a_mag: f32 = 0.0;
+			let mut b_mag: f32 = 0.0;
+			let vec_size: usize = cmp::min(vec_a.len(), vec_b.len());
+
+			for i in 0..vec_size {
+				a_dot_b += vec_a[i] * vec_b[i];
+				a_mag += vec_a[i] * vec_a[i];
+				b_mag += vec_b[i] * vec_b[i];
+			}
+
+			a_dot_b / (a_mag.sqrt() * b_mag.sqrt())
+		})
+		.max_by(|a, b| a.total_cmp(b))
+		.unwrap_or(0.0)
+}
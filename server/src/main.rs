@@ -1,17 +1,40 @@
+mod ats;
+mod attributes;
+mod auth;
+mod browser;
 mod entities;
+mod error;
 mod extract;
+mod fetch;
+mod jobs;
+mod lifecycle;
+mod llm;
 mod openai;
+mod pagination;
 mod pool;
 mod routes;
+mod rules;
+mod scheduler;
+mod scoring;
+mod similarity;
+mod user_postings;
 mod util;
 
 #[macro_use]
 extern crate rocket;
 
+use auth::User;
+use browser::{BrowserConfig, BrowserPool};
+use jobs::{JobQueue, JobsConfig};
 use pool::Db;
+use rocket::fairing::AdHoc;
 use rocket::fs::{relative, NamedFile};
+use rocket::response::Redirect;
+use routes::accounts::SetupSecret;
+use routes::feeds::FeedConfig;
+use scheduler::{Scheduler, SchedulerConfig};
+use similarity::VectorBackend;
 
-use sea_orm::*;
 use sea_orm_rocket::{Config, Database};
 
 use std::{
@@ -19,10 +42,10 @@ use std::{
 	path::{Path, PathBuf},
 };
 
-use crate::entities::prelude::*;
 use migration::MigratorTrait;
 
 const DIST: &str = relative!("dist");
+const LOGIN_PAGE: &str = "login";
 
 // serve any static file
 #[get("/<file..>", rank = 2)]
@@ -36,10 +59,14 @@ async fn favicon() -> Option<NamedFile> {
 	NamedFile::open(Path::new(DIST).join("favicon.svg")).await.ok()
 }
 
-// serve the index.html
-#[get("/<_..>", rank = 3)]
-async fn index() -> Option<NamedFile> {
-	NamedFile::open(Path::new(DIST).join("index.html")).await.ok()
+// serve the index.html, signed out users are redirected to the login page
+#[get("/<path..>", rank = 3)]
+async fn index(user: Option<User>, path: PathBuf) -> Result<Option<NamedFile>, Redirect> {
+	if user.is_none() && !path.starts_with(LOGIN_PAGE) {
+		return Err(Redirect::to(format!("/{LOGIN_PAGE}/")));
+	}
+
+	Ok(NamedFile::open(Path::new(DIST).join("index.html")).await.ok())
 }
 
 #[launch]
@@ -62,28 +89,70 @@ async fn rocket() -> _ {
 	let db = sea_orm::Database::connect(config.url).await.unwrap();
 	migration::Migrator::up(&db, None).await.unwrap();
 
-	// create default settings if they haven't been stored yet
-	match env::var("API_KEY") {
-		Ok(api_key) => {
-			if Settings::find().count(&db).await.unwrap() == 0 {
-				let new_settings = entities::settings::ActiveModel {
-					id: NotSet,
-					api_key: Set(Some(api_key)),
-					model: Set(Some("gpt-3.5-turbo".to_string())),
-				};
-				let _ = new_settings.insert(&db).await;
-			}
-		}
-		_ => {}
-	};
+	// postings stored before URLs were normalized are identified by their canonical URL as well
+	extract::backfill_canonical_urls(&db).await.unwrap();
+
+	// the first account can only be created with the setup secret
+	let setup_secret = SetupSecret::new();
+	let setup_db = db.clone();
+
+	// the similarity of embeddings is computed by pgvector if it is installed
+	let vector_backend = VectorBackend::detect(&db).await.unwrap();
+
+	// headless browsers are shared by all refreshes
+	let browser_config: BrowserConfig = figment.extract_inner("browser").unwrap_or_default();
+	let browser_pool = BrowserPool::new(browser_config);
+
+	// refresh jobs are run in the background by the job worker
+	let jobs_config: JobsConfig = figment.extract_inner("jobs").unwrap_or_default();
+	let (job_queue, job_worker) = JobQueue::new(db.clone(), jobs_config, browser_pool.clone(), vector_backend);
+
+	// jobs that were queued or running when the server stopped will never finish
+	job_queue.fail_interrupted_jobs().await.unwrap();
+
+	// refresh sources periodically in the background once the server is running
+	let scheduler_config: SchedulerConfig = figment.extract_inner("scheduler").unwrap_or_default();
+	let scheduler = Scheduler::new(db, scheduler_config, job_queue.clone());
+
+	// URL used for links in feeds
+	let feed_config: FeedConfig = figment.extract_inner("feeds").unwrap_or_default();
 
 	// mount API routes
 	rocket::custom(figment)
 		.attach(Db::init())
+		.manage(job_queue)
+		.manage(browser_pool)
+		.manage(feed_config)
+		.manage(vector_backend)
+		.manage(setup_secret)
+		.attach(AdHoc::on_liftoff("Job Worker", |_| Box::pin(async move { job_worker.start() })))
+		.attach(AdHoc::on_liftoff("Scheduler", |_| Box::pin(async move { scheduler.start() })))
+		.attach(AdHoc::on_liftoff("Setup Secret", |rocket| {
+			Box::pin(async move {
+				if let Some(secret) = rocket.state::<SetupSecret>() {
+					if let Err(e) = secret.log_if_in_setup(&setup_db).await {
+						error!("Could not check whether accounts have been set up: {e}");
+					}
+				}
+			})
+		}))
 		.mount("/_app", routes![static_files])
+		.register("/api/v1", catchers![error::default_catcher])
+		.register("/", catchers![auth::unauthorized])
 		.mount(
 			"/api/v1",
 			routes![
+				routes::sessions::login,
+				routes::sessions::logout,
+				routes::accounts::accounts,
+				routes::accounts::setup_status,
+				routes::accounts::current_account,
+				routes::accounts::add_account,
+				routes::accounts::update_password,
+				routes::accounts::delete_account,
+				routes::tokens::api_tokens,
+				routes::tokens::add_api_token,
+				routes::tokens::delete_api_token,
 				routes::sources::sources,
 				routes::sources::add_source,
 				routes::sources::source_by_id,
@@ -92,11 +161,31 @@ async fn rocket() -> _ {
 				routes::sources::reset_source_cache,
 				routes::sources::refresh_source_suggestions,
 				routes::sources::source_suggestions,
+				routes::sources::enqueue_source_refresh,
 				routes::filters::filters,
-				routes::filters::update_filters,
+				routes::filters::filter_by_id,
+				routes::filters::add_filter,
+				routes::filters::update_filter,
+				routes::filters::delete_filter,
+				routes::filter_profiles::filter_profiles,
+				routes::filter_profiles::filter_profile_by_id,
+				routes::filter_profiles::add_filter_profile,
+				routes::filter_profiles::update_filter_profile,
+				routes::filter_profiles::delete_filter_profile,
+				routes::filter_profiles::filter_profile_sources,
+				routes::filter_profiles::source_filter_profiles,
+				routes::filter_profiles::attach_filter_profile,
+				routes::filter_profiles::detach_filter_profile,
 				routes::postings::unread_postings,
-				routes::postings::refresh_postings,
+				routes::postings::refresh_all_postings,
+				routes::postings::rescore_postings,
+				routes::postings::posting_stats,
 				routes::postings::posting_by_id,
+				routes::postings::posting_duplicates,
+				routes::postings::posting_history,
+				routes::postings::search_postings,
+				routes::postings::semantic_search_postings,
+				routes::postings::similar_to_posting,
 				routes::postings::mark_postings_read,
 				routes::postings::update_posting,
 				routes::postings::bookmarked_postings,
@@ -105,7 +194,11 @@ async fn rocket() -> _ {
 				routes::settings::update_settings,
 				routes::settings::get_models,
 				routes::suggestions::suggestions,
+				routes::jobs::jobs,
+				routes::jobs::job_by_id,
+				routes::scoring::scoring_status,
+				routes::scoring::train_scoring_model,
 			],
 		)
-		.mount("/", routes![favicon, index])
+		.mount("/", routes![favicon, index, routes::feeds::rss_feed, routes::feeds::atom_feed, routes::feeds::json_feed])
 }
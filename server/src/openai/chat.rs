@@ -0,0 +1,143 @@
+// OpenAI-compatible Chat Completions API
+use anyhow::anyhow;
+use anyhow::Result;
+
+use reqwest::StatusCode;
+use serde_json::json;
+use serde_json::Value;
+
+use crate::llm::{LlmProvider, Task};
+use crate::openai::OpenAIApi;
+use async_trait::async_trait;
+
+// default model used for chat completions
+pub const CHAT_MODEL: &str = "gpt-4o-mini";
+
+/// Chat Completions API handler.
+///
+/// Works with any provider that implements the OpenAI chat completions API,
+/// such as Ollama, llama.cpp server or vLLM.
+pub struct ChatCompletions {
+	/// API key; can be empty for providers that don't require authentication.
+	api_key: String,
+
+	/// Base URL of the API.
+	base_url: String,
+
+	/// LLM model to use.
+	pub model: String,
+
+	/// Task the model is used for.
+	task: Task,
+}
+
+impl OpenAIApi for ChatCompletions {
+	fn api_key(&self) -> &String {
+		&self.api_key
+	}
+
+	fn base_url(&self) -> &str {
+		&self.base_url
+	}
+}
+
+#[async_trait]
+impl LlmProvider for ChatCompletions {
+	async fn run(&self, messages: &[String]) -> Result<Vec<Value>> {
+		self.complete(messages).await
+	}
+
+	async fn get_models(&self) -> Result<Vec<String>> {
+		self.list_models().await
+	}
+}
+
+impl ChatCompletions {
+	/// Create and return a new chat completions API handler.
+	pub fn new(api_key: &str, base_url: &str, model: &str, task: Task) -> Self {
+		Self {
+			api_key: api_key.to_string(),
+			base_url: base_url.trim_end_matches('/').to_string(),
+			model: model.to_string(),
+			task,
+		}
+	}
+
+	/// Send the messages, preceded by the task instructions, as a single chat completion request.
+	/// The response is constrained to the JSON schema of the task using structured outputs. Providers and models
+	/// that reject structured outputs are asked for a JSON object instead, with the schema added to the instructions.
+	///
+	/// Returns the items of the model response.
+	async fn complete(&self, messages: &[String]) -> Result<Vec<Value>> {
+		let res = self.send(messages, true).await?;
+		let res = match res.status() {
+			StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => self.send(messages, false).await?,
+			_ => res,
+		};
+
+		if res.status() == StatusCode::OK {
+			let response_body = res.json::<Value>().await?;
+			let content = response_body
+				.get("choices")
+				.and_then(|c| c.get(0))
+				.and_then(|c| c.get("message"))
+				.and_then(|m| m.get("content"))
+				.and_then(|c| c.as_str())
+				.ok_or(anyhow!("Unexpected chat completion response"))?;
+
+			let response: Value = serde_json::from_str(content)?;
+			let items = response.get("items").and_then(|i| i.as_array()).ok_or(anyhow!("Chat completion response is missing items"))?;
+
+			return Ok(items.clone());
+		}
+
+		Err(anyhow!("Chat completion request failed with status {}", res.status()))
+	}
+
+	/// Send a chat completion request, either with a structured output response format following the task schema,
+	/// or in JSON mode with the schema described in the instructions.
+	async fn send(&self, messages: &[String], structured: bool) -> Result<reqwest::Response> {
+		let url = format!("{}/chat/completions", self.base_url);
+		let headers = self.headers()?;
+		let client = self.client();
+
+		let (instructions, response_format) = if structured {
+			let response_format = json!({
+				"type": "json_schema",
+				"json_schema": {
+					"name": self.task.name(),
+					"strict": true,
+					"schema": self.task.response_schema()
+				}
+			});
+			(self.task.instructions().to_string(), response_format)
+		} else {
+			let instructions = format!(
+				"{}\nRespond with a JSON object that follows this JSON schema: {}",
+				self.task.instructions(),
+				self.task.response_schema()
+			);
+			(instructions, json!({ "type": "json_object" }))
+		};
+
+		let mut messages_json: Vec<Value> = vec![json!({
+			"role": "system",
+			"content": instructions
+		})];
+		messages_json.extend(messages.iter().map(|m| {
+			json!({
+				"role": "user",
+				"content": m
+			})
+		}));
+
+		let body = json!({
+			"model": &self.model,
+			"messages": messages_json,
+			"temperature": 0,
+			"response_format": response_format
+		});
+
+		Ok(client.post(url).headers(headers).json(&body).send().await?)
+	}
+}
@@ -1,8 +1,8 @@
 // OpenAI Embedding API
+use crate::llm::EmbeddingProvider;
 use crate::openai::OpenAIApi;
-use crate::openai::BASE_URL;
+use async_trait::async_trait;
 use reqwest::StatusCode;
-use std::cmp;
 
 use serde_json::json;
 use serde_json::Value;
@@ -10,14 +10,17 @@ use serde_json::Value;
 use anyhow::anyhow;
 use anyhow::Result;
 
-// model to create embeddings
-const EMBEDDING_MODEL: &str = "text-embedding-3-small";
+// default model to create embeddings
+pub const EMBEDDING_MODEL: &str = "text-embedding-3-small";
 
 /// Embeddings API instance
 pub struct Embeddings {
 	/// OpenAI API key
 	api_key: String,
 
+	/// Base URL of the OpenAI-compatible API
+	base_url: String,
+
 	/// Embedding model to use
 	pub model: String,
 }
@@ -26,21 +29,33 @@ impl OpenAIApi for Embeddings {
 	fn api_key(&self) -> &String {
 		&self.api_key
 	}
+
+	fn base_url(&self) -> &str {
+		&self.base_url
+	}
+}
+
+#[async_trait]
+impl EmbeddingProvider for Embeddings {
+	async fn create(&self, input: &str) -> Result<Vec<f32>> {
+		self.create_embedding(input).await
+	}
 }
 
 impl Embeddings {
 	/// Create a new embedding API handler instance.
-	pub fn new(api_key: &String) -> Self {
+	pub fn new(api_key: &str, base_url: &str, model: &str) -> Self {
 		Self {
-			api_key: api_key.clone(),
-			model: EMBEDDING_MODEL.to_string(),
+			api_key: api_key.to_string(),
+			base_url: base_url.trim_end_matches('/').to_string(),
+			model: model.to_string(),
 		}
 	}
 
 	/// Create embedding for text input.
 	/// Returns the embedding vector.
-	pub async fn create(&self, input: &String) -> Result<Vec<f32>> {
-		let url = format!("{BASE_URL}/embeddings");
+	async fn create_embedding(&self, input: &str) -> Result<Vec<f32>> {
+		let url = format!("{}/embeddings", self.base_url);
 		let headers = self.headers()?;
 
 		let body = json!({
@@ -54,39 +69,16 @@ impl Embeddings {
 
 		if res.status() == StatusCode::OK {
 			let response_body = res.json::<Value>().await?;
-			let data = response_body.get("data").unwrap().as_array().unwrap();
-			if data.len() > 0 {
-				return Ok(data[0].get("embedding").unwrap().as_array().unwrap().iter().map(|e| e.as_f64().unwrap() as f32).collect::<Vec<f32>>());
-			}
+			let embedding = response_body
+				.get("data")
+				.and_then(|data| data.get(0))
+				.and_then(|data| data.get("embedding"))
+				.and_then(|embedding| embedding.as_array())
+				.ok_or(anyhow!("Unexpected embedding response: {response_body}"))?;
+
+			return embedding.iter().map(|e| e.as_f64().map(|e| e as f32).ok_or(anyhow!("Invalid embedding value: {e}"))).collect();
 		}
 
-		return Err(anyhow!("Could not get embedding"));
-	}
-
-	/// Compute the similarity between one embedding vector and a set of embedding vectors.
-	/// Similarity score based on https://www.simonwenkel.com/notes/ai/metrics/cosine_distance.html
-	///
-	/// Returns the similarity score.
-	pub fn get_similarity(&self, vec_a: &Vec<f32>, vecs_b: &Vec<Vec<f32>>) -> f32 {
-		vecs_b
-			.iter()
-			.map(|vec_b| {
-				let mut a_dot_b: f32 = 0.0;
-				let mut a_mag: f32 = 0.0;
-				let mut b_mag: f32 = 0.0;
-				let vec_size: usize = cmp::min(vec_a.len(), vec_b.len());
-
-				for i in 0..vec_size as usize {
-					a_dot_b += vec_a[i] * vec_b[i];
-					a_mag += vec_a[i] * vec_a[i];
-					b_mag += vec_b[i] * vec_b[i];
-				}
-
-				let dist: f32 = a_dot_b / (a_mag.sqrt() * b_mag.sqrt());
-
-				return dist;
-			})
-			.max_by(|a, b| a.total_cmp(b))
-			.unwrap_or(0.0)
+		Err(anyhow!("Could not get embedding"))
 	}
 }
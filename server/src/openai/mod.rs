@@ -1,28 +1,43 @@
 // OpenAI API
-pub mod assistant;
+pub mod chat;
 pub mod embeddings;
 
+use anyhow::anyhow;
 use anyhow::Result;
 
-use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use reqwest::StatusCode;
 use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
 use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
+use serde_json::Value;
 use std::time::Duration;
 
-const BASE_URL: &str = "https://api.openai.com/v1";
+pub const BASE_URL: &str = "https://api.openai.com/v1";
 
 /// OpenAI API Handler.
-pub trait OpenAIApi {
+///
+/// Also used for OpenAI-compatible APIs, such as Ollama, llama.cpp server or vLLM.
+#[async_trait]
+pub trait OpenAIApi: Sync {
 	/// Returns the OpenAI API key.
 	fn api_key(&self) -> &String;
 
+	/// Returns the base URL of the API.
+	fn base_url(&self) -> &str {
+		BASE_URL
+	}
+
 	/// Returns the request headers needed to make requests against the OpenAI API.
 	fn headers(&self) -> Result<HeaderMap> {
-		let bearer = format!("Bearer {}", self.api_key());
 		let mut headers = HeaderMap::new();
 		headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
-		headers.insert(AUTHORIZATION, bearer.parse()?);
-		headers.insert(HeaderName::from_static("openai-beta"), HeaderValue::from_static("assistants=v1"));
+
+		// local providers usually don't require an API key
+		if !self.api_key().is_empty() {
+			let bearer = format!("Bearer {}", self.api_key());
+			headers.insert(AUTHORIZATION, bearer.parse()?);
+		}
 
 		Ok(headers)
 	}
@@ -33,4 +48,21 @@ pub trait OpenAIApi {
 		let reqwest_client = reqwest::Client::builder().timeout(Duration::from_secs(30)).build().unwrap();
 		ClientBuilder::new(reqwest_client).with(RetryTransientMiddleware::new_with_policy(retry_policy)).build()
 	}
+
+	/// Returns available LLM models.
+	async fn list_models(&self) -> Result<Vec<String>> {
+		let url = format!("{}/models", self.base_url());
+		let headers = self.headers()?;
+		let client = self.client();
+		let res = client.get(url).headers(headers).send().await?;
+
+		if res.status() == StatusCode::OK {
+			let response_body = res.json::<Value>().await?;
+			let data = response_body.get("data").and_then(|d| d.as_array()).ok_or(anyhow!("Unexpected models response"))?;
+			let models = data.iter().filter_map(|d| d.get("id").and_then(|id| id.as_str()).map(|id| id.to_string())).collect();
+			return Ok(models);
+		}
+
+		Err(anyhow!("Couldn't get models"))
+	}
 }
@@ -0,0 +1,447 @@
+/// Pagination, sorting and field selection for lists of postings.
+use crate::entities::{prelude::*, *};
+use crate::error::{Error, Result};
+use crate::user_postings::UserPosting;
+
+use rocket::form::{self, DataField, FromForm, ValueField};
+use rocket::request::Request;
+use rocket::response::{self, Responder};
+use rocket::serde::json::Json;
+use sea_orm::sea_query::{Expr, SimpleExpr};
+use sea_orm::*;
+use serde_json::Value as JsonValue;
+use std::str::FromStr;
+
+/// Number of postings per page if no limit is provided
+const DEFAULT_LIMIT: u64 = 50;
+
+/// Maximum number of postings per page
+const MAX_LIMIT: u64 = 500;
+
+/// Name of the response header containing the cursor of the next page
+const NEXT_CURSOR_HEADER: &str = "X-Next-Cursor";
+
+/// Sort key of postings without a creation timestamp
+const CREATED_AT_KEY: &str = "coalesce(posting.created_at, timestamptz 'epoch')";
+
+/// Sort key of postings without a match similarity; scores range from -1 to 1, so these are sorted last
+const MATCH_SIMILARITY_KEY: &str = "coalesce(posting_state.match_similarity, -2)";
+
+/// Match similarity of postings without a score, as used in the sort key
+const MISSING_SIMILARITY: f32 = -2.0;
+
+/// Options for paginating, sorting and selecting the fields of postings.
+///
+/// Pages are either requested by `cursor`, which is returned in the `X-Next-Cursor` header of the previous page,
+/// or by `offset`. Without `limit`, 50 postings are returned per page.
+#[derive(Debug, Default, FromForm)]
+pub struct PageOptions {
+	/// Maximum number of postings to return
+	limit: Option<u64>,
+
+	/// Number of postings to skip
+	offset: Option<u64>,
+
+	/// Cursor of the page to return
+	cursor: Option<String>,
+
+	/// Sort order, either `created_at` (default) or `match_similarity`; postings are sorted descending
+	sort: Option<String>,
+
+	/// Comma-separated list of fields to return, e.g. `id,title,seen`; the ID is always returned
+	fields: Option<String>,
+}
+
+impl PageOptions {
+	/// Returns the requested sort order.
+	fn sort(&self) -> Result<SortOrder> {
+		match self.sort.as_deref().map(str::trim) {
+			None | Some("") | Some("created_at") => Ok(SortOrder::CreatedAt),
+			Some("match_similarity") => Ok(SortOrder::MatchSimilarity),
+			Some(sort) => Err(Error::Validation(format!("Invalid sort order {sort}, expected created_at or match_similarity"))),
+		}
+	}
+
+	/// Returns the requested fields, or `None` if all fields should be returned.
+	fn fields(&self) -> Result<Option<Vec<Field>>> {
+		let fields = match self.fields.as_deref().map(str::trim) {
+			None | Some("") => return Ok(None),
+			Some(fields) => fields,
+		};
+
+		let mut columns = vec![Field::Posting(posting::Column::Id)];
+		for field in fields.split(',').map(str::trim).filter(|f| !f.is_empty()) {
+			let column = Field::parse(field).ok_or_else(|| Error::Validation(format!("Invalid field {field}")))?;
+			if !columns.iter().any(|c| c.as_str() == column.as_str()) {
+				columns.push(column);
+			}
+		}
+
+		Ok(Some(columns))
+	}
+
+	/// Returns the maximum number of postings to return.
+	fn limit(&self) -> u64 {
+		self.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT)
+	}
+}
+
+/// Field of a posting that can be selected.
+#[derive(Clone, Copy, Debug)]
+enum Field {
+	/// Column of the posting
+	Posting(posting::Column),
+
+	/// State of the posting for the user, e.g. whether it has been read
+	State(posting_state::Column),
+}
+
+impl Field {
+	/// Returns the field with the name, or `None` if no such field exists.
+	/// Only the state columns that are part of `UserPosting` can be selected.
+	fn parse(name: &str) -> Option<Self> {
+		match posting_state::Column::from_str(name) {
+			Ok(column) if !matches!(column, posting_state::Column::AccountId | posting_state::Column::PostingId) => Some(Field::State(column)),
+			_ => posting::Column::from_str(name).ok().map(Field::Posting),
+		}
+	}
+
+	/// Returns the name of the field.
+	fn as_str(&self) -> &str {
+		match self {
+			Field::Posting(column) => column.as_str(),
+			Field::State(column) => column.as_str(),
+		}
+	}
+}
+
+/// Filters combined with the options for paginating postings, so both can be parsed from the same query string.
+/// Routes can only have a single `<param..>` query parameter.
+#[derive(Debug)]
+pub struct Paginated<T> {
+	/// Filters postings need to match
+	pub filters: T,
+
+	/// Options for paginating, sorting and selecting fields
+	pub page: PageOptions,
+}
+
+#[rocket::async_trait]
+impl<'r, T: FromForm<'r>> FromForm<'r> for Paginated<T> {
+	type Context = (T::Context, <PageOptions as FromForm<'r>>::Context);
+
+	fn init(opts: form::Options) -> Self::Context {
+		(T::init(opts), PageOptions::init(opts))
+	}
+
+	fn push_value(ctxt: &mut Self::Context, field: ValueField<'r>) {
+		T::push_value(&mut ctxt.0, field.clone());
+		PageOptions::push_value(&mut ctxt.1, field);
+	}
+
+	async fn push_data(ctxt: &mut Self::Context, field: DataField<'r, '_>) {
+		T::push_data(&mut ctxt.0, field).await;
+	}
+
+	fn finalize(ctxt: Self::Context) -> form::Result<'r, Self> {
+		match (T::finalize(ctxt.0), PageOptions::finalize(ctxt.1)) {
+			(Ok(filters), Ok(page)) => Ok(Paginated { filters, page }),
+			(filters, page) => {
+				let mut errors = form::Errors::new();
+				errors.extend(filters.err().into_iter().flatten());
+				errors.extend(page.err().into_iter().flatten());
+				Err(errors)
+			}
+		}
+	}
+}
+
+/// Order in which postings are returned.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum SortOrder {
+	/// Most recently created postings first
+	CreatedAt,
+
+	/// Best matching postings first
+	MatchSimilarity,
+}
+
+impl SortOrder {
+	/// Returns the field postings are sorted by.
+	fn field(&self) -> Field {
+		match self {
+			SortOrder::CreatedAt => Field::Posting(posting::Column::CreatedAt),
+			SortOrder::MatchSimilarity => Field::State(posting_state::Column::MatchSimilarity),
+		}
+	}
+
+	/// Returns the SQL expression postings are sorted by; postings without a value are sorted last.
+	fn key(&self) -> &'static str {
+		match self {
+			SortOrder::CreatedAt => CREATED_AT_KEY,
+			SortOrder::MatchSimilarity => MATCH_SIMILARITY_KEY,
+		}
+	}
+}
+
+/// Position after the last posting of a page.
+///
+/// Cursors consist of the sort key and the ID of the last posting, separated by `_`. The sort key is the creation
+/// timestamp in microseconds since the Unix epoch, or the match similarity.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Cursor {
+	/// Position when sorting by creation timestamp
+	CreatedAt(i64, i32),
+
+	/// Position when sorting by match similarity
+	MatchSimilarity(f32, i32),
+}
+
+impl Cursor {
+	/// Parse a cursor returned for a page sorted by `sort`.
+	fn parse(cursor: &str, sort: SortOrder) -> Result<Self> {
+		let invalid = || Error::Validation(format!("Invalid cursor {cursor}"));
+		let (key, id) = cursor.rsplit_once('_').ok_or_else(invalid)?;
+		let id = id.parse::<i32>().map_err(|_| invalid())?;
+
+		match sort {
+			SortOrder::CreatedAt => Ok(Cursor::CreatedAt(key.parse().map_err(|_| invalid())?, id)),
+			SortOrder::MatchSimilarity => Ok(Cursor::MatchSimilarity(key.parse().map_err(|_| invalid())?, id)),
+		}
+	}
+
+	/// Returns the cursor after the `posting`, or `None` if the posting doesn't contain the sort key.
+	fn after(posting: &JsonValue, sort: SortOrder) -> Option<Self> {
+		let id = posting.get("id")?.as_i64()? as i32;
+		let key = posting.get(sort.field().as_str())?;
+
+		match sort {
+			SortOrder::CreatedAt => {
+				let micros = match key.as_str() {
+					Some(created_at) => chrono::DateTime::parse_from_rfc3339(created_at).ok()?.timestamp_micros(),
+					None => 0,
+				};
+				Some(Cursor::CreatedAt(micros, id))
+			}
+			SortOrder::MatchSimilarity => Some(Cursor::MatchSimilarity(key.as_f64().map(|s| s as f32).unwrap_or(MISSING_SIMILARITY), id)),
+		}
+	}
+
+	/// Returns the condition for postings after the cursor.
+	fn condition(&self) -> SimpleExpr {
+		match self {
+			Cursor::CreatedAt(micros, id) => Expr::cust_with_values(
+				format!("({CREATED_AT_KEY}, posting.id) < (timestamptz 'epoch' + $1::bigint * interval '1 microsecond', $2)"),
+				[Value::from(*micros), Value::from(*id)],
+			),
+			Cursor::MatchSimilarity(similarity, id) => Expr::cust_with_values(format!("({MATCH_SIMILARITY_KEY}, posting.id) < ($1::real, $2)"), [Value::from(*similarity), Value::from(*id)]),
+		}
+	}
+}
+
+impl std::fmt::Display for Cursor {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Cursor::CreatedAt(micros, id) => write!(f, "{micros}_{id}"),
+			Cursor::MatchSimilarity(similarity, id) => write!(f, "{similarity}_{id}"),
+		}
+	}
+}
+
+/// Page of postings.
+/// Postings are returned as JSON list; the cursor of the next page is returned in the `X-Next-Cursor` header.
+#[derive(Debug)]
+pub struct Page {
+	/// Postings, only containing the requested fields
+	postings: Vec<JsonValue>,
+
+	/// Cursor of the next page, or `None` if this is the last page
+	next_cursor: Option<String>,
+}
+
+impl<'r> Responder<'r, 'static> for Page {
+	fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+		let mut res = Json(self.postings).respond_to(req)?;
+
+		if let Some(next_cursor) = self.next_cursor {
+			res.set_raw_header(NEXT_CURSOR_HEADER, next_cursor);
+		}
+
+		Ok(res)
+	}
+}
+
+/// Return the page of the postings of a user matching `condition`.
+pub async fn paginate_postings(db: &DatabaseConnection, user_id: i32, condition: Condition, options: &PageOptions) -> Result<Page> {
+	let sort = options.sort()?;
+	let fields = options.fields()?;
+	let limit = options.limit();
+
+	let mut condition = condition.add(posting_state::Column::AccountId.eq(user_id));
+
+	if let Some(cursor) = &options.cursor {
+		condition = condition.add(Cursor::parse(cursor, sort)?.condition());
+	}
+
+	let mut postings: Vec<JsonValue> = match &fields {
+		Some(fields) => {
+			// the sort key is needed for the cursor of the next page
+			let mut selected = fields.clone();
+			if !selected.iter().any(|f| f.as_str() == sort.field().as_str()) {
+				selected.push(sort.field());
+			}
+
+			let mut query = page_query(Posting::find().join(JoinType::InnerJoin, posting::Relation::PostingState.def()), condition, sort, options, limit).select_only();
+			for field in selected {
+				query = match field {
+					Field::Posting(column) => query.column(column),
+					Field::State(column) => query.column(column),
+				};
+			}
+
+			query.into_json().all(db).await?
+		}
+		None => page_query(Posting::find().find_also_related(PostingState), condition, sort, options, limit)
+			.all(db)
+			.await?
+			.into_iter()
+			.filter_map(|(posting, state)| Some(UserPosting::new(posting, state?)))
+			.map(|p| serde_json::to_value(p).unwrap_or_default())
+			.collect(),
+	};
+
+	let mut next_cursor = None;
+	if postings.len() as u64 > limit {
+		postings.truncate(limit as usize);
+		next_cursor = postings.last().and_then(|p| Cursor::after(p, sort)).map(|c| c.to_string());
+	}
+
+	if let Some(fields) = &fields {
+		let names: Vec<String> = fields.iter().map(|f| f.as_str().to_string()).collect();
+		for posting in postings.iter_mut() {
+			if let Some(posting) = posting.as_object_mut() {
+				posting.retain(|name, _| names.contains(name));
+			}
+		}
+	}
+
+	Ok(Page { postings, next_cursor })
+}
+
+/// Apply the condition, sort order, offset and limit of a page to a query.
+/// One more posting than the limit is fetched, to know whether there is a next page.
+fn page_query<Q: QueryFilter + QueryOrder + QuerySelect>(query: Q, condition: Condition, sort: SortOrder, options: &PageOptions, limit: u64) -> Q {
+	let mut query = query.filter(condition).order_by(Expr::cust(sort.key()), Order::Desc).order_by_desc(posting::Column::Id);
+
+	if let Some(offset) = options.offset {
+		query = query.offset(offset);
+	}
+
+	query.limit(limit + 1)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn options(sort: Option<&str>, fields: Option<&str>) -> PageOptions {
+		PageOptions {
+			sort: sort.map(str::to_string),
+			fields: fields.map(str::to_string),
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn cursor_round_trip() {
+		let cursor = Cursor::CreatedAt(1_700_000_000_123_456, 42);
+		assert_eq!(cursor.to_string(), "1700000000123456_42");
+		assert_eq!(Cursor::parse(&cursor.to_string(), SortOrder::CreatedAt).unwrap(), cursor);
+
+		let cursor = Cursor::MatchSimilarity(-0.25, 7);
+		assert_eq!(cursor.to_string(), "-0.25_7");
+		assert_eq!(Cursor::parse(&cursor.to_string(), SortOrder::MatchSimilarity).unwrap(), cursor);
+
+		// postings without a score are sorted last
+		let cursor = Cursor::MatchSimilarity(MISSING_SIMILARITY, 3);
+		assert_eq!(Cursor::parse(&cursor.to_string(), SortOrder::MatchSimilarity).unwrap(), cursor);
+	}
+
+	#[test]
+	fn invalid_cursors() {
+		for cursor in ["", "42", "abc_1", "1_abc", "0.5_1"] {
+			assert!(Cursor::parse(cursor, SortOrder::CreatedAt).is_err(), "{cursor}");
+		}
+
+		for cursor in ["", "0.5", "high_1", "0.5_"] {
+			assert!(Cursor::parse(cursor, SortOrder::MatchSimilarity).is_err(), "{cursor}");
+		}
+	}
+
+	#[test]
+	fn cursor_after_posting() {
+		let posting = serde_json::json!({"id": 5, "created_at": "1970-01-01T00:00:01Z", "match_similarity": 0.5});
+		assert_eq!(Cursor::after(&posting, SortOrder::CreatedAt), Some(Cursor::CreatedAt(1_000_000, 5)));
+		assert_eq!(Cursor::after(&posting, SortOrder::MatchSimilarity), Some(Cursor::MatchSimilarity(0.5, 5)));
+
+		// missing values use the same sort keys as the database
+		let posting = serde_json::json!({"id": 6, "created_at": null, "match_similarity": null});
+		assert_eq!(Cursor::after(&posting, SortOrder::CreatedAt), Some(Cursor::CreatedAt(0, 6)));
+		assert_eq!(Cursor::after(&posting, SortOrder::MatchSimilarity), Some(Cursor::MatchSimilarity(MISSING_SIMILARITY, 6)));
+
+		// the sort key has to be selected
+		assert_eq!(Cursor::after(&serde_json::json!({"id": 7}), SortOrder::CreatedAt), None);
+	}
+
+	#[test]
+	fn sort_orders() {
+		assert_eq!(options(None, None).sort().unwrap(), SortOrder::CreatedAt);
+		assert_eq!(options(Some(" "), None).sort().unwrap(), SortOrder::CreatedAt);
+		assert_eq!(options(Some("created_at"), None).sort().unwrap(), SortOrder::CreatedAt);
+		assert_eq!(options(Some("match_similarity"), None).sort().unwrap(), SortOrder::MatchSimilarity);
+		assert!(options(Some("title"), None).sort().is_err());
+	}
+
+	#[test]
+	fn selected_fields() {
+		assert!(options(None, None).fields().unwrap().is_none());
+
+		let fields: Vec<String> = options(None, Some("title, seen,title,,url"))
+			.fields()
+			.unwrap()
+			.unwrap()
+			.iter()
+			.map(|f| f.as_str().to_string())
+			.collect();
+		assert_eq!(fields, ["id", "title", "seen", "url"]);
+
+		assert!(options(None, Some("title,password")).fields().is_err());
+	}
+
+	#[test]
+	fn parse_fields() {
+		assert!(matches!(Field::parse("title"), Some(Field::Posting(posting::Column::Title))));
+		assert!(matches!(Field::parse("bookmarked"), Some(Field::State(posting_state::Column::Bookmarked))));
+
+		// the IDs of the posting state are not part of user postings
+		assert!(Field::parse("posting_id").is_none());
+		assert!(Field::parse("account_id").is_none());
+		assert!(Field::parse("unknown").is_none());
+	}
+
+	#[test]
+	fn limits() {
+		assert_eq!(options(None, None).limit(), DEFAULT_LIMIT);
+
+		let limit = |limit| {
+			PageOptions {
+				limit: Some(limit),
+				..Default::default()
+			}
+			.limit()
+		};
+		assert_eq!(limit(0), 1);
+		assert_eq!(limit(20), 20);
+		assert_eq!(limit(10_000), MAX_LIMIT);
+	}
+}
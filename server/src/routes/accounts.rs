@@ -0,0 +1,277 @@
+use crate::auth::{generate_token, hash_password, token_hash, User, MIN_PASSWORD_CHARS};
+use crate::entities::{prelude::*, *};
+use crate::error::{Error, Result};
+use crate::openai::chat::CHAT_MODEL;
+use chrono::FixedOffset;
+use std::env;
+
+use crate::pool::Db;
+use rocket::serde::json::Json;
+use rocket::State;
+use sea_orm_rocket::Connection;
+use serde::{Deserialize, Serialize};
+
+use sea_orm::*;
+
+/// Key of the advisory lock that serializes creating accounts, so the first account can't be claimed twice
+const ACCOUNT_SETUP_LOCK: i64 = 7_235_001;
+
+/// Secret that needs to be provided to create the first account.
+///
+/// The secret is read from the `SETUP_SECRET` environment variable. If it isn't set, a random secret is generated
+/// when the server starts and written to the server log.
+pub struct SetupSecret(String);
+
+impl SetupSecret {
+	/// Returns the configured setup secret, or a new random secret.
+	pub fn new() -> Self {
+		match env::var("SETUP_SECRET") {
+			Ok(secret) if !secret.trim().is_empty() => SetupSecret(secret.trim().to_string()),
+			_ => SetupSecret(generate_token()),
+		}
+	}
+
+	/// Returns whether the provided secret matches the setup secret.
+	/// Hashes are compared, so the comparison doesn't reveal how much of the secret matches.
+	fn verify(&self, secret: Option<&str>) -> bool {
+		secret.is_some_and(|secret| token_hash(secret.trim()) == token_hash(&self.0))
+	}
+
+	/// Write the secret to the server log if the first account still needs to be created.
+	pub async fn log_if_in_setup(&self, db: &DatabaseConnection) -> Result<()> {
+		if in_setup(db).await? {
+			if env::var("SETUP_SECRET").is_ok() {
+				warn!("No account has been created yet; the first account can be created with the SETUP_SECRET");
+			} else {
+				warn!("No account has been created yet; the first account can be created with the setup secret {}", self.0);
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// Credentials of a new account.
+#[derive(Debug, Deserialize)]
+pub struct AccountInput {
+	/// Unique name the user signs in with
+	username: String,
+
+	/// Password the user signs in with
+	password: String,
+
+	/// Setup secret; only required to create the first account
+	setup_secret: Option<String>,
+}
+
+/// New password of an account.
+#[derive(Debug, Deserialize)]
+pub struct PasswordInput {
+	/// Password the user signs in with
+	password: String,
+}
+
+/// Whether the first account still needs to be created.
+#[derive(Debug, Serialize)]
+pub struct SetupStatus {
+	/// `true` as long as no account has a password
+	setup: bool,
+}
+
+/// Returns whether no account has a password yet, in which case accounts can be created without authentication.
+async fn in_setup(db: &impl ConnectionTrait) -> Result<bool> {
+	Ok(Account::find().filter(account::Column::PasswordHash.is_not_null()).count(db).await? == 0)
+}
+
+/// Returns the account with the provided ID.
+async fn find_account(db: &DatabaseConnection, id: i32) -> Result<account::Model> {
+	Account::find_by_id(id).one(db).await?.ok_or(Error::NotFound(format!("Account {id} does not exist")))
+}
+
+/// Check that the username isn't empty and isn't used by another account.
+///
+/// Returns the trimmed username.
+async fn validate_username(db: &impl ConnectionTrait, username: &str, id: Option<i32>) -> Result<String> {
+	let username = username.trim().to_string();
+	if username.is_empty() {
+		return Err(Error::Validation("Username must not be empty".to_string()));
+	}
+
+	let mut existing = Account::find().filter(account::Column::Username.eq(username.clone()));
+	if let Some(id) = id {
+		existing = existing.filter(account::Column::Id.ne(id));
+	}
+
+	if existing.one(db).await?.is_some() {
+		return Err(Error::Validation(format!("Username {username} is already taken")));
+	}
+
+	Ok(username)
+}
+
+/// Check that the password is long enough.
+fn validate_password(password: &str) -> Result<()> {
+	if password.chars().count() < MIN_PASSWORD_CHARS {
+		return Err(Error::Validation(format!("Password must have at least {MIN_PASSWORD_CHARS} characters")));
+	}
+
+	Ok(())
+}
+
+/// Set up a new account: create default settings if an API key has been provided via the `API_KEY` environment
+/// variable, and show all open postings of active sources as unread.
+async fn setup_account(db: &impl ConnectionTrait, account_id: i32) -> Result<()> {
+	if let Ok(api_key) = env::var("API_KEY") {
+		let new_settings = settings::ActiveModel {
+			id: NotSet,
+			api_key: Set(Some(api_key)),
+			model: Set(Some(CHAT_MODEL.to_string())),
+			provider: Set(None),
+			base_url: Set(None),
+			embedding_model: Set(None),
+			account_id: Set(account_id),
+		};
+		new_settings.insert(db).await?;
+	}
+
+	db.execute(Statement::from_sql_and_values(
+		DbBackend::Postgres,
+		r#"INSERT INTO posting_state (account_id, posting_id)
+		SELECT $1, posting.id
+		FROM posting
+		JOIN source ON source.id = posting.source_id
+		WHERE source.deleted = false AND posting.closed_at IS NULL
+		ON CONFLICT DO NOTHING"#,
+		[account_id.into()],
+	))
+	.await?;
+
+	Ok(())
+}
+
+/// Return all accounts.
+#[get("/accounts")]
+pub async fn accounts(conn: Connection<'_, Db>, _user: User) -> Result<Json<Vec<account::Model>>> {
+	let db = conn.into_inner();
+
+	Ok(Json(Account::find().order_by_asc(account::Column::Id).all(db).await?))
+}
+
+/// Return whether the first account still needs to be created.
+/// This doesn't require authentication, so that the login page can offer to create the first account.
+#[get("/accounts/setup")]
+pub async fn setup_status(conn: Connection<'_, Db>) -> Result<Json<SetupStatus>> {
+	let db = conn.into_inner();
+
+	Ok(Json(SetupStatus { setup: in_setup(db).await? }))
+}
+
+/// Return the account of the authenticated user.
+#[get("/accounts/me")]
+pub async fn current_account(conn: Connection<'_, Db>, user: User) -> Result<Json<account::Model>> {
+	let db = conn.into_inner();
+
+	Ok(Json(find_account(db, user.id).await?))
+}
+
+/// Create a new account.
+/// As long as no account has a password, the first account can be created with the setup secret instead of
+/// authentication; it takes over the data that existed before accounts were introduced. Afterwards, only signed in users
+/// can create accounts.
+///
+/// Returns the created account.
+#[post("/accounts", data = "<input>")]
+pub async fn add_account(conn: Connection<'_, Db>, user: Option<User>, setup_secret: &State<SetupSecret>, input: Json<AccountInput>) -> Result<Json<account::Model>> {
+	let db = conn.into_inner();
+
+	validate_password(&input.password)?;
+	let password_hash = hash_password(&input.password).await?;
+
+	// accounts are created one at a time, so whether the first account still needs to be created can't change
+	// until the account has been created
+	let txn = db.begin().await?;
+	txn.execute(Statement::from_sql_and_values(DbBackend::Postgres, "SELECT pg_advisory_xact_lock($1)", [ACCOUNT_SETUP_LOCK.into()]))
+		.await?;
+
+	let setup = in_setup(&txn).await?;
+	if setup && !setup_secret.verify(input.setup_secret.as_deref()) {
+		return Err(Error::Unauthorized("Invalid setup secret".to_string()));
+	}
+	if !setup && user.is_none() {
+		return Err(Error::Unauthorized("Authentication required".to_string()));
+	}
+
+	// claim the account that owns the existing data
+	if setup {
+		if let Some(existing_account) = Account::find().filter(account::Column::PasswordHash.is_null()).order_by_asc(account::Column::Id).one(&txn).await? {
+			let username = validate_username(&txn, &input.username, Some(existing_account.id)).await?;
+
+			let mut existing_account: account::ActiveModel = existing_account.into();
+			existing_account.username = Set(username);
+			existing_account.password_hash = Set(Some(password_hash));
+
+			let claimed_account = existing_account.update(&txn).await?;
+			txn.commit().await?;
+
+			return Ok(Json(claimed_account));
+		}
+	}
+
+	let username = validate_username(&txn, &input.username, None).await?;
+	let new_account = account::ActiveModel {
+		id: NotSet,
+		username: Set(username),
+		password_hash: Set(Some(password_hash)),
+		created_at: Set(Some(chrono::offset::Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap()))),
+	};
+	let inserted_account = new_account.insert(&txn).await?;
+	setup_account(&txn, inserted_account.id).await?;
+	txn.commit().await?;
+
+	Ok(Json(inserted_account))
+}
+
+/// Change the password of the authenticated user.
+#[put("/accounts/me/password", data = "<input>")]
+pub async fn update_password(conn: Connection<'_, Db>, user: User, input: Json<PasswordInput>) -> Result<()> {
+	let db = conn.into_inner();
+
+	validate_password(&input.password)?;
+
+	let mut existing_account: account::ActiveModel = find_account(db, user.id).await?.into();
+	existing_account.password_hash = Set(Some(hash_password(&input.password).await?));
+	existing_account.update(db).await?;
+
+	Ok(())
+}
+
+/// Delete the account of the authenticated user, including their posting state, filters and settings.
+/// Sources the user added are kept for the other users. The last account can't be deleted.
+#[delete("/accounts/me")]
+pub async fn delete_account(conn: Connection<'_, Db>, user: User) -> Result<()> {
+	let db = conn.into_inner();
+
+	if Account::find().count(db).await? <= 1 {
+		return Err(Error::Validation("The last account can't be deleted".to_string()));
+	}
+
+	find_account(db, user.id).await?.delete(db).await?;
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn setup_secret_needs_to_match() {
+		let secret = SetupSecret("s3cret".to_string());
+
+		assert!(secret.verify(Some("s3cret")));
+		assert!(secret.verify(Some(" s3cret\n")));
+		assert!(!secret.verify(Some("s3cre")));
+		assert!(!secret.verify(Some("")));
+		assert!(!secret.verify(None));
+	}
+}
@@ -0,0 +1,250 @@
+use crate::auth::User;
+use crate::entities::{prelude::*, *};
+use crate::error::Result;
+
+use crate::pool::Db;
+use atom_syndication as atom;
+use rocket::http::ContentType;
+use rocket::request::{FromRequest, Outcome, Request};
+use sea_orm_rocket::Connection;
+use serde::Deserialize;
+use serde_json::json;
+
+use sea_orm::*;
+
+/// Maximum number of postings included in a feed.
+const FEED_MAX_ITEMS: u64 = 100;
+
+/// Feed title
+const FEED_TITLE: &str = "Jobs Feed";
+
+/// Feed configuration.
+///
+/// Read from the `feeds` section of `Rocket.toml`; missing values fall back to the defaults.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct FeedConfig {
+	/// URL Jobs Feed is publicly reachable at, e.g. `https://jobs.example.com`.
+	/// If not set, the URL is derived from the `Host` and `X-Forwarded-Proto` headers of the request.
+	pub base_url: Option<String>,
+}
+
+/// URL Jobs Feed is reachable at, used for links in feeds; `None` if it can't be determined.
+pub struct BaseUrl(Option<String>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for BaseUrl {
+	type Error = ();
+
+	async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+		if let Some(base_url) = req.rocket().state::<FeedConfig>().and_then(|c| c.base_url.clone()) {
+			return Outcome::Success(BaseUrl(Some(base_url.trim_end_matches('/').to_string())));
+		}
+
+		// the scheme is forwarded by reverse proxies that terminate TLS
+		let scheme = match req.headers().get_one("X-Forwarded-Proto").and_then(|p| p.split(',').next()).map(|p| p.trim()) {
+			Some("https") => "https",
+			_ => "http",
+		};
+
+		Outcome::Success(BaseUrl(req.host().map(|host| format!("{scheme}://{host}"))))
+	}
+}
+
+/// Filters that can be applied to all feeds.
+#[derive(Debug, FromForm)]
+pub struct FeedFilters {
+	/// Only include postings of this source
+	source_id: Option<i32>,
+
+	/// Only include postings with this match state
+	is_match: Option<bool>,
+
+	/// Only include postings with this bookmark state
+	bookmarked: Option<bool>,
+
+	/// Only include postings with at least this match similarity
+	min_similarity: Option<f32>,
+
+	/// Only include postings that are still listed on their source, or that have been closed
+	open: Option<bool>,
+}
+
+/// Posting included in a feed.
+struct FeedItem {
+	/// Posting
+	posting: posting::Model,
+
+	/// Source the posting was found on
+	source: Option<source::Model>,
+
+	/// Link to the posting
+	link: String,
+}
+
+impl FeedItem {
+	/// Returns a globally unique ID of the item.
+	fn id(&self) -> String {
+		format!("urn:jobs-feed:posting:{}", self.posting.id)
+	}
+
+	/// Returns the text content of the item.
+	fn content(&self) -> Option<String> {
+		self.posting.content.clone().or(self.posting.description.clone())
+	}
+
+	/// Returns the categories of the item: the source name and the posting department.
+	fn categories(&self) -> Vec<String> {
+		let source_name = self.source.as_ref().map(|s| s.name.clone());
+		[source_name, self.posting.department.clone()].into_iter().flatten().collect()
+	}
+}
+
+/// Return the postings of the user matching the filters, most recent first.
+async fn feed_items(db: &DatabaseConnection, user_id: i32, filters: &FeedFilters, base_url: &BaseUrl) -> Result<Vec<FeedItem>> {
+	let mut filter_condition = Condition::all().add(source::Column::Deleted.eq(false)).add(posting_state::Column::AccountId.eq(user_id));
+
+	if let Some(source_id) = filters.source_id {
+		filter_condition = filter_condition.add(posting::Column::SourceId.eq(source_id));
+	}
+
+	if let Some(is_match) = filters.is_match {
+		filter_condition = filter_condition.add(posting_state::Column::IsMatch.eq(is_match));
+	}
+
+	if let Some(bookmarked) = filters.bookmarked {
+		filter_condition = filter_condition.add(posting_state::Column::Bookmarked.eq(bookmarked));
+	}
+
+	if let Some(min_similarity) = filters.min_similarity {
+		filter_condition = filter_condition.add(posting_state::Column::MatchSimilarity.gte(min_similarity));
+	}
+
+	if let Some(open) = filters.open {
+		filter_condition = filter_condition.add(if open { posting::Column::ClosedAt.is_null() } else { posting::Column::ClosedAt.is_not_null() });
+	}
+
+	let postings = Posting::find()
+		.find_also_related(Source)
+		.join(JoinType::InnerJoin, posting::Relation::PostingState.def())
+		.filter(filter_condition)
+		.order_by_desc(posting::Column::CreatedAt)
+		.limit(FEED_MAX_ITEMS)
+		.all(db)
+		.await?;
+
+	Ok(postings
+		.into_iter()
+		.map(|(posting, source)| {
+			// link to the posting page in the UI if the posting URL is unknown
+			let link = match (&posting.url, &base_url.0) {
+				(Some(url), _) => url.clone(),
+				(None, Some(base_url)) => format!("{base_url}/posting/{}", posting.id),
+				(None, None) => format!("/posting/{}", posting.id),
+			};
+
+			FeedItem { posting, source, link }
+		})
+		.collect())
+}
+
+/// Return postings of the user as RSS 2.0 feed.
+#[get("/feed.rss?<filters..>")]
+pub async fn rss_feed(conn: Connection<'_, Db>, user: User, base_url: BaseUrl, filters: FeedFilters) -> Result<(ContentType, String)> {
+	let db = conn.into_inner();
+
+	let items: Vec<rss::Item> = feed_items(db, user.id, &filters, &base_url)
+		.await?
+		.into_iter()
+		.map(|item| {
+			rss::ItemBuilder::default()
+				.title(Some(item.posting.title.clone()))
+				.link(Some(item.link.clone()))
+				.description(item.content())
+				.guid(Some(rss::Guid { value: item.id(), permalink: false }))
+				.pub_date(item.posting.created_at.map(|d| d.to_rfc2822()))
+				.categories(item.categories().into_iter().map(|c| rss::CategoryBuilder::default().name(c).build()).collect::<Vec<rss::Category>>())
+				.build()
+		})
+		.collect();
+
+	let channel = rss::ChannelBuilder::default()
+		.title(FEED_TITLE)
+		.link(base_url.0.as_ref().map(|b| format!("{b}/")).unwrap_or_default())
+		.description("Job postings found by Jobs Feed")
+		.items(items)
+		.build();
+
+	Ok((ContentType::new("application", "rss+xml"), channel.to_string()))
+}
+
+/// Return postings of the user as Atom feed.
+#[get("/feed.atom?<filters..>")]
+pub async fn atom_feed(conn: Connection<'_, Db>, user: User, base_url: BaseUrl, filters: FeedFilters) -> Result<(ContentType, String)> {
+	let db = conn.into_inner();
+
+	let items = feed_items(db, user.id, &filters, &base_url).await?;
+	let now = chrono::offset::Utc::now().fixed_offset();
+	let updated = items.iter().filter_map(|i| i.posting.created_at).max().unwrap_or(now);
+
+	let entries: Vec<atom::Entry> = items
+		.into_iter()
+		.map(|item| {
+			atom::EntryBuilder::default()
+				.id(item.id())
+				.title(item.posting.title.clone())
+				.updated(item.posting.created_at.unwrap_or(now))
+				.published(item.posting.created_at)
+				.link(atom::LinkBuilder::default().href(item.link.clone()).rel("alternate").build())
+				.content(item.content().map(|c| atom::ContentBuilder::default().value(Some(c)).content_type(Some("text".to_string())).build()))
+				.categories(item.categories().into_iter().map(|c| atom::CategoryBuilder::default().term(c).build()).collect::<Vec<atom::Category>>())
+				.build()
+		})
+		.collect();
+
+	// entries don't have authors, so the feed needs one
+	let author = atom::PersonBuilder::default().name(FEED_TITLE).uri(base_url.0.as_ref().map(|b| format!("{b}/"))).build();
+
+	let feed = atom::FeedBuilder::default()
+		.id("urn:jobs-feed:postings")
+		.title(FEED_TITLE)
+		.updated(updated)
+		.author(author)
+		.entries(entries)
+		.build();
+
+	Ok((ContentType::new("application", "atom+xml"), feed.to_string()))
+}
+
+/// Return postings of the user as JSON Feed 1.1.
+#[get("/feed.json?<filters..>")]
+pub async fn json_feed(conn: Connection<'_, Db>, user: User, base_url: BaseUrl, filters: FeedFilters) -> Result<(ContentType, String)> {
+	let db = conn.into_inner();
+
+	let items: Vec<serde_json::Value> = feed_items(db, user.id, &filters, &base_url)
+		.await?
+		.into_iter()
+		.map(|item| {
+			json!({
+				"id": item.id(),
+				"url": item.link,
+				"title": item.posting.title,
+				"content_text": item.content().unwrap_or_default(),
+				"date_published": item.posting.created_at.map(|d| d.to_rfc3339()),
+				"tags": item.categories(),
+			})
+		})
+		.collect();
+
+	let mut feed = json!({
+		"version": "https://jsonfeed.org/version/1.1",
+		"title": FEED_TITLE,
+		"items": items,
+	});
+
+	if let Some(base_url) = &base_url.0 {
+		feed["home_page_url"] = json!(format!("{base_url}/"));
+	}
+
+	Ok((ContentType::new("application", "feed+json"), feed.to_string()))
+}
@@ -0,0 +1,180 @@
+use crate::auth::User;
+use crate::entities::{prelude::*, *};
+use crate::error::{Error, Result};
+use chrono::FixedOffset;
+
+use crate::pool::Db;
+use rocket::serde::json::Json;
+use sea_orm_rocket::Connection;
+
+use sea_orm::*;
+
+/// Returns the filter profile of the user with the provided ID.
+async fn find_profile(db: &DatabaseConnection, user_id: i32, id: i32) -> Result<filter_profile::Model> {
+	FilterProfile::find_by_id(id)
+		.filter(filter_profile::Column::AccountId.eq(user_id))
+		.one(db)
+		.await?
+		.ok_or(Error::NotFound(format!("Filter profile {id} does not exist")))
+}
+
+/// Returns the active source with the provided ID.
+async fn find_source(db: &DatabaseConnection, id: i32) -> Result<source::Model> {
+	Source::find_by_id(id)
+		.filter(source::Column::Deleted.eq(false))
+		.one(db)
+		.await?
+		.ok_or(Error::NotFound(format!("Source {id} does not exist")))
+}
+
+/// Check that the profile has a name that isn't used by another profile of the user.
+///
+/// Returns the trimmed name.
+async fn validate_profile(db: &DatabaseConnection, user_id: i32, profile: &filter_profile::Model, id: Option<i32>) -> Result<String> {
+	let name = profile.name.trim().to_string();
+	if name.is_empty() {
+		return Err(Error::Validation("Filter profile name must not be empty".to_string()));
+	}
+
+	let mut existing = FilterProfile::find()
+		.filter(filter_profile::Column::AccountId.eq(user_id))
+		.filter(filter_profile::Column::Name.eq(name.clone()));
+	if let Some(id) = id {
+		existing = existing.filter(filter_profile::Column::Id.ne(id));
+	}
+
+	if existing.one(db).await?.is_some() {
+		return Err(Error::Validation(format!("Filter profile {name} already exists")));
+	}
+
+	Ok(name)
+}
+
+/// Returns all filter profiles of the user.
+#[get("/filter_profiles")]
+pub async fn filter_profiles(conn: Connection<'_, Db>, user: User) -> Result<Json<Vec<filter_profile::Model>>> {
+	let db = conn.into_inner();
+
+	Ok(Json(
+		FilterProfile::find()
+			.filter(filter_profile::Column::AccountId.eq(user.id))
+			.order_by_asc(filter_profile::Column::Id)
+			.all(db)
+			.await?,
+	))
+}
+
+/// Return a specific filter profile.
+#[get("/filter_profiles/<id>")]
+pub async fn filter_profile_by_id(conn: Connection<'_, Db>, user: User, id: i32) -> Result<Json<filter_profile::Model>> {
+	let db = conn.into_inner();
+
+	Ok(Json(find_profile(db, user.id, id).await?))
+}
+
+/// Create a new filter profile.
+/// Filters are added to the profile by setting their `profile_id`.
+///
+/// Returns the created profile.
+#[post("/filter_profiles", data = "<input>")]
+pub async fn add_filter_profile(conn: Connection<'_, Db>, user: User, input: Json<filter_profile::Model>) -> Result<Json<filter_profile::Model>> {
+	let db = conn.into_inner();
+	let name = validate_profile(db, user.id, &input, None).await?;
+
+	let new_profile = filter_profile::ActiveModel {
+		id: NotSet,
+		account_id: Set(user.id),
+		name: Set(name),
+		created_at: Set(Some(chrono::offset::Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap()))),
+	};
+
+	Ok(Json(new_profile.insert(db).await?))
+}
+
+/// Rename an existing filter profile.
+///
+/// Returns the updated profile.
+#[put("/filter_profiles/<id>", data = "<input>")]
+pub async fn update_filter_profile(conn: Connection<'_, Db>, user: User, id: i32, input: Json<filter_profile::Model>) -> Result<Json<filter_profile::Model>> {
+	let db = conn.into_inner();
+	let name = validate_profile(db, user.id, &input, Some(id)).await?;
+
+	let mut existing_profile: filter_profile::ActiveModel = find_profile(db, user.id, id).await?.into();
+	existing_profile.name = Set(name);
+
+	Ok(Json(existing_profile.update(db).await?))
+}
+
+/// Delete a filter profile, including its filters.
+/// The profile is detached from all sources.
+#[delete("/filter_profiles/<id>")]
+pub async fn delete_filter_profile(conn: Connection<'_, Db>, user: User, id: i32) -> Result<()> {
+	let db = conn.into_inner();
+
+	let existing_profile = find_profile(db, user.id, id).await?;
+	existing_profile.delete(db).await?;
+
+	Ok(())
+}
+
+/// Return the active sources the filter profile is attached to.
+#[get("/filter_profiles/<id>/sources")]
+pub async fn filter_profile_sources(conn: Connection<'_, Db>, user: User, id: i32) -> Result<Json<Vec<source::Model>>> {
+	let db = conn.into_inner();
+
+	let profile = find_profile(db, user.id, id).await?;
+	let sources = profile.find_related(Source).filter(source::Column::Deleted.eq(false)).order_by_asc(source::Column::Id).all(db).await?;
+
+	Ok(Json(sources))
+}
+
+/// Return the filter profiles of the user attached to a source.
+#[get("/sources/<id>/filter_profiles")]
+pub async fn source_filter_profiles(conn: Connection<'_, Db>, user: User, id: i32) -> Result<Json<Vec<filter_profile::Model>>> {
+	let db = conn.into_inner();
+
+	let source = find_source(db, id).await?;
+	let profiles = source
+		.find_related(FilterProfile)
+		.filter(filter_profile::Column::AccountId.eq(user.id))
+		.order_by_asc(filter_profile::Column::Id)
+		.all(db)
+		.await?;
+
+	Ok(Json(profiles))
+}
+
+/// Attach a filter profile of the user to a source.
+/// Attaching a profile that is already attached has no effect.
+#[put("/sources/<id>/filter_profiles/<profile_id>")]
+pub async fn attach_filter_profile(conn: Connection<'_, Db>, user: User, id: i32, profile_id: i32) -> Result<()> {
+	let db = conn.into_inner();
+
+	let source = find_source(db, id).await?;
+	let profile = find_profile(db, user.id, profile_id).await?;
+
+	if SourceFilterProfile::find_by_id((source.id, profile.id)).one(db).await?.is_none() {
+		let attachment = source_filter_profile::ActiveModel {
+			source_id: Set(source.id),
+			profile_id: Set(profile.id),
+		};
+		attachment.insert(db).await?;
+	}
+
+	Ok(())
+}
+
+/// Detach a filter profile of the user from a source.
+#[delete("/sources/<id>/filter_profiles/<profile_id>")]
+pub async fn detach_filter_profile(conn: Connection<'_, Db>, user: User, id: i32, profile_id: i32) -> Result<()> {
+	let db = conn.into_inner();
+
+	let profile = find_profile(db, user.id, profile_id).await?;
+	let attachment = SourceFilterProfile::find_by_id((id, profile.id))
+		.one(db)
+		.await?
+		.ok_or(Error::NotFound(format!("Filter profile {profile_id} is not attached to source {id}")))?;
+	attachment.delete(db).await?;
+
+	Ok(())
+}
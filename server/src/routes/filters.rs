@@ -1,47 +1,114 @@
+use crate::auth::User;
 use crate::entities::{prelude::*, *};
+use crate::error::{Error, Result};
+use crate::rules::Rule;
 use chrono::FixedOffset;
 
 use crate::pool::Db;
-use rocket::http::Status;
 use rocket::serde::json::Json;
 use sea_orm_rocket::Connection;
 
 use sea_orm::*;
 
-/// Returns all existing filters.
+/// Returns the filter of the user with the provided ID.
+async fn find_filter(db: &DatabaseConnection, user_id: i32, id: i32) -> Result<filter::Model> {
+	Filter::find_by_id(id)
+		.filter(filter::Column::AccountId.eq(user_id))
+		.one(db)
+		.await?
+		.ok_or(Error::NotFound(format!("Filter {id} does not exist")))
+}
+
+/// Check that the filter has a name and a valid rule.
+///
+/// Returns the parsed rule.
+fn validate_filter(filter: &filter::Model) -> Result<Rule> {
+	if filter.name.trim().is_empty() {
+		return Err(Error::Validation("Filter name must not be empty".to_string()));
+	}
+
+	let rule: Rule = serde_json::from_value(filter.rule.clone()).map_err(|e| Error::Validation(format!("Invalid filter rule: {e}")))?;
+	rule.validate().map_err(Error::Validation)?;
+
+	Ok(rule)
+}
+
+/// Check that the profile the filter is added to exists and belongs to the user.
+async fn validate_profile(db: &DatabaseConnection, user_id: i32, filter: &filter::Model) -> Result<()> {
+	if let Some(profile_id) = filter.profile_id {
+		if FilterProfile::find_by_id(profile_id).filter(filter_profile::Column::AccountId.eq(user_id)).one(db).await?.is_none() {
+			return Err(Error::Validation(format!("Filter profile {profile_id} does not exist")));
+		}
+	}
+
+	Ok(())
+}
+
+/// Returns all filters of the user.
+/// Filters without a `profile_id` apply to all sources.
 #[get("/filters")]
-pub async fn filters(conn: Connection<'_, Db>) -> Result<Json<Vec<filter::Model>>, Status> {
+pub async fn filters(conn: Connection<'_, Db>, user: User) -> Result<Json<Vec<filter::Model>>> {
+	let db = conn.into_inner();
+
+	Ok(Json(Filter::find().filter(filter::Column::AccountId.eq(user.id)).order_by_asc(filter::Column::Id).all(db).await?))
+}
+
+/// Return a specific filter.
+#[get("/filters/<id>")]
+pub async fn filter_by_id(conn: Connection<'_, Db>, user: User, id: i32) -> Result<Json<filter::Model>> {
 	let db = conn.into_inner();
 
-	Ok(Json(Filter::find().all(db).await.expect("Could not retrieve filters").into_iter().collect::<Vec<_>>()))
+	Ok(Json(find_filter(db, user.id, id).await?))
+}
+
+/// Create a new filter, either applying to all sources or belonging to the filter profile with `profile_id`.
+/// The rule of the filter is validated before the filter is stored.
+///
+/// Returns the created filter.
+#[post("/filters", data = "<input>")]
+pub async fn add_filter(conn: Connection<'_, Db>, user: User, input: Json<filter::Model>) -> Result<Json<filter::Model>> {
+	let db = conn.into_inner();
+	let input = input.into_inner();
+	let rule = validate_filter(&input)?;
+	validate_profile(db, user.id, &input).await?;
+
+	let new_filter = filter::ActiveModel {
+		id: NotSet,
+		account_id: Set(user.id),
+		name: Set(input.name.trim().to_string()),
+		created_at: Set(Some(chrono::offset::Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap()))),
+		rule: Set(serde_json::to_value(rule).map_err(|e| Error::Validation(e.to_string()))?),
+		profile_id: Set(input.profile_id),
+	};
+
+	Ok(Json(new_filter.insert(db).await?))
 }
 
-/// Update the filters.
-/// This method is also used to create new filters and delete existing filters
-/// as it will overwrite all the stored filters.
+/// Update the name, rule and profile of an existing filter.
 ///
-/// Returns filters that got created.
-#[put("/filters", data = "<input>")]
-pub async fn update_filters(conn: Connection<'_, Db>, input: Json<Vec<filter::Model>>) -> Result<Json<Vec<filter::Model>>, Status> {
+/// Returns the updated filter.
+#[put("/filters/<id>", data = "<input>")]
+pub async fn update_filter(conn: Connection<'_, Db>, user: User, id: i32, input: Json<filter::Model>) -> Result<Json<filter::Model>> {
 	let db = conn.into_inner();
+	let input = input.into_inner();
+	let rule = validate_filter(&input)?;
+	validate_profile(db, user.id, &input).await?;
+
+	let mut existing_filter: filter::ActiveModel = find_filter(db, user.id, id).await?.into();
+	existing_filter.name = Set(input.name.trim().to_string());
+	existing_filter.rule = Set(serde_json::to_value(rule).map_err(|e| Error::Validation(e.to_string()))?);
+	existing_filter.profile_id = Set(input.profile_id);
+
+	Ok(Json(existing_filter.update(db).await?))
+}
+
+/// Delete a filter.
+#[delete("/filters/<id>")]
+pub async fn delete_filter(conn: Connection<'_, Db>, user: User, id: i32) -> Result<()> {
+	let db = conn.into_inner();
+
+	let existing_filter = find_filter(db, user.id, id).await?;
+	existing_filter.delete(db).await?;
 
-	let txn = db.begin().await.expect("Could not create transaction.");
-	// delete existing filters
-	Filter::delete_many().exec(&txn).await.expect("Could not delete filters.");
-	// and add the filters that were provided in the request
-	let filter_models: Vec<filter::ActiveModel> = input
-		.into_inner()
-		.into_iter()
-		.map(|f| {
-			let mut new_filter: filter::ActiveModel = f.into();
-			new_filter.id = NotSet;
-			new_filter.created_at = Set(Some(chrono::offset::Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap())));
-			new_filter
-		})
-		.collect();
-	Filter::insert_many(filter_models).exec(&txn).await.expect("Could not update filters");
-	txn.commit().await.expect("Cannot commit transaction");
-
-	// return filters that were created
-	Ok(Json(Filter::find().all(db).await.expect("Could not retrieve filters").into_iter().collect::<Vec<_>>()))
+	Ok(())
 }
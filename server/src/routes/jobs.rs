@@ -0,0 +1,36 @@
+use crate::auth::User;
+use crate::entities::{prelude::*, *};
+use crate::error::{Error, Result};
+
+use crate::pool::Db;
+use rocket::serde::json::Json;
+use sea_orm_rocket::Connection;
+
+use sea_orm::*;
+
+/// Return the most recent refresh jobs.
+/// Jobs can be filtered by source and status.
+#[get("/jobs?<source_id>&<status>")]
+pub async fn jobs(conn: Connection<'_, Db>, _user: User, source_id: Option<i32>, status: Option<String>) -> Result<Json<Vec<refresh_job::Model>>> {
+	let db = conn.into_inner();
+
+	let mut query = RefreshJob::find();
+
+	if let Some(source_id) = source_id {
+		query = query.filter(refresh_job::Column::SourceId.eq(source_id));
+	}
+
+	if let Some(status) = status {
+		query = query.filter(refresh_job::Column::Status.eq(status));
+	}
+
+	Ok(Json(query.order_by_desc(refresh_job::Column::CreatedAt).limit(100).all(db).await?))
+}
+
+/// Return a specific refresh job.
+#[get("/jobs/<id>")]
+pub async fn job_by_id(conn: Connection<'_, Db>, _user: User, id: i32) -> Result<Json<refresh_job::Model>> {
+	let db = conn.into_inner();
+
+	Ok(Json(RefreshJob::find_by_id(id).one(db).await?.ok_or(Error::NotFound(format!("Refresh job {id} does not exist")))?))
+}
@@ -1,7 +1,14 @@
 // API routes
 
+pub mod accounts;
+pub mod feeds;
+pub mod filter_profiles;
 pub mod filters;
+pub mod jobs;
 pub mod postings;
+pub mod scoring;
+pub mod sessions;
 pub mod settings;
 pub mod sources;
 pub mod suggestions;
+pub mod tokens;
@@ -1,163 +1,660 @@
+use crate::attributes::{EMPLOYMENT_TYPES, SENIORITIES, WORKPLACES};
+use crate::auth::User;
 use crate::entities::{prelude::*, *};
-use crate::extract::PostingsExtractor;
+use crate::error::{Error, Result};
+use crate::jobs::{JobQueue, JobStatus};
+use crate::llm::embedding_provider;
+use crate::pagination::{paginate_postings, Page, PageOptions, Paginated};
+use crate::routes::settings::find_settings;
+use crate::scoring::{rescore_in_background, rescore_unread};
+use crate::similarity::{nearest_postings, VectorBackend};
+use crate::user_postings::{user_posting, user_postings, UserPosting};
+use crate::util::contains_pattern;
 
-use rocket::http::Status;
+use rocket::response::status;
 use rocket::serde::json::Json;
+use rocket::State;
 
 use crate::pool::Db;
+use sea_orm::prelude::DateTimeWithTimeZone;
 use sea_orm::sea_query::Expr;
-use sea_orm::{entity::*, query::*};
+use sea_orm::{entity::*, query::*, DatabaseConnection, DbBackend, FromQueryResult, Statement};
 use sea_orm_rocket::Connection;
+use serde::{Deserialize, Deserializer, Serialize};
 
-/// Return all postings that have not been seen by the user.
-#[get("/postings/unread")]
-pub async fn unread_postings(conn: Connection<'_, Db>) -> Result<Json<Vec<posting::Model>>, Status> {
+/// Full-text search query parsed from the search input, in the same way web search engines parse queries
+const SEARCH_QUERY: &str = "websearch_to_tsquery('english', $1)";
+
+/// Options for highlighting search terms in snippets
+/// Matching terms are wrapped in control characters, which are replaced by `<mark>` tags once the snippet is escaped
+const SNIPPET_OPTIONS: &str = "StartSel=\u{2}, StopSel=\u{3}, MaxFragments=2, MaxWords=30, MinWords=10";
+
+/// Default number of search results
+const SEARCH_DEFAULT_LIMIT: u64 = 20;
+
+/// Maximum number of search results
+const SEARCH_MAX_LIMIT: u64 = 100;
+
+/// Maximum number of characters of a semantic search query
+const SEMANTIC_QUERY_MAX_CHARS: usize = 2000;
+
+/// Filters that can be combined with search queries.
+#[derive(Debug, FromForm)]
+pub struct SearchFilters {
+	/// Only include postings of this source
+	source_id: Option<i32>,
+
+	/// Only include postings with this bookmark state
+	bookmarked: Option<bool>,
+
+	/// Only include postings with this match state
+	is_match: Option<bool>,
+
+	/// Only include postings created on or after this date (`YYYY-MM-DD`) or timestamp (RFC 3339)
+	from: Option<String>,
+
+	/// Only include postings created on or before this date (`YYYY-MM-DD`), or before this timestamp (RFC 3339)
+	to: Option<String>,
+
+	/// Maximum number of results
+	limit: Option<u64>,
+}
+
+impl SearchFilters {
+	/// Returns the condition postings need to match.
+	fn condition(&self) -> Result<Condition> {
+		let mut filter_condition = Condition::all();
+
+		if let Some(source_id) = self.source_id {
+			filter_condition = filter_condition.add(posting::Column::SourceId.eq(source_id));
+		}
+
+		if let Some(bookmarked) = self.bookmarked {
+			filter_condition = filter_condition.add(posting_state::Column::Bookmarked.eq(bookmarked));
+		}
+
+		if let Some(is_match) = self.is_match {
+			filter_condition = filter_condition.add(posting_state::Column::IsMatch.eq(is_match));
+		}
+
+		if let Some(from) = &self.from {
+			filter_condition = filter_condition.add(posting::Column::CreatedAt.gte(parse_date("from", from, false)?));
+		}
+
+		if let Some(to) = &self.to {
+			filter_condition = filter_condition.add(posting::Column::CreatedAt.lt(parse_date("to", to, true)?));
+		}
+
+		Ok(filter_condition)
+	}
+
+	/// Returns the maximum number of results.
+	fn limit(&self) -> u64 {
+		search_limit(self.limit)
+	}
+}
+
+/// Returns the number of search results to return for the requested `limit`.
+fn search_limit(limit: Option<u64>) -> u64 {
+	limit.unwrap_or(SEARCH_DEFAULT_LIMIT).clamp(1, SEARCH_MAX_LIMIT)
+}
+
+/// Filters for listing postings.
+#[derive(Debug, FromForm)]
+pub struct PostingFilters {
+	/// Only include postings of this source
+	source_id: Option<i32>,
+
+	/// Only include postings based on their `seen` state
+	read: Option<bool>,
+
+	/// Only include postings that are still listed on their source, or that have been closed
+	open: Option<bool>,
+
+	/// Only include postings whose location contains this text
+	location: Option<String>,
+
+	/// Only include postings with this workplace: `remote`, `hybrid` or `onsite`
+	workplace: Option<String>,
+
+	/// Only include postings with this employment type, e.g. `full_time` or `contract`
+	employment_type: Option<String>,
+
+	/// Only include postings with this seniority level, e.g. `senior`
+	seniority: Option<String>,
+
+	/// Only include postings whose department or team contains this text
+	department: Option<String>,
+
+	/// Only include postings whose salary range reaches at least this yearly salary
+	min_salary: Option<i64>,
+
+	/// Only include postings whose salary is in this currency
+	salary_currency: Option<String>,
+
+	/// Only include postings published on or after this date (`YYYY-MM-DD`) or timestamp (RFC 3339)
+	posted_from: Option<String>,
+}
+
+impl PostingFilters {
+	/// Returns the condition postings need to match.
+	fn condition(&self) -> Result<Condition> {
+		let mut filter_condition = Condition::all();
+
+		if let Some(source_id) = self.source_id {
+			filter_condition = filter_condition.add(posting::Column::SourceId.eq(source_id));
+		}
+
+		if let Some(read) = self.read {
+			filter_condition = filter_condition.add(posting_state::Column::Seen.eq(read));
+		}
+
+		if let Some(open) = self.open {
+			filter_condition = filter_condition.add(open_condition(open));
+		}
+
+		if let Some(location) = &self.location {
+			filter_condition = filter_condition.add(Expr::cust_with_values("posting.location ILIKE $1", [contains_pattern(location)]));
+		}
+
+		if let Some(workplace) = &self.workplace {
+			filter_condition = filter_condition.add(posting::Column::Workplace.eq(allowed_value("workplace", workplace, &WORKPLACES)?));
+		}
+
+		if let Some(employment_type) = &self.employment_type {
+			filter_condition = filter_condition.add(posting::Column::EmploymentType.eq(allowed_value("employment_type", employment_type, &EMPLOYMENT_TYPES)?));
+		}
+
+		if let Some(seniority) = &self.seniority {
+			filter_condition = filter_condition.add(posting::Column::Seniority.eq(allowed_value("seniority", seniority, &SENIORITIES)?));
+		}
+
+		if let Some(department) = &self.department {
+			filter_condition = filter_condition.add(Expr::cust_with_values("posting.department ILIKE $1", [contains_pattern(department)]));
+		}
+
+		if let Some(min_salary) = self.min_salary {
+			filter_condition = filter_condition.add(Expr::cust_with_values("coalesce(posting.salary_max, posting.salary_min) >= $1", [min_salary]));
+		}
+
+		if let Some(salary_currency) = &self.salary_currency {
+			filter_condition = filter_condition.add(posting::Column::SalaryCurrency.eq(salary_currency.trim().to_uppercase()));
+		}
+
+		if let Some(posted_from) = &self.posted_from {
+			filter_condition = filter_condition.add(posting::Column::PublishedAt.gte(parse_date("posted_from", posted_from, false)?));
+		}
+
+		Ok(filter_condition)
+	}
+}
+
+/// Returns the lowercase `value` of the filter `name` if it is one of the `allowed` values.
+fn allowed_value(name: &str, value: &str, allowed: &[&str]) -> Result<String> {
+	let value = value.trim().to_lowercase();
+	if allowed.contains(&value.as_str()) {
+		Ok(value)
+	} else {
+		Err(Error::Validation(format!("Invalid {name} {value}, expected one of {}", allowed.join(", "))))
+	}
+}
+
+/// Posting matching a search query.
+#[derive(Debug, Serialize)]
+pub struct SearchResult {
+	/// Matching posting
+	posting: UserPosting,
+
+	/// Relevance of the posting for the query; higher is more relevant
+	rank: f32,
+
+	/// HTML-escaped excerpt of the posting with the matching terms highlighted by `<mark>` tags
+	snippet: String,
+}
+
+/// Posting similar to a search query or another posting.
+#[derive(Debug, Serialize)]
+pub struct SimilarPosting {
+	/// Similar posting
+	posting: UserPosting,
+
+	/// Cosine similarity of the posting embedding; higher is more similar
+	similarity: f32,
+}
+
+/// State of a posting that can be updated by the user.
+#[derive(Debug, Deserialize)]
+pub struct PostingStateInput {
+	/// Whether the posting has been read; unchanged if not provided
+	seen: Option<bool>,
+
+	/// Whether the posting is bookmarked; unchanged if not provided
+	bookmarked: Option<bool>,
+
+	/// Whether the posting is a good match: true = good match, false = not a good match, null = neutral;
+	/// unchanged if not provided
+	#[serde(default, deserialize_with = "explicit_null")]
+	is_match: Option<Option<bool>>,
+}
+
+/// Deserialize a field that can be set to `null`, so a missing field (`None`) can be told apart from `null` (`Some(None)`).
+fn explicit_null<'de, D: Deserializer<'de>, T: Deserialize<'de>>(deserializer: D) -> std::result::Result<Option<Option<T>>, D::Error> {
+	Option::deserialize(deserializer).map(Some)
+}
+
+/// Result of rescoring postings.
+#[derive(Debug, Serialize)]
+pub struct RescoreSummary {
+	/// Number of unread postings that were rescored
+	rescored: usize,
+}
+
+/// Posting counts and refresh outcome of a source.
+#[derive(Debug, FromQueryResult, Serialize)]
+pub struct SourceStats {
+	/// Source ID
+	source_id: i32,
+
+	/// Source name
+	name: String,
+
+	/// Number of postings
+	total: i64,
+
+	/// Number of postings that have not been seen
+	unread: i64,
+
+	/// Number of bookmarked postings
+	bookmarked: i64,
+
+	/// Number of postings marked as a match
+	matched: i64,
+
+	/// Creation timestamp of the most recent posting
+	newest_posting_at: Option<DateTimeWithTimeZone>,
+
+	/// Timestamp of the last refresh
+	refreshed_at: Option<DateTimeWithTimeZone>,
+
+	/// Whether the source couldn't be reached during the last refresh
+	unreachable: Option<bool>,
+
+	/// Status of the last completed refresh job, either `finished` or `failed`
+	last_refresh_status: Option<String>,
+
+	/// Error of the last completed refresh job, if it failed
+	last_refresh_error: Option<String>,
+}
+
+/// Lifecycle of a posting on its source.
+#[derive(Debug, Serialize)]
+pub struct PostingHistory {
+	/// Posting ID
+	posting_id: i32,
+
+	/// Timestamp when the posting was first found on its source
+	first_seen_at: Option<DateTimeWithTimeZone>,
+
+	/// Timestamp of the last refresh the posting was listed on its source
+	last_seen_at: Option<DateTimeWithTimeZone>,
+
+	/// Timestamp when the posting was no longer listed on its source; `None` if the posting is still open
+	closed_at: Option<DateTimeWithTimeZone>,
+
+	/// Lifecycle events, oldest first
+	events: Vec<posting_event::Model>,
+}
+
+/// Return all postings that have not been seen by the user, ordered by creation time descending.
+/// Postings can be paginated and sorted via `page`.
+#[get("/postings/unread?<page..>")]
+pub async fn unread_postings(conn: Connection<'_, Db>, user: User, page: PageOptions) -> Result<Page> {
 	let db = conn.into_inner();
 
-	Ok(Json(
-		Posting::find()
-			.filter(posting::Column::Seen.eq(false))
-			.order_by_desc(posting::Column::CreatedAt)
-			.all(db)
-			.await
-			.expect("Could not retrieve postings")
-			.into_iter()
-			.collect::<Vec<_>>(),
+	paginate_postings(db, user.id, Condition::all().add(posting_state::Column::Seen.eq(false)), &page).await
+}
+
+/// Return all postings that have been bookmarked, ordered by creation time descending.
+/// If `open` has been provided then only return postings based on whether they are still listed on their source.
+/// Postings can be paginated and sorted via `page`.
+#[get("/postings/bookmarked?<open>&<page..>")]
+pub async fn bookmarked_postings(conn: Connection<'_, Db>, user: User, open: Option<bool>, page: PageOptions) -> Result<Page> {
+	let db = conn.into_inner();
+
+	let mut filter_condition = Condition::all().add(posting_state::Column::Bookmarked.eq(true));
+
+	if let Some(open) = open {
+		filter_condition = filter_condition.add(open_condition(open));
+	}
+
+	paginate_postings(db, user.id, filter_condition, &page).await
+}
+
+/// Queue a refresh of all active sources.
+/// Refreshes run in the background; their progress can be checked via the returned jobs.
+///
+/// Returns the refresh job of every source, or the already queued or running job of the source.
+#[post("/postings/refresh_all")]
+pub async fn refresh_all_postings(_user: User, jobs: &State<JobQueue>) -> Result<status::Accepted<Json<Vec<refresh_job::Model>>>> {
+	Ok(status::Accepted(Json(jobs.enqueue_all().await?)))
+}
+
+/// Return posting counts of the user, the newest posting and the outcome of the last refresh of every active source.
+#[get("/postings/stats")]
+pub async fn posting_stats(conn: Connection<'_, Db>, user: User) -> Result<Json<Vec<SourceStats>>> {
+	let db = conn.into_inner();
+
+	let stats = SourceStats::find_by_statement(Statement::from_sql_and_values(
+		DbBackend::Postgres,
+		r#"SELECT
+			source.id AS source_id,
+			source.name,
+			count(posting.id) AS total,
+			count(posting.id) FILTER (WHERE posting_state.seen = false) AS unread,
+			count(posting.id) FILTER (WHERE posting_state.bookmarked = true) AS bookmarked,
+			count(posting.id) FILTER (WHERE posting_state.is_match = true) AS matched,
+			max(posting.created_at) AS newest_posting_at,
+			source.refreshed_at,
+			source.unreachable,
+			last_job.status AS last_refresh_status,
+			last_job.error AS last_refresh_error
+		FROM source
+		LEFT JOIN (posting JOIN posting_state ON posting_state.posting_id = posting.id AND posting_state.account_id = $3) ON posting.source_id = source.id
+		LEFT JOIN LATERAL (
+			SELECT refresh_job.status, refresh_job.error
+			FROM refresh_job
+			WHERE refresh_job.source_id = source.id AND refresh_job.status IN ($1, $2)
+			ORDER BY refresh_job.finished_at DESC NULLS LAST, refresh_job.id DESC
+			LIMIT 1
+		) last_job ON true
+		WHERE source.deleted = false
+		GROUP BY source.id, last_job.status, last_job.error
+		ORDER BY source.name"#,
+		[JobStatus::Finished.as_str().into(), JobStatus::Failed.as_str().into(), user.id.into()],
 	))
+	.all(db)
+	.await?;
+
+	Ok(Json(stats))
+}
+
+/// Recompute the match score of all unread postings from their stored embeddings, using the current scoring model of the user.
+///
+/// Return the number of rescored postings.
+#[post("/postings/rescore")]
+pub async fn rescore_postings(conn: Connection<'_, Db>, user: User, backend: &State<VectorBackend>) -> Result<Json<RescoreSummary>> {
+	let db = conn.into_inner();
+
+	Ok(Json(RescoreSummary {
+		rescored: rescore_unread(db, *backend.inner(), user.id).await?,
+	}))
+}
+
+/// Returns the snippet with HTML special characters escaped and the matching terms wrapped in `<mark>` tags.
+/// Snippets are excerpts of scraped pages, so they can't be returned as HTML without escaping them.
+fn highlight_snippet(snippet: &str) -> String {
+	snippet
+		.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+		.replace('"', "&quot;")
+		.replace('\'', "&#39;")
+		.replace('\u{2}', "<mark>")
+		.replace('\u{3}', "</mark>")
 }
 
-/// Return all postings that have been bookmarked, order by creation time descending.
-#[get("/postings/bookmarked")]
-pub async fn bookmarked_postings(conn: Connection<'_, Db>) -> Result<Json<Vec<posting::Model>>, Status> {
+/// Search postings by their title, description and content.
+/// The query supports the syntax of web search engines, e.g. quoted phrases, `or` and `-` to exclude terms.
+/// Results can be narrowed down by source, bookmark state, match state and creation date.
+///
+/// Return list of matching postings with highlighted snippets, ordered by relevance descending.
+#[get("/postings/search?<q>&<filters..>")]
+pub async fn search_postings(conn: Connection<'_, Db>, user: User, q: &str, filters: SearchFilters) -> Result<Json<Vec<SearchResult>>> {
 	let db = conn.into_inner();
 
+	let query = q.trim().to_string();
+	if query.is_empty() {
+		return Err(Error::Validation("Search query must not be empty".to_string()));
+	}
+
+	let matches: Vec<(i32, f32, String)> = Posting::find()
+		.select_only()
+		.column(posting::Column::Id)
+		.column_as(Expr::cust_with_values(format!("ts_rank(search_vector, {SEARCH_QUERY})"), [query.clone()]), "rank")
+		.column_as(
+			Expr::cust_with_values(
+				format!("ts_headline('english', translate(left(coalesce(content, description, title), 100000), E'\\x02\\x03', ''), {SEARCH_QUERY}, '{SNIPPET_OPTIONS}')"),
+				[query.clone()],
+			),
+			"snippet",
+		)
+		.join(JoinType::InnerJoin, posting::Relation::PostingState.def())
+		.filter(posting_state::Column::AccountId.eq(user.id))
+		.filter(Expr::cust_with_values(format!("search_vector @@ {SEARCH_QUERY}"), [query.clone()]))
+		.filter(filters.condition()?)
+		.order_by_desc(Expr::cust("rank"))
+		.order_by_desc(posting::Column::CreatedAt)
+		.limit(filters.limit())
+		.into_tuple()
+		.all(db)
+		.await?;
+
+	let ids: Vec<i32> = matches.iter().map(|(id, _, _)| *id).collect();
+	let mut postings = user_postings(db, user.id, Condition::all().add(posting::Column::Id.is_in(ids))).await?;
+
+	// keep the order of the matches
 	Ok(Json(
-		Posting::find()
-			.filter(posting::Column::Bookmarked.eq(true))
-			.order_by_desc(posting::Column::CreatedAt)
-			.all(db)
-			.await
-			.expect("Could not retrieve postings")
+		matches
 			.into_iter()
-			.collect::<Vec<_>>(),
+			.filter_map(|(id, rank, snippet)| {
+				let index = postings.iter().position(|p| p.posting.id == id)?;
+				Some(SearchResult {
+					posting: postings.swap_remove(index),
+					rank,
+					snippet: highlight_snippet(&snippet),
+				})
+			})
+			.collect(),
 	))
 }
 
-/// Retrieve new postings from a specific source.
+/// Search postings by meaning rather than by keywords.
+/// The query is turned into an embedding, which is compared to the embeddings of all postings of the user.
 ///
-/// Return unread postings.
-#[get("/postings/refresh?<source_id>")]
-pub async fn refresh_postings(conn: Connection<'_, Db>, source_id: Option<i32>) -> Result<Json<Vec<posting::Model>>, Status> {
+/// Return list of the most similar postings, ordered by similarity descending.
+#[get("/postings/semantic?<q>&<limit>")]
+pub async fn semantic_search_postings(conn: Connection<'_, Db>, user: User, backend: &State<VectorBackend>, q: &str, limit: Option<u64>) -> Result<Json<Vec<SimilarPosting>>> {
 	let db = conn.into_inner();
 
-	let source = Source::find()
-		.filter(source::Column::Deleted.eq(false))
-		.filter(source::Column::Id.eq(source_id))
-		.all(db)
-		.await
-		.expect("Could not retrieve source information");
-	if source.is_empty() {
-		return Ok(Json(vec![]));
+	let query: String = q.trim().chars().take(SEMANTIC_QUERY_MAX_CHARS).collect();
+	if query.is_empty() {
+		return Err(Error::Validation("Search query must not be empty".to_string()));
 	}
 
-	let filters = Filter::find().all(db).await.expect("Could not get filters");
-	let settings = Settings::find().one(db).await.expect("Could not get settings").expect("No settings stored");
+	let settings = find_settings(db, user.id).await?;
+	let vector = embedding_provider(&settings).create(&query).await.map_err(Error::llm)?;
 
-	// start extraction process
-	let mut extractor = PostingsExtractor::new(
-		source.first().unwrap().url.clone(),
-		source.first().unwrap().id,
-		settings,
-		source.first().unwrap().selector.clone(),
-		source.first().unwrap().pagination.clone(),
-		filters,
-		source.first().unwrap().content.clone(),
-	);
-	extractor.extract(db).await.expect("Could not refresh postings");
+	let nearest = nearest_postings(db, *backend.inner(), user.id, &vector, None, search_limit(limit)).await?;
 
-	// save extracted postings to database
-	extractor.save(db).await.expect("Could not cache source content");
+	Ok(Json(similar_postings(db, user.id, nearest).await?))
+}
 
-	Ok(Json(
-		Posting::find()
-			.filter(posting::Column::SourceId.eq(source.first().unwrap().id))
-			.order_by_desc(posting::Column::CreatedAt)
-			.all(db)
-			.await
-			.expect("Could not retrieve postings"),
-	))
+/// Return the postings most similar to a specific posting, based on their embeddings.
+///
+/// Return list of similar postings, ordered by similarity descending.
+#[get("/postings/<id>/similar?<limit>")]
+pub async fn similar_to_posting(conn: Connection<'_, Db>, user: User, backend: &State<VectorBackend>, id: i32, limit: Option<u64>) -> Result<Json<Vec<SimilarPosting>>> {
+	let db = conn.into_inner();
+
+	let posting = user_posting(db, user.id, id).await?.posting;
+	let vector = posting
+		.find_related(Embedding)
+		.one(db)
+		.await?
+		.and_then(|e| e.vector)
+		.ok_or(Error::BadRequest(format!("Posting {id} does not have an embedding")))?;
+
+	let nearest = nearest_postings(db, *backend.inner(), user.id, &vector, Some(id), search_limit(limit)).await?;
+
+	Ok(Json(similar_postings(db, user.id, nearest).await?))
+}
+
+/// Load the postings of the user of the `nearest` posting IDs and similarities, keeping their order.
+async fn similar_postings(db: &DatabaseConnection, user_id: i32, nearest: Vec<(i32, f32)>) -> Result<Vec<SimilarPosting>> {
+	let ids: Vec<i32> = nearest.iter().map(|(id, _)| *id).collect();
+	let mut postings = user_postings(db, user_id, Condition::all().add(posting::Column::Id.is_in(ids))).await?;
+
+	Ok(nearest
+		.into_iter()
+		.filter_map(|(id, similarity)| {
+			let index = postings.iter().position(|p| p.posting.id == id)?;
+			Some(SimilarPosting {
+				posting: postings.swap_remove(index),
+				similarity,
+			})
+		})
+		.collect())
+}
+
+/// Parse a date filter, either a date (`YYYY-MM-DD`) or an RFC 3339 timestamp.
+/// Dates are interpreted as the start of the day in UTC, or as the start of the next day if `end_of_day` is set.
+fn parse_date(name: &str, value: &str, end_of_day: bool) -> Result<DateTimeWithTimeZone> {
+	if let Ok(timestamp) = chrono::DateTime::parse_from_rfc3339(value) {
+		return Ok(timestamp);
+	}
+
+	let date = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| Error::Validation(format!("Invalid {name} date {value}")))?;
+	let date = if end_of_day { date.succ_opt().unwrap_or(date) } else { date };
+
+	Ok(date.and_hms_opt(0, 0, 0).unwrap().and_utc().fixed_offset())
 }
 
 /// Return a specific posting.
 #[get("/postings/<id>")]
-pub async fn posting_by_id(conn: Connection<'_, Db>, id: i32) -> Result<Json<Option<posting::Model>>, Status> {
+pub async fn posting_by_id(conn: Connection<'_, Db>, user: User, id: i32) -> Result<Json<UserPosting>> {
 	let db = conn.into_inner();
 
-	Ok(Json(Posting::find().filter(posting::Column::Id.eq(id)).one(db).await.expect("Could not retrieve posting")))
+	Ok(Json(user_posting(db, user.id, id).await?))
 }
 
-/// Return postings.
-/// If `source_id` has been provided then only return postings associated with that source.
-/// If `read` has been provided then only return postings based on their `seen` state.
+/// Return the postings of other sources that have been identified as duplicates of a specific posting.
 ///
-/// Return list of postings, ordered by creation timestamp descending.
-#[get("/postings?<source_id>&<read>")]
-pub async fn get_postings(conn: Connection<'_, Db>, source_id: Option<i32>, read: Option<bool>) -> Result<Json<Vec<posting::Model>>, Status> {
+/// Return list of duplicates, ordered by creation timestamp ascending.
+#[get("/postings/<id>/duplicates")]
+pub async fn posting_duplicates(conn: Connection<'_, Db>, user: User, id: i32) -> Result<Json<Vec<posting_duplicate::Model>>> {
 	let db = conn.into_inner();
 
-	let mut filter_condition = Condition::all();
+	let posting = user_posting(db, user.id, id).await?.posting;
 
-	if let Some(source_id) = source_id {
-		filter_condition = filter_condition.add(posting::Column::SourceId.eq(source_id));
-	}
+	Ok(Json(posting.find_related(PostingDuplicate).order_by_asc(posting_duplicate::Column::CreatedAt).all(db).await?))
+}
+
+/// Return the lifecycle of a specific posting: when it was first and last seen on its source, when it was closed,
+/// and all lifecycle events.
+#[get("/postings/<id>/history")]
+pub async fn posting_history(conn: Connection<'_, Db>, user: User, id: i32) -> Result<Json<PostingHistory>> {
+	let db = conn.into_inner();
 
-	if let Some(read) = read {
-		filter_condition = filter_condition.add(posting::Column::Seen.eq(read));
+	let posting = user_posting(db, user.id, id).await?.posting;
+	let events = posting
+		.find_related(PostingEvent)
+		.order_by_asc(posting_event::Column::CreatedAt)
+		.order_by_asc(posting_event::Column::Id)
+		.all(db)
+		.await?;
+
+	Ok(Json(PostingHistory {
+		posting_id: posting.id,
+		first_seen_at: posting.created_at,
+		last_seen_at: posting.last_seen_at,
+		closed_at: posting.closed_at,
+		events,
+	}))
+}
+
+/// Returns the condition for postings that are still listed on their source, or that have been closed.
+fn open_condition(open: bool) -> Condition {
+	if open {
+		Condition::all().add(posting::Column::ClosedAt.is_null())
+	} else {
+		Condition::all().add(posting::Column::ClosedAt.is_not_null())
 	}
+}
 
-	Ok(Json(
-		Posting::find()
-			.filter(filter_condition)
-			.order_by_desc(posting::Column::CreatedAt)
-			.all(db)
-			.await
-			.expect("Could not retrieve postings"),
-	))
+/// Return postings matching the `filters`.
+/// Postings can be paginated and sorted via the page options.
+///
+/// Return list of postings, ordered by creation timestamp descending unless sorted otherwise.
+#[get("/postings?<query..>")]
+pub async fn get_postings(conn: Connection<'_, Db>, user: User, query: Paginated<PostingFilters>) -> Result<Page> {
+	let db = conn.into_inner();
+
+	paginate_postings(db, user.id, query.filters.condition()?, &query.page).await
 }
 
-/// Update the `seen` state of a set of postings.
+/// Update the `seen` state of a set of postings of the user.
 /// The request body is expected to contain a list of posting IDs to update.
 #[put("/postings/mark_read", data = "<input>")]
-pub async fn mark_postings_read(conn: Connection<'_, Db>, input: Json<Vec<i32>>) -> Result<(), Status> {
+pub async fn mark_postings_read(conn: Connection<'_, Db>, user: User, input: Json<Vec<i32>>) -> Result<()> {
 	let db = conn.into_inner();
 
-	let _ = Posting::update_many()
-		.col_expr(posting::Column::Seen, Expr::value(true))
-		.filter(posting::Column::Id.is_in(input.into_inner()))
+	PostingState::update_many()
+		.col_expr(posting_state::Column::Seen, Expr::value(true))
+		.filter(posting_state::Column::AccountId.eq(user.id))
+		.filter(posting_state::Column::PostingId.is_in(input.into_inner()))
 		.exec(db)
-		.await
-		.expect("Could not mark posting as read");
+		.await?;
 
 	Ok(())
 }
 
-/// Update a specific posting.
-/// Onlye `seen`, `bookmarked` and `is_match` properties can be updated, any other property update is ignored.
+/// Update the state of a specific posting for the user.
+/// Only `seen`, `bookmarked` and `is_match` properties can be updated, any other property update is ignored.
 ///
 /// Return updated posting.
 #[put("/postings/<id>", data = "<input>")]
-pub async fn update_posting(conn: Connection<'_, Db>, id: i32, input: Json<posting::Model>) -> Result<Json<posting::Model>, Status> {
+pub async fn update_posting(conn: Connection<'_, Db>, user: User, backend: &State<VectorBackend>, id: i32, input: Json<PostingStateInput>) -> Result<Json<UserPosting>> {
 	let db = conn.into_inner();
 
-	let existing_posting = Posting::find_by_id(id).one(db).await.expect("Could not find posting");
-	let mut existing_posting: posting::ActiveModel = existing_posting.unwrap().into();
-	let updated_posting: posting::Model = input.into_inner();
+	let existing_state = PostingState::find_by_id((user.id, id)).one(db).await?.ok_or(Error::NotFound(format!("Posting {id} does not exist")))?;
+	let is_match = input.is_match.unwrap_or(existing_state.is_match);
+	let is_match_changed = existing_state.is_match != is_match;
 
-	existing_posting.seen = Set(updated_posting.seen);
-	existing_posting.bookmarked = Set(updated_posting.bookmarked);
-	existing_posting.is_match = Set(updated_posting.is_match);
+	let mut updated_state: posting_state::ActiveModel = existing_state.clone().into();
+	updated_state.seen = Set(input.seen.unwrap_or(existing_state.seen));
+	updated_state.bookmarked = Set(input.bookmarked.unwrap_or(existing_state.bookmarked));
+	updated_state.is_match = Set(is_match);
+	updated_state.update(db).await?;
 
-	let existing_posting: posting::Model = existing_posting.update(db).await.expect("Could not update posting");
+	// liking or disliking a posting changes which postings are considered a match
+	if is_match_changed {
+		rescore_in_background(db.clone(), *backend.inner(), user.id);
+	}
+
+	Ok(Json(user_posting(db, user.id, id).await?))
+}
 
-	Ok(Json(existing_posting))
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn highlight_snippet_escapes_html() {
+		assert_eq!(
+			highlight_snippet("<img src=x onerror=\"alert('1')\"> \u{2}Rust\u{3} & \u{2}Go\u{3} engineer"),
+			"&lt;img src=x onerror=&quot;alert(&#39;1&#39;)&quot;&gt; <mark>Rust</mark> &amp; <mark>Go</mark> engineer"
+		);
+	}
+
+	#[test]
+	fn posting_state_input_keeps_omitted_fields() {
+		let input: PostingStateInput = serde_json::from_value(serde_json::json!({"seen": true})).unwrap();
+		assert_eq!((input.seen, input.bookmarked, input.is_match), (Some(true), None, None));
+
+		let input: PostingStateInput = serde_json::from_value(serde_json::json!({"is_match": null})).unwrap();
+		assert_eq!(input.is_match, Some(None));
+
+		let input: PostingStateInput = serde_json::from_value(serde_json::json!({"bookmarked": false, "is_match": true})).unwrap();
+		assert_eq!((input.bookmarked, input.is_match), (Some(false), Some(Some(true))));
+	}
 }
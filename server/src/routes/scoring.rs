@@ -0,0 +1,62 @@
+use crate::auth::User;
+use crate::entities::{prelude::*, *};
+use crate::error::Result;
+use crate::scoring::{rescore_unread, train, TrainingReport};
+use crate::similarity::VectorBackend;
+
+use crate::pool::Db;
+use rocket::serde::json::Json;
+use rocket::State;
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm_rocket::Connection;
+use serde::Serialize;
+
+use sea_orm::*;
+
+/// State of the trained scoring model.
+#[derive(Debug, Serialize)]
+pub struct ScoringStatus {
+	/// Timestamp when the model was trained
+	trained_at: Option<DateTimeWithTimeZone>,
+
+	/// Number of liked and disliked postings the model was trained on
+	examples: i32,
+
+	/// Number of liked and disliked postings held out to evaluate the model
+	held_out: i32,
+
+	/// Share of held out postings that were classified correctly
+	accuracy: Option<f32>,
+}
+
+/// Return the state of the scoring model of the user, or `null` if no model has been trained yet.
+#[get("/scoring")]
+pub async fn scoring_status(conn: Connection<'_, Db>, user: User) -> Result<Json<Option<ScoringStatus>>> {
+	let db = conn.into_inner();
+
+	let model = ScoringModel::find()
+		.filter(scoring_model::Column::AccountId.eq(user.id))
+		.order_by_desc(scoring_model::Column::TrainedAt)
+		.one(db)
+		.await?;
+
+	Ok(Json(model.map(|m| ScoringStatus {
+		trained_at: m.trained_at,
+		examples: m.examples,
+		held_out: m.held_out,
+		accuracy: m.accuracy,
+	})))
+}
+
+/// Train the scoring model of the user on all postings the user liked and disliked and rescore their unread postings.
+///
+/// Return the accuracy of the model on held out postings and the number of rescored postings.
+#[post("/scoring/train")]
+pub async fn train_scoring_model(conn: Connection<'_, Db>, user: User, backend: &State<VectorBackend>) -> Result<Json<TrainingReport>> {
+	let db = conn.into_inner();
+
+	let mut report = train(db, user.id).await?;
+	report.rescored = rescore_unread(db, *backend.inner(), user.id).await?;
+
+	Ok(Json(report))
+}
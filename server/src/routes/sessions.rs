@@ -0,0 +1,49 @@
+use crate::auth::{authenticate, create_session, delete_session, SESSION_COOKIE};
+use crate::entities::*;
+use crate::error::{Error, Result};
+
+use crate::pool::Db;
+use rocket::http::CookieJar;
+use rocket::serde::json::Json;
+use sea_orm_rocket::Connection;
+use serde::Deserialize;
+
+/// Credentials to sign in with.
+#[derive(Debug, Deserialize)]
+pub struct LoginInput {
+	/// Username of the account
+	username: String,
+
+	/// Password of the account
+	password: String,
+}
+
+/// Sign in with a username and password.
+/// A session is started and its token is stored in an HTTP-only cookie that authenticates subsequent requests.
+///
+/// Returns the signed in account.
+#[post("/login", data = "<input>")]
+pub async fn login(conn: Connection<'_, Db>, cookies: &CookieJar<'_>, input: Json<LoginInput>) -> Result<Json<account::Model>> {
+	let db = conn.into_inner();
+
+	let account = authenticate(db, &input.username, &input.password)
+		.await?
+		.ok_or(Error::Unauthorized("Invalid username or password".to_string()))?;
+
+	cookies.add(create_session(db, account.id).await?);
+
+	Ok(Json(account))
+}
+
+/// Sign out by ending the current session.
+#[post("/logout")]
+pub async fn logout(conn: Connection<'_, Db>, cookies: &CookieJar<'_>) -> Result<()> {
+	let db = conn.into_inner();
+
+	if let Some(cookie) = cookies.get(SESSION_COOKIE) {
+		delete_session(db, cookie.value()).await?;
+	}
+	cookies.remove(SESSION_COOKIE);
+
+	Ok(())
+}
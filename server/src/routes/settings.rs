@@ -1,59 +1,96 @@
+use crate::auth::User;
 use crate::entities;
 use crate::entities::prelude::*;
-use crate::openai::assistant::{Assistant, AssistantType};
+use crate::error::{Error, Result};
+use crate::llm::{llm_provider, Task};
 
 use crate::pool::Db;
-use rocket::http::Status;
 use rocket::serde::json::Json;
 use sea_orm_rocket::Connection;
 
 use sea_orm::*;
 
-/// Return all stored settings.
+/// Returns the settings of the user, or an error if the user hasn't stored any settings yet.
+pub async fn find_settings(db: &DatabaseConnection, user_id: i32) -> Result<entities::settings::Model> {
+	Settings::find()
+		.filter(entities::settings::Column::AccountId.eq(user_id))
+		.one(db)
+		.await?
+		.ok_or(Error::BadRequest("No settings stored".to_string()))
+}
+
+/// Characters shown in place of the hidden part of the API key
+const API_KEY_MASK: &str = "********";
+
+/// Number of trailing characters of the API key that are shown, so users can tell keys apart
+const API_KEY_VISIBLE_CHARS: usize = 4;
+
+/// Returns the API key with all but its last few characters hidden.
+fn mask_api_key(api_key: &str) -> String {
+	let visible: String = api_key.chars().rev().take(API_KEY_VISIBLE_CHARS).collect::<Vec<_>>().into_iter().rev().collect();
+	format!("{API_KEY_MASK}{visible}")
+}
+
+/// Returns the settings with the API key masked, so that it isn't exposed in responses.
+fn masked_settings(settings: Option<entities::settings::Model>) -> Option<entities::settings::Model> {
+	settings.map(|mut settings| {
+		settings.api_key = settings.api_key.as_deref().map(mask_api_key);
+		settings
+	})
+}
+
+/// Return the settings of the user.
+/// The API key is masked, only its last characters are returned.
 #[get("/settings")]
-pub async fn settings(conn: Connection<'_, Db>) -> Result<Json<Option<entities::settings::Model>>, Status> {
+pub async fn settings(conn: Connection<'_, Db>, user: User) -> Result<Json<Option<entities::settings::Model>>> {
 	let db = conn.into_inner();
 
-	Ok(Json(Settings::find().one(db).await.expect("Could not retrieve settings")))
+	Ok(Json(masked_settings(Settings::find().filter(entities::settings::Column::AccountId.eq(user.id)).one(db).await?)))
 }
 
-/// Update stored settings.
-/// The request body is expected to contain the new setting values.
+/// Update the settings of the user.
+/// The request body is expected to contain the new setting values. If the API key is left masked, the stored key is kept.
 ///
 /// Return updated settings.
 #[put("/settings", data = "<input>")]
-pub async fn update_settings(conn: Connection<'_, Db>, input: Json<entities::settings::Model>) -> Result<Json<Option<entities::settings::Model>>, Status> {
+pub async fn update_settings(conn: Connection<'_, Db>, user: User, input: Json<entities::settings::Model>) -> Result<Json<Option<entities::settings::Model>>> {
 	let db = conn.into_inner();
 
-	let txn = db.begin().await.expect("Could not create transaction.");
+	let txn = db.begin().await?;
+
+	// keep the stored API key if the masked key is sent back unchanged
+	let mut input = input.into_inner();
+	if input.api_key.as_deref().is_some_and(|api_key| api_key.starts_with(API_KEY_MASK)) {
+		let existing_settings = Settings::find().filter(entities::settings::Column::AccountId.eq(user.id)).one(&txn).await?;
+		input.api_key = existing_settings.and_then(|settings| settings.api_key);
+	}
 
 	// delete existing settings and overwrite
-	Settings::delete_many().exec(&txn).await.expect("Could not delete settings.");
-	let new_settings: entities::settings::ActiveModel = input.into_inner().into();
-	Settings::insert(new_settings).exec(&txn).await.expect("Could not update settings");
+	Settings::delete_many().filter(entities::settings::Column::AccountId.eq(user.id)).exec(&txn).await?;
+	let mut new_settings: entities::settings::ActiveModel = input.into();
+	new_settings.id = NotSet;
+	new_settings.account_id = Set(user.id);
+	Settings::insert(new_settings).exec(&txn).await?;
 
-	txn.commit().await.expect("Cannot commit transaction");
+	txn.commit().await?;
 
-	Ok(Json(Settings::find().one(db).await.expect("Could not retrieve settings")))
+	Ok(Json(masked_settings(Settings::find().filter(entities::settings::Column::AccountId.eq(user.id)).one(db).await?)))
 }
 
 /// Get available LLM models.
 ///
 /// Return list of model names.
 #[get("/settings/models")]
-pub async fn get_models(conn: Connection<'_, Db>) -> Result<Json<Vec<String>>, Status> {
+pub async fn get_models(conn: Connection<'_, Db>, user: User) -> Result<Json<Vec<String>>> {
 	let db = conn.into_inner();
-	let settings = Settings::find().one(db).await.expect("Could not retrieve settings");
-
-	if settings.is_none() {
-		return Ok(Json(vec![]));
-	}
+	let settings = match Settings::find().filter(entities::settings::Column::AccountId.eq(user.id)).one(db).await? {
+		Some(settings) => settings,
+		None => return Ok(Json(vec![])),
+	};
 
-	// get models via the OpenAI API
-	let assistant = Assistant::new(&settings.unwrap().api_key.unwrap(), &"".to_string(), AssistantType::JobsFeed)
-		.await
-		.expect("Could not retrieve model information");
-	let models = assistant.get_models().await.expect("Could not retrieve model information");
+	// get models via the configured LLM provider
+	let llm = llm_provider(&settings, Task::JobsFeed);
+	let models = llm.get_models().await.map_err(Error::llm)?;
 
 	Ok(Json(models))
 }
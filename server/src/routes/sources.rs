@@ -1,129 +1,189 @@
+use crate::ats::Ats;
+use crate::auth::User;
 use crate::entities::{prelude::*, *};
+use crate::error::{Error, Result};
 use chrono::FixedOffset;
 
-use crate::openai::assistant::{Assistant, AssistantType};
+use crate::jobs::JobQueue;
+use crate::llm::{llm_provider, Task};
 use crate::pool::Db;
-use rocket::http::Status;
+use crate::routes::settings::find_settings;
+use rocket::response::status;
 use rocket::serde::json::Json;
+use rocket::State;
 use sea_orm_rocket::Connection;
 
 use sea_orm::*;
 
+/// Return the source with the provided `id`, or a not found error if it doesn't exist.
+async fn find_source(db: &DatabaseConnection, id: i32) -> Result<source::Model> {
+	Source::find_by_id(id).one(db).await?.ok_or(Error::NotFound(format!("Source {id} does not exist")))
+}
+
+/// Return the active source with the provided `id` if the user is allowed to change it.
+/// Sources are shared, but only the user that added a source can change it. Sources whose owner has been deleted
+/// can be changed by any user.
+async fn find_owned_source(db: &DatabaseConnection, user: &User, id: i32) -> Result<source::Model> {
+	let source = find_source(db, id).await?;
+	if source.deleted {
+		return Err(Error::NotFound(format!("Source {id} does not exist")));
+	}
+
+	match source.account_id {
+		Some(account_id) if account_id != user.id => Err(Error::Forbidden(format!("Source {id} can only be changed by the user that added it"))),
+		_ => Ok(source),
+	}
+}
+
+/// Check that the required source fields are set.
+fn validate_source(source: &source::Model) -> Result<()> {
+	if source.name.trim().is_empty() {
+		return Err(Error::Validation("Source name must not be empty".to_string()));
+	}
+
+	if source.url.trim().is_empty() {
+		return Err(Error::Validation("Source URL must not be empty".to_string()));
+	}
+
+	if let Some(kind) = &source.kind {
+		if kind != "page" && kind != "feed" && Ats::from_kind(&source.kind).is_none() {
+			return Err(Error::Validation(format!("Unknown source kind {kind}")));
+		}
+	}
+
+	if let Some(fetch_mode) = &source.fetch_mode {
+		if fetch_mode != "browser" && fetch_mode != "http" {
+			return Err(Error::Validation(format!("Unknown fetch mode {fetch_mode}")));
+		}
+	}
+
+	if let Some(interval) = source.refresh_interval {
+		if interval < 0 {
+			return Err(Error::Validation("Refresh interval must not be negative".to_string()));
+		}
+	}
+
+	Ok(())
+}
+
+/// Returns whether the URL looks like it points to an RSS or Atom feed.
+fn is_feed_url(url: &str) -> bool {
+	let path = url.split(['?', '#']).next().unwrap_or("").trim_end_matches('/').to_lowercase();
+	[".rss", ".atom", ".xml", "/feed", "/rss", "/atom"].iter().any(|suffix| path.ends_with(suffix))
+}
+
+/// Returns the kind of the source.
+/// If no kind has been set, it is detected based on the source URL.
+fn source_kind(source: &source::Model) -> String {
+	match &source.kind {
+		Some(kind) => kind.clone(),
+		None if is_feed_url(&source.url) => "feed".to_string(),
+		None => Ats::detect(&source.url).map(|ats| ats.kind()).unwrap_or("page").to_string(),
+	}
+}
+
 /// Return active sources.
 #[get("/sources")]
-pub async fn sources(conn: Connection<'_, Db>) -> Result<Json<Vec<source::Model>>, Status> {
+pub async fn sources(conn: Connection<'_, Db>, _user: User) -> Result<Json<Vec<source::Model>>> {
 	let db = conn.into_inner();
 
-	Ok(Json(
-		Source::find()
-			.filter(source::Column::Deleted.eq(false))
-			.all(db)
-			.await
-			.expect("Could not retrieve sources")
-			.into_iter()
-			.collect::<Vec<_>>(),
-	))
+	Ok(Json(Source::find().filter(source::Column::Deleted.eq(false)).all(db).await?))
 }
 
 /// Add a new source.
+/// Sources are shared by all users; postings are extracted with the settings of the user that added the source.
 /// The request body is expected to have the source information.
 ///
 /// Return newly created source.
 #[post("/sources", data = "<input>")]
-pub async fn add_source(conn: Connection<'_, Db>, input: Json<source::Model>) -> Result<Json<source::Model>, Status> {
+pub async fn add_source(conn: Connection<'_, Db>, user: User, input: Json<source::Model>) -> Result<Json<source::Model>> {
 	let db = conn.into_inner();
+	let input = input.into_inner();
+	validate_source(&input)?;
+	let kind = source_kind(&input);
 
-	let mut new_source: source::ActiveModel = input.into_inner().into();
+	let mut new_source: source::ActiveModel = input.into();
 	new_source.id = NotSet;
+	new_source.account_id = Set(Some(user.id));
+	new_source.kind = Set(Some(kind));
 	new_source.created_at = Set(Some(chrono::offset::Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap())));
-	let inserted_source: source::Model = new_source.insert(db).await.expect("Could not insert source");
+	let inserted_source: source::Model = new_source.insert(db).await?;
 
 	// get similar sources
-	let _ = _refresh_source_suggestions(&db, inserted_source.id).await?;
+	// the source has already been created, so failing to get suggestions is not an error
+	if let Err(e) = _refresh_source_suggestions(db, user.id, inserted_source.id).await {
+		warn!("Could not get suggestions for source {}: {e}", inserted_source.id);
+	}
 
 	Ok(Json(inserted_source))
 }
 
 /// Remove a specific source.
+/// Only the user that added the source can remove it.
 #[delete("/sources/<id>")]
-pub async fn delete_source(conn: Connection<'_, Db>, id: i32) -> Result<(), Status> {
+pub async fn delete_source(conn: Connection<'_, Db>, user: User, id: i32) -> Result<()> {
 	let db = conn.into_inner();
 
-	let source: Option<source::Model> = Source::find_by_id(id).one(db).await.expect("Could not find source");
-	let mut source: source::ActiveModel = source.unwrap().into();
+	let mut source: source::ActiveModel = find_owned_source(db, &user, id).await?.into();
 	// don't actually delete the source from the database, but instead set `deleted` flag
 	// this prevents related postings from being removed
 	source.deleted = Set(true);
 
-	source.update(db).await.expect("Could not delete source");
+	source.update(db).await?;
 
 	Ok(())
 }
 
 /// Return a specific source.
 #[get("/sources/<id>")]
-pub async fn source_by_id(conn: Connection<'_, Db>, id: i32) -> Result<Json<Option<source::Model>>, Status> {
+pub async fn source_by_id(conn: Connection<'_, Db>, _user: User, id: i32) -> Result<Json<source::Model>> {
 	let db = conn.into_inner();
 
-	Ok(Json(Source::find().filter(source::Column::Id.eq(id)).one(db).await.expect("Could not retrieve source")))
+	Ok(Json(find_source(db, id).await?))
 }
 
 /// Return source suggestions that are similar to the source with the provided ID.
 #[get("/sources/<id>/suggestions")]
-pub async fn source_suggestions(conn: Connection<'_, Db>, id: i32) -> Result<Json<Vec<suggestion::Model>>, Status> {
+pub async fn source_suggestions(conn: Connection<'_, Db>, _user: User, id: i32) -> Result<Json<Vec<suggestion::Model>>> {
 	let db = conn.into_inner();
 
-	Ok(Json(
-		Suggestion::find()
-			.filter(suggestion::Column::SourceId.eq(id))
-			.limit(10)
-			.all(db)
-			.await
-			.expect("Could not retrieve suggestions"),
-	))
+	Ok(Json(Suggestion::find().filter(suggestion::Column::SourceId.eq(id)).limit(10).all(db).await?))
 }
 
-/// Get sources that are similar to the source with the provided `id`.
+/// Get sources that are similar to the source with the provided `id`, using the LLM configured by the user.
 ///
 /// Returns the retrieved source suggestions.
-async fn _refresh_source_suggestions(db: &DatabaseConnection, id: i32) -> Result<Json<Vec<suggestion::Model>>, Status> {
+async fn _refresh_source_suggestions(db: &DatabaseConnection, user_id: i32, id: i32) -> Result<Json<Vec<suggestion::Model>>> {
 	// get the source similar suggestions should be determined
-	let source = Source::find().filter(source::Column::Id.eq(id)).one(db).await.expect("Could not retrieve source");
+	let source = find_source(db, id).await?;
 
 	// get a set of existing sources
 	// to prevent duplicate suggestions, these sources will be passed to the LLM to be ignored
-	let sources = Source::find().filter(source::Column::Deleted.eq(false)).limit(50).all(db).await.expect("Could not retrieve sources");
+	let sources = Source::find().filter(source::Column::Deleted.eq(false)).limit(50).all(db).await?;
 
 	// get existing suggestions for source
-	let suggestions = Suggestion::find()
-		.filter(suggestion::Column::SourceId.eq(id))
-		.limit(20)
-		.all(db)
-		.await
-		.expect("Could not retrieve existing suggestions");
+	let suggestions = Suggestion::find().filter(suggestion::Column::SourceId.eq(id)).limit(20).all(db).await?;
 	let mut existing_suggestions: Vec<String> = suggestions.iter().map(|s| s.name.clone()).collect();
 	let existing_sources: Vec<String> = sources.iter().map(|s| s.name.clone()).collect();
 
 	// existing suggestions that should be ignored by the LLM when searching for new suggestions
 	existing_suggestions.extend(existing_sources);
 
-	// create a new assistant instance
-	let settings = Settings::find().one(db).await.expect("Could not retrieve settings").unwrap();
-	let api_key = settings.api_key.unwrap().clone();
-	let model = settings.model.unwrap().clone();
-	let mut assistant = Assistant::new(&api_key, &model, AssistantType::JobsSuggestion).await.expect("Could not retrieve assistant");
+	// create a new LLM provider instance
+	let settings = find_settings(db, user_id).await?;
+	let llm = llm_provider(&settings, Task::JobsSuggestion);
 
 	// create the prompt to get suggestions, and ignore existing ones
-	let source_name = source.unwrap().name;
+	let source_name = source.name;
 	let ignore = existing_suggestions.join(", ");
 	let message = format!(
 		"Company: {source_name}; \
     Ignore career pages of the following companies: {ignore}"
 	);
-	let response = assistant.run(&vec![message]).await.expect("Could not get source suggestions");
+	let response = llm.run(&[message]).await.map_err(Error::llm)?;
 
 	// store the retrieved suggestions
-	let parsed_response: Vec<suggestion::Model> = serde_json::from_str(response.first().expect("Did not get suggestions")).expect("Could not extract suggestions");
+	let parsed_response: Vec<suggestion::Model> = response.into_iter().map(serde_json::from_value).collect::<Result<_, _>>().map_err(Error::llm)?;
 	let active_suggestions: Vec<suggestion::ActiveModel> = parsed_response
 		.into_iter()
 		.map(|res| {
@@ -134,38 +194,41 @@ async fn _refresh_source_suggestions(db: &DatabaseConnection, id: i32) -> Result
 		})
 		.collect();
 
-	let _ = Suggestion::insert_many(active_suggestions).exec(db).await;
+	if !active_suggestions.is_empty() {
+		Suggestion::insert_many(active_suggestions).exec(db).await?;
+	}
 
-	Ok(Json(
-		Suggestion::find()
-			.filter(suggestion::Column::SourceId.eq(id))
-			.limit(10)
-			.all(db)
-			.await
-			.expect("Could not retrieve suggestions"),
-	))
+	Ok(Json(Suggestion::find().filter(suggestion::Column::SourceId.eq(id)).limit(10).all(db).await?))
 }
 
 /// Get new suggestions for a specific source.
 ///
 /// Returns the similar source suggestions.
 #[put("/sources/<id>/suggestions/refresh")]
-pub async fn refresh_source_suggestions(conn: Connection<'_, Db>, id: i32) -> Result<Json<Vec<suggestion::Model>>, Status> {
+pub async fn refresh_source_suggestions(conn: Connection<'_, Db>, user: User, id: i32) -> Result<Json<Vec<suggestion::Model>>> {
 	let db = conn.into_inner();
 
-	_refresh_source_suggestions(&db, id).await
+	_refresh_source_suggestions(db, user.id, id).await
 }
 
 /// Update an existing source.
+/// Only the user that added the source can update it. Updating a source without owner makes the user its owner, so
+/// its postings are extracted with the user's settings.
 ///
 /// Returns the updated source information.
 #[put("/sources/<id>", data = "<input>")]
-pub async fn update_source(conn: Connection<'_, Db>, id: i32, input: Json<source::Model>) -> Result<Json<source::Model>, Status> {
+pub async fn update_source(conn: Connection<'_, Db>, user: User, id: i32, input: Json<source::Model>) -> Result<Json<source::Model>> {
 	let db = conn.into_inner();
 
-	let existing_source = Source::find_by_id(id).one(db).await.expect("Could not find source").unwrap();
+	let existing_source = find_owned_source(db, &user, id).await?;
 	let updated_source: source::Model = input.into_inner();
-	let content_changed = existing_source.url != updated_source.url || existing_source.selector != updated_source.selector || existing_source.pagination != updated_source.pagination;
+	validate_source(&updated_source)?;
+	let kind = source_kind(&updated_source);
+	let content_changed = existing_source.url != updated_source.url
+		|| existing_source.selector != updated_source.selector
+		|| existing_source.pagination != updated_source.pagination
+		|| existing_source.fetch_mode != updated_source.fetch_mode
+		|| existing_source.kind.as_ref() != Some(&kind);
 
 	let mut existing_source_active: source::ActiveModel = existing_source.into();
 
@@ -173,28 +236,48 @@ pub async fn update_source(conn: Connection<'_, Db>, id: i32, input: Json<source
 		existing_source_active.content = Set(Some("".to_string()));
 	}
 
+	existing_source_active.kind = Set(Some(kind));
 	existing_source_active.name = Set(updated_source.name);
 	existing_source_active.url = Set(updated_source.url);
 	existing_source_active.selector = Set(updated_source.selector);
 	existing_source_active.pagination = Set(updated_source.pagination);
 	existing_source_active.favicon = Set(updated_source.favicon);
+	existing_source_active.refresh_interval = Set(updated_source.refresh_interval);
+	existing_source_active.fetch_mode = Set(updated_source.fetch_mode);
+	existing_source_active.account_id = Set(Some(user.id));
 
-	let existing_source: source::Model = existing_source_active.update(db).await.expect("Could not update source");
+	let existing_source: source::Model = existing_source_active.update(db).await?;
 
 	Ok(Json(existing_source))
 }
 
 /// Clear the source's content cache.
 /// The next time postings are refreshed, the source page will be parsed entirely instead of just the source page changes.
+/// Only the user that added the source can clear its cache, as the source is parsed with the user's settings.
 #[put("/sources/<id>/reset")]
-pub async fn reset_source_cache(conn: Connection<'_, Db>, id: i32) -> Result<(), Status> {
+pub async fn reset_source_cache(conn: Connection<'_, Db>, user: User, id: i32) -> Result<()> {
 	let db = conn.into_inner();
 
-	let existing_source = Source::find_by_id(id).one(db).await.expect("Could not find source").unwrap();
-	let mut existing_source_active: source::ActiveModel = existing_source.into();
+	let mut existing_source_active: source::ActiveModel = find_owned_source(db, &user, id).await?.into();
 	existing_source_active.content = Set(Some("".to_string()));
 
-	let _ = existing_source_active.update(db).await.expect("Could not reset source");
+	existing_source_active.update(db).await?;
 
 	Ok(())
 }
+
+/// Queue a refresh of the source's postings.
+/// The refresh runs in the background; its progress can be checked via the returned job.
+///
+/// Returns the refresh job, or the already queued or running job of the source.
+#[post("/sources/<id>/refresh")]
+pub async fn enqueue_source_refresh(conn: Connection<'_, Db>, _user: User, jobs: &State<JobQueue>, id: i32) -> Result<status::Accepted<Json<refresh_job::Model>>> {
+	let db = conn.into_inner();
+
+	let source = find_source(db, id).await?;
+	if source.deleted {
+		return Err(Error::NotFound(format!("Source {id} does not exist")));
+	}
+
+	Ok(status::Accepted(Json(jobs.enqueue(source.id).await?)))
+}
@@ -1,7 +1,8 @@
+use crate::auth::User;
 use crate::entities::{prelude::*, *};
+use crate::error::Result;
 
 use crate::pool::Db;
-use rocket::http::Status;
 use rocket::serde::json::Json;
 use sea_orm_rocket::Connection;
 
@@ -9,10 +10,8 @@ use sea_orm::*;
 
 /// Returns all source suggestions.
 #[get("/suggestions")]
-pub async fn suggestions(conn: Connection<'_, Db>) -> Result<Json<Vec<suggestion::Model>>, Status> {
+pub async fn suggestions(conn: Connection<'_, Db>, _user: User) -> Result<Json<Vec<suggestion::Model>>> {
 	let db = conn.into_inner();
 
-	Ok(Json(
-		Suggestion::find().limit(10).all(db).await.expect("Could not retrieve suggestions").into_iter().collect::<Vec<_>>(),
-	))
+	Ok(Json(Suggestion::find().limit(10).all(db).await?))
 }
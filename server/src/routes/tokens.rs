@@ -0,0 +1,81 @@
+use crate::auth::{generate_token, token_hash, User, API_TOKEN_PREFIX};
+use crate::entities::{prelude::*, *};
+use crate::error::{Error, Result};
+use chrono::FixedOffset;
+
+use crate::pool::Db;
+use rocket::serde::json::Json;
+use sea_orm_rocket::Connection;
+use serde::Serialize;
+
+use sea_orm::*;
+
+/// Newly created API token.
+#[derive(Debug, Serialize)]
+pub struct CreatedApiToken {
+	/// Stored token
+	#[serde(flatten)]
+	api_token: api_token::Model,
+
+	/// Token to authenticate with; only returned when the token is created
+	token: String,
+}
+
+/// Returns the API token of the user with the provided ID.
+async fn find_api_token(db: &DatabaseConnection, user_id: i32, id: i32) -> Result<api_token::Model> {
+	ApiToken::find_by_id(id)
+		.filter(api_token::Column::AccountId.eq(user_id))
+		.one(db)
+		.await?
+		.ok_or(Error::NotFound(format!("API token {id} does not exist")))
+}
+
+/// Returns all API tokens of the user.
+/// The tokens themselves are only returned when they are created.
+#[get("/tokens")]
+pub async fn api_tokens(conn: Connection<'_, Db>, user: User) -> Result<Json<Vec<api_token::Model>>> {
+	let db = conn.into_inner();
+
+	Ok(Json(
+		ApiToken::find().filter(api_token::Column::AccountId.eq(user.id)).order_by_asc(api_token::Column::Id).all(db).await?,
+	))
+}
+
+/// Create a new API token for scripts, which authenticate with the `Authorization: Bearer <token>` header.
+///
+/// Returns the created token.
+#[post("/tokens", data = "<input>")]
+pub async fn add_api_token(conn: Connection<'_, Db>, user: User, input: Json<api_token::Model>) -> Result<Json<CreatedApiToken>> {
+	let db = conn.into_inner();
+
+	let name = input.name.trim().to_string();
+	if name.is_empty() {
+		return Err(Error::Validation("API token name must not be empty".to_string()));
+	}
+
+	let token = format!("{API_TOKEN_PREFIX}{}", generate_token());
+	let new_api_token = api_token::ActiveModel {
+		id: NotSet,
+		account_id: Set(user.id),
+		name: Set(name),
+		token_hash: Set(token_hash(&token)),
+		created_at: Set(Some(chrono::offset::Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap()))),
+		last_used_at: Set(None),
+	};
+
+	Ok(Json(CreatedApiToken {
+		api_token: new_api_token.insert(db).await?,
+		token,
+	}))
+}
+
+/// Revoke an API token.
+#[delete("/tokens/<id>")]
+pub async fn delete_api_token(conn: Connection<'_, Db>, user: User, id: i32) -> Result<()> {
+	let db = conn.into_inner();
+
+	let existing_api_token = find_api_token(db, user.id, id).await?;
+	existing_api_token.delete(db).await?;
+
+	Ok(())
+}
@@ -0,0 +1,810 @@
+/// Typed filter rules that postings are matched against.
+use crate::attributes::{SENIORITIES, WORKPLACES};
+use crate::entities::{filter, filter_profile, posting};
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+/// Maximum nesting depth of `all` and `any` rules
+const MAX_DEPTH: usize = 5;
+
+/// Maximum size of compiled title regular expressions, in bytes
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+/// Rule a posting needs to match.
+///
+/// Rules are stored as JSON objects with a `type`, e.g. `{"type": "keywords", "include": ["rust"], "exclude": []}`,
+/// and can be grouped with `all` and `any`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Rule {
+	/// Posting needs to match all rules
+	All { rules: Vec<Rule> },
+
+	/// Posting needs to match at least one of the rules
+	Any { rules: Vec<Rule> },
+
+	/// Posting needs to mention at least one of the `include` keywords, if any, and none of the `exclude` keywords
+	Keywords {
+		#[serde(default)]
+		include: Vec<Keyword>,
+		#[serde(default)]
+		exclude: Vec<Keyword>,
+	},
+
+	/// Posting title needs to match the regular expression
+	TitleRegex { pattern: TitlePattern },
+
+	/// Posting location needs to contain one of the values
+	Location { values: Vec<String> },
+
+	/// Posting workplace needs to be one of the values: `remote`, `hybrid` or `onsite`
+	Workplace { values: Vec<String> },
+
+	/// Posting salary range needs to reach at least the yearly amount, in the currency if provided
+	MinSalary { amount: i64, currency: Option<String> },
+
+	/// Posting seniority level needs to be one of the values
+	Seniority { values: Vec<String> },
+
+	/// Free-text criteria the LLM checks postings against
+	Criteria { text: String },
+}
+
+/// Keyword of a keyword rule.
+/// The regular expression matching the keyword is compiled once when the rule is parsed.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub struct Keyword {
+	/// Keyword as provided
+	text: String,
+
+	/// Regular expression matching the keyword as a whole word, or `None` if it can't be compiled
+	regex: Option<Regex>,
+}
+
+impl Keyword {
+	/// Returns whether the text mentions the keyword as a whole word, ignoring case.
+	fn is_mentioned(&self, text: &str) -> bool {
+		self.regex.as_ref().is_some_and(|r| r.is_match(text))
+	}
+}
+
+impl From<String> for Keyword {
+	fn from(text: String) -> Self {
+		let regex = keyword_regex(&text).ok();
+		Keyword { text, regex }
+	}
+}
+
+impl From<Keyword> for String {
+	fn from(keyword: Keyword) -> Self {
+		keyword.text
+	}
+}
+
+impl PartialEq for Keyword {
+	fn eq(&self, other: &Self) -> bool {
+		self.text == other.text
+	}
+}
+
+impl fmt::Display for Keyword {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.text)
+	}
+}
+
+/// Regular expression of a title rule.
+/// The pattern is compiled once when the rule is parsed.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub struct TitlePattern {
+	/// Pattern as provided
+	pattern: String,
+
+	/// Compiled regular expression, or the error if the pattern is invalid
+	regex: Result<Regex, regex::Error>,
+}
+
+impl From<String> for TitlePattern {
+	fn from(pattern: String) -> Self {
+		let regex = RegexBuilder::new(&pattern).size_limit(REGEX_SIZE_LIMIT).build();
+		TitlePattern { pattern, regex }
+	}
+}
+
+impl From<TitlePattern> for String {
+	fn from(pattern: TitlePattern) -> Self {
+		pattern.pattern
+	}
+}
+
+impl PartialEq for TitlePattern {
+	fn eq(&self, other: &Self) -> bool {
+		self.pattern == other.pattern
+	}
+}
+
+impl fmt::Display for TitlePattern {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.pattern)
+	}
+}
+
+/// Result of evaluating a rule for a posting.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Verdict {
+	/// Posting matches the rule
+	Match,
+
+	/// Posting doesn't match the rule
+	NoMatch,
+
+	/// Rule can't be evaluated without the LLM, or the posting attributes the rule depends on are unknown
+	Unknown,
+}
+
+impl Verdict {
+	/// Returns the verdict of a posting that needs to match both rules.
+	fn and(self, other: Verdict) -> Verdict {
+		match (self, other) {
+			(Verdict::NoMatch, _) | (_, Verdict::NoMatch) => Verdict::NoMatch,
+			(Verdict::Match, Verdict::Match) => Verdict::Match,
+			_ => Verdict::Unknown,
+		}
+	}
+
+	/// Returns the verdict of a posting that needs to match either rule.
+	fn or(self, other: Verdict) -> Verdict {
+		match (self, other) {
+			(Verdict::Match, _) | (_, Verdict::Match) => Verdict::Match,
+			(Verdict::NoMatch, Verdict::NoMatch) => Verdict::NoMatch,
+			_ => Verdict::Unknown,
+		}
+	}
+
+	/// Returns the verdict for a posting attribute that is `Some(matches)` if the attribute is known.
+	fn from_known(matches: Option<bool>) -> Verdict {
+		match matches {
+			Some(true) => Verdict::Match,
+			Some(false) => Verdict::NoMatch,
+			None => Verdict::Unknown,
+		}
+	}
+}
+
+impl Rule {
+	/// Check that the rule can be evaluated.
+	///
+	/// Returns a description of the first problem found.
+	pub fn validate(&self) -> Result<(), String> {
+		self.validate_at(0)
+	}
+
+	/// Check that the rule, nested at `depth`, can be evaluated.
+	fn validate_at(&self, depth: usize) -> Result<(), String> {
+		match self {
+			Rule::All { rules } | Rule::Any { rules } => {
+				if depth >= MAX_DEPTH {
+					return Err(format!("Rules can't be nested more than {MAX_DEPTH} levels deep"));
+				}
+				if rules.is_empty() {
+					return Err("Rule groups need to contain at least one rule".to_string());
+				}
+				rules.iter().try_for_each(|r| r.validate_at(depth + 1))
+			}
+			Rule::Keywords { include, exclude } => {
+				if include.iter().chain(exclude).any(|k| k.text.trim().is_empty()) {
+					return Err("Keywords must not be empty".to_string());
+				}
+				if let Some(keyword) = include.iter().chain(exclude).find(|k| k.regex.is_none()) {
+					return Err(format!("Invalid keyword {keyword}"));
+				}
+				if include.is_empty() && exclude.is_empty() {
+					return Err("Keyword rules need to include or exclude at least one keyword".to_string());
+				}
+				Ok(())
+			}
+			Rule::TitleRegex { pattern } => pattern.regex.as_ref().map(|_| ()).map_err(|e| format!("Invalid title pattern: {e}")),
+			Rule::Location { values } => non_empty_values("Location", values),
+			Rule::Workplace { values } => allowed_values("workplace", values, &WORKPLACES),
+			Rule::Seniority { values } => allowed_values("seniority", values, &SENIORITIES),
+			Rule::MinSalary { amount, currency } => {
+				if *amount <= 0 {
+					return Err("Minimum salary needs to be positive".to_string());
+				}
+				match currency {
+					Some(c) if c.trim().len() != 3 || !c.trim().chars().all(|c| c.is_ascii_alphabetic()) => Err(format!("Invalid currency {c}, expected an ISO 4217 code")),
+					_ => Ok(()),
+				}
+			}
+			Rule::Criteria { text } => {
+				if text.trim().is_empty() {
+					return Err("Criteria must not be empty".to_string());
+				}
+				Ok(())
+			}
+		}
+	}
+
+	/// Evaluate the rule for a posting without the LLM.
+	/// Details of the posting that haven't been fetched yet, such as its content, are treated as unknown.
+	pub fn evaluate(&self, posting: &posting::Model) -> Verdict {
+		match self {
+			Rule::All { rules } => rules.iter().fold(Verdict::Match, |verdict, r| verdict.and(r.evaluate(posting))),
+			Rule::Any { rules } => rules.iter().fold(Verdict::NoMatch, |verdict, r| verdict.or(r.evaluate(posting))),
+			Rule::Keywords { include, exclude } => {
+				let text = [Some(&posting.title), posting.description.as_ref(), posting.content.as_ref()]
+					.into_iter()
+					.flatten()
+					.map(String::as_str)
+					.collect::<Vec<&str>>()
+					.join("\n");
+				let details_known = posting.description.is_some() || posting.content.is_some();
+				let mentions = |keywords: &[Keyword]| keywords.iter().any(|k| k.is_mentioned(&text));
+
+				let included = if include.is_empty() || mentions(include) {
+					Verdict::Match
+				} else if details_known {
+					Verdict::NoMatch
+				} else {
+					Verdict::Unknown
+				};
+
+				let excluded = if mentions(exclude) {
+					Verdict::NoMatch
+				} else if details_known {
+					Verdict::Match
+				} else {
+					Verdict::Unknown
+				};
+
+				included.and(excluded)
+			}
+			Rule::TitleRegex { pattern } => Verdict::from_known(pattern.regex.as_ref().ok().map(|r| r.is_match(&posting.title))),
+			Rule::Location { values } => Verdict::from_known(posting.location.as_ref().map(|l| {
+				let location = l.to_lowercase();
+				values.iter().any(|v| location.contains(&v.trim().to_lowercase()))
+			})),
+			Rule::Workplace { values } => Verdict::from_known(posting.workplace.as_ref().map(|w| values.iter().any(|v| v.trim().eq_ignore_ascii_case(w)))),
+			Rule::Seniority { values } => Verdict::from_known(posting.seniority.as_ref().map(|s| values.iter().any(|v| v.trim().eq_ignore_ascii_case(s)))),
+			Rule::MinSalary { amount, currency } => {
+				let salary = posting.salary_max.or(posting.salary_min);
+				let same_currency = match (currency, &posting.salary_currency) {
+					(Some(currency), Some(salary_currency)) => currency.trim().eq_ignore_ascii_case(salary_currency),
+					// salaries in unknown currencies can't be compared
+					(Some(_), None) => false,
+					(None, _) => true,
+				};
+
+				match salary {
+					Some(salary) if same_currency => Verdict::from_known(Some(salary >= *amount)),
+					_ => Verdict::Unknown,
+				}
+			}
+			Rule::Criteria { .. } => Verdict::Unknown,
+		}
+	}
+
+	/// Returns whether the rule contains criteria that need to be checked by the LLM.
+	fn has_criteria(&self) -> bool {
+		match self {
+			Rule::All { rules } | Rule::Any { rules } => rules.iter().any(|r| r.has_criteria()),
+			Rule::Criteria { .. } => true,
+			_ => false,
+		}
+	}
+
+	/// Returns a description of the rule that can be passed to the LLM.
+	fn describe(&self) -> String {
+		match self {
+			Rule::All { rules } => rules.iter().map(|r| format!("({})", r.describe())).collect::<Vec<String>>().join(" and "),
+			Rule::Any { rules } => rules.iter().map(|r| format!("({})", r.describe())).collect::<Vec<String>>().join(" or "),
+			Rule::Keywords { include, exclude } => {
+				let mut description = vec![];
+				if !include.is_empty() {
+					description.push(format!("mentions any of {}", join(include)));
+				}
+				if !exclude.is_empty() {
+					description.push(format!("mentions none of {}", join(exclude)));
+				}
+				description.join(" and ")
+			}
+			Rule::TitleRegex { pattern } => format!("title matches the regular expression {pattern}"),
+			Rule::Location { values } => format!("located in any of {}", values.join(", ")),
+			Rule::Workplace { values } => format!("workplace is any of {}", values.join(", ")),
+			Rule::Seniority { values } => format!("seniority is any of {}", values.join(", ")),
+			Rule::MinSalary { amount, currency } => format!("yearly salary of at least {amount} {}", currency.as_deref().unwrap_or_default()).trim().to_string(),
+			Rule::Criteria { text } => text.clone(),
+		}
+	}
+}
+
+/// Compile a regular expression matching the keyword as a whole word, ignoring case.
+/// Word boundaries are only required next to word characters, so keywords such as `C++`, `C#` or `.NET` match as well.
+fn keyword_regex(keyword: &str) -> Result<Regex, regex::Error> {
+	let keyword = keyword.trim();
+	let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+	let start = if keyword.starts_with(is_word_char) { r"\b" } else { "" };
+	let end = if keyword.ends_with(is_word_char) { r"\b" } else { "" };
+
+	RegexBuilder::new(&format!("{start}{}{end}", regex::escape(keyword)))
+		.case_insensitive(true)
+		.size_limit(REGEX_SIZE_LIMIT)
+		.build()
+}
+
+/// Returns the values separated by commas.
+fn join<T: fmt::Display>(values: &[T]) -> String {
+	values.iter().map(|v| v.to_string()).collect::<Vec<String>>().join(", ")
+}
+
+/// Check that all values are non-empty.
+fn non_empty_values(name: &str, values: &[String]) -> Result<(), String> {
+	if values.is_empty() || values.iter().any(|v| v.trim().is_empty()) {
+		return Err(format!("{name} rules need at least one value, and values must not be empty"));
+	}
+	Ok(())
+}
+
+/// Check that all values are one of the `allowed` values.
+fn allowed_values(name: &str, values: &[String], allowed: &[&str]) -> Result<(), String> {
+	if values.is_empty() {
+		return Err(format!("The {name} rule needs at least one value"));
+	}
+	match values.iter().find(|v| !allowed.contains(&v.trim().to_lowercase().as_str())) {
+		Some(value) => Err(format!("Invalid {name} {value}, expected one of {}", allowed.join(", "))),
+		None => Ok(()),
+	}
+}
+
+/// Rules of the filters that apply to a source; postings need to match the rules of every filter.
+#[derive(Clone, Debug, Default)]
+pub struct FilterRules {
+	/// Filter names and their rules
+	rules: Vec<(String, Rule)>,
+}
+
+impl FilterRules {
+	/// Create the filter rules of a source from stored filters and the filter profiles attached to the source.
+	///
+	/// Filters without a profile apply to all sources. If multiple profiles are attached, postings need to match the
+	/// filters of at least one of the profiles. Filters of profiles that aren't attached are ignored and rules that
+	/// can't be parsed are skipped.
+	pub fn from_filters(filters: &[filter::Model], profiles: &[filter_profile::Model]) -> Self {
+		let mut rules: Vec<(String, Rule)> = filters.iter().filter(|f| f.profile_id.is_none()).filter_map(parse_filter).collect();
+
+		let profile_rules = profiles
+			.iter()
+			.map(|p| (p.name.clone(), filters.iter().filter(|f| f.profile_id == Some(p.id)).filter_map(parse_filter).collect()))
+			.collect();
+		rules.extend(any_group(profile_rules));
+
+		FilterRules { rules }
+	}
+
+	/// Evaluate the rules of all filters for a posting, given the verdict of the LLM on the `criteria`.
+	/// Filters containing criteria are only decided by the LLM if they can't be evaluated without it.
+	pub fn evaluate_with_criteria(&self, posting: &posting::Model, criteria: Verdict) -> Verdict {
+		self.rules.iter().fold(Verdict::Match, |verdict, (_, rule)| {
+			let rule_verdict = match rule.evaluate(posting) {
+				Verdict::Unknown if rule.has_criteria() => criteria,
+				rule_verdict => rule_verdict,
+			};
+			verdict.and(rule_verdict)
+		})
+	}
+
+	/// Returns the criteria postings are checked against by the LLM, one filter per line.
+	/// Only filters that contain criteria are passed to the LLM; all other rules are evaluated without the LLM.
+	pub fn criteria(&self) -> String {
+		self.rules
+			.iter()
+			.filter(|(_, rule)| rule.has_criteria())
+			.map(|(name, rule)| format!("- {name}: {}", rule.describe()))
+			.collect::<Vec<String>>()
+			.join("\n")
+	}
+}
+
+/// Rules of the filters of every account for a source.
+/// Postings are extracted once for all accounts, but only stored for the accounts whose rules they match.
+///
+/// The criteria of all accounts are checked by the LLM at once. Accounts are never named in the prompt; instead,
+/// the criteria of each account are numbered, and the LLM returns the numbers of the criteria a posting matches.
+#[derive(Clone, Debug, Default)]
+pub struct AccountFilterRules {
+	/// Account IDs and the rules of their filters
+	accounts: Vec<(i32, FilterRules)>,
+
+	/// IDs of the accounts whose criteria postings match according to the LLM, by posting title
+	criteria_matches: HashMap<String, BTreeSet<i32>>,
+}
+
+impl AccountFilterRules {
+	/// Create the filter rules from the rules of every account.
+	pub fn new(accounts: Vec<(i32, FilterRules)>) -> Self {
+		AccountFilterRules {
+			accounts,
+			criteria_matches: HashMap::new(),
+		}
+	}
+
+	/// Returns whether the posting doesn't match the rules of any account.
+	/// Criteria are only evaluated once the LLM checked the posting.
+	pub fn excludes(&self, posting: &posting::Model) -> bool {
+		self.matching_accounts(posting).is_empty()
+	}
+
+	/// Returns whether any account has criteria that need to be checked by the LLM.
+	pub fn has_criteria(&self) -> bool {
+		!self.criteria_groups().is_empty()
+	}
+
+	/// Returns the numbered criteria postings are checked against by the LLM.
+	/// Accounts with the same criteria share a number.
+	pub fn criteria(&self) -> String {
+		self.criteria_groups()
+			.iter()
+			.enumerate()
+			.map(|(i, (criteria, _))| format!("{}.\n{criteria}", i + 1))
+			.collect::<Vec<String>>()
+			.join("\n")
+	}
+
+	/// Record the `numbers` of the criteria the LLM found the posting with the `title` to match.
+	/// Postings that have been checked don't match the criteria of any other account.
+	pub fn record_criteria(&mut self, title: &str, numbers: &[usize]) {
+		let groups = self.criteria_groups();
+		let account_ids = numbers.iter().filter_map(|n| groups.get(n.checked_sub(1)?)).flat_map(|(_, account_ids)| account_ids.iter().copied());
+
+		self.criteria_matches.entry(title.to_string()).or_default().extend(account_ids);
+	}
+
+	/// Returns the IDs of all accounts.
+	pub fn account_ids(&self) -> Vec<i32> {
+		self.accounts.iter().map(|(id, _)| *id).collect()
+	}
+
+	/// Returns the IDs of the accounts whose rules don't exclude the posting, including the criteria checked by the LLM.
+	pub fn matching_accounts(&self, posting: &posting::Model) -> Vec<i32> {
+		let matches = self.criteria_matches.get(&posting.title);
+
+		self.accounts
+			.iter()
+			.filter(|(id, rules)| {
+				let criteria = Verdict::from_known(matches.map(|account_ids| account_ids.contains(id)));
+				rules.evaluate_with_criteria(posting, criteria) != Verdict::NoMatch
+			})
+			.map(|(id, _)| *id)
+			.collect()
+	}
+
+	/// Returns the distinct criteria of all accounts, and the IDs of the accounts with these criteria.
+	fn criteria_groups(&self) -> Vec<(String, Vec<i32>)> {
+		let mut groups: Vec<(String, Vec<i32>)> = vec![];
+
+		for (id, rules) in &self.accounts {
+			let criteria = rules.criteria();
+			if criteria.is_empty() {
+				continue;
+			}
+
+			match groups.iter_mut().find(|(c, _)| *c == criteria) {
+				Some((_, account_ids)) => account_ids.push(*id),
+				None => groups.push((criteria, vec![*id])),
+			}
+		}
+
+		groups
+	}
+}
+
+/// Combine named groups of rules, so postings need to match the rules of at least one of the groups.
+/// A group without rules matches all postings, so no rules are returned if any group is empty.
+fn any_group(groups: Vec<(String, Vec<(String, Rule)>)>) -> Vec<(String, Rule)> {
+	if groups.iter().any(|(_, rules)| rules.is_empty()) {
+		return vec![];
+	}
+
+	if groups.len() <= 1 {
+		return groups.into_iter().flat_map(|(_, rules)| rules).collect();
+	}
+
+	let name = groups.iter().map(|(name, _)| name.as_str()).collect::<Vec<&str>>().join(" or ");
+	let any = Rule::Any {
+		rules: groups
+			.into_iter()
+			.map(|(_, rules)| Rule::All {
+				rules: rules.into_iter().map(|(_, rule)| rule).collect(),
+			})
+			.collect(),
+	};
+
+	vec![(name, any)]
+}
+
+/// Returns the name and rule of a stored filter, or `None` if its rule can't be parsed.
+fn parse_filter(filter: &filter::Model) -> Option<(String, Rule)> {
+	match serde_json::from_value::<Rule>(filter.rule.clone()) {
+		Ok(rule) => Some((filter.name.clone(), rule)),
+		Err(e) => {
+			warn!("Skipping filter {} with invalid rule: {e}", filter.id);
+			None
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	fn rule(value: serde_json::Value) -> Rule {
+		serde_json::from_value(value).unwrap()
+	}
+
+	fn posting(value: serde_json::Value) -> posting::Model {
+		serde_json::from_value(value).unwrap()
+	}
+
+	#[test]
+	fn verdict_and() {
+		use Verdict::*;
+		assert_eq!(Match.and(Match), Match);
+		assert_eq!(Match.and(Unknown), Unknown);
+		assert_eq!(Unknown.and(Unknown), Unknown);
+		assert_eq!(Unknown.and(NoMatch), NoMatch);
+		assert_eq!(Match.and(NoMatch), NoMatch);
+	}
+
+	#[test]
+	fn verdict_or() {
+		use Verdict::*;
+		assert_eq!(NoMatch.or(NoMatch), NoMatch);
+		assert_eq!(NoMatch.or(Unknown), Unknown);
+		assert_eq!(Unknown.or(Unknown), Unknown);
+		assert_eq!(Unknown.or(Match), Match);
+		assert_eq!(NoMatch.or(Match), Match);
+	}
+
+	#[test]
+	fn verdict_from_known() {
+		assert_eq!(Verdict::from_known(Some(true)), Verdict::Match);
+		assert_eq!(Verdict::from_known(Some(false)), Verdict::NoMatch);
+		assert_eq!(Verdict::from_known(None), Verdict::Unknown);
+	}
+
+	#[test]
+	fn keywords_match_whole_words() {
+		let rust = rule(json!({"type": "keywords", "include": ["Rust"]}));
+		assert_eq!(rust.evaluate(&posting(json!({"title": "Engineer", "content": "We use rust and Go"}))), Verdict::Match);
+		assert_eq!(rust.evaluate(&posting(json!({"title": "Engineer", "content": "A trusted team"}))), Verdict::NoMatch);
+	}
+
+	#[test]
+	fn keywords_with_symbols() {
+		let cases = [
+			("C++", "Modern C++ developer", true),
+			("C++", "C developer", false),
+			("C#", "Backend (C#, SQL)", true),
+			("C#", "Backend (C, SQL)", false),
+			(".NET", "Experience with .NET Core", true),
+			(".NET", "Experience with ASP.NET", true),
+			(".NET", "Visit example.network", false),
+			("node.js", "Node.js services", true),
+		];
+
+		for (keyword, content, matches) in cases {
+			let rule = rule(json!({"type": "keywords", "include": [keyword]}));
+			let expected = if matches { Verdict::Match } else { Verdict::NoMatch };
+			assert_eq!(rule.evaluate(&posting(json!({"title": "Developer", "content": content}))), expected, "{keyword} in {content}");
+		}
+	}
+
+	#[test]
+	fn keywords_without_details() {
+		let rule = rule(json!({"type": "keywords", "include": ["rust"], "exclude": ["php"]}));
+
+		// the keywords might still be mentioned in the content that hasn't been fetched yet
+		assert_eq!(rule.evaluate(&posting(json!({"title": "Engineer"}))), Verdict::Unknown);
+		assert_eq!(rule.evaluate(&posting(json!({"title": "Rust Engineer"}))), Verdict::Unknown);
+		assert_eq!(rule.evaluate(&posting(json!({"title": "PHP Engineer"}))), Verdict::NoMatch);
+		assert_eq!(rule.evaluate(&posting(json!({"title": "Rust Engineer", "description": "No PHP"}))), Verdict::NoMatch);
+		assert_eq!(rule.evaluate(&posting(json!({"title": "Rust Engineer", "description": "Backend"}))), Verdict::Match);
+	}
+
+	#[test]
+	fn attribute_rules() {
+		let remote = posting(json!({"title": "Engineer", "location": "Berlin, Germany", "workplace": "remote", "seniority": "senior"}));
+		let unknown = posting(json!({"title": "Engineer"}));
+
+		let location = rule(json!({"type": "location", "values": ["berlin"]}));
+		assert_eq!(location.evaluate(&remote), Verdict::Match);
+		assert_eq!(location.evaluate(&unknown), Verdict::Unknown);
+
+		let workplace = rule(json!({"type": "workplace", "values": ["onsite", "hybrid"]}));
+		assert_eq!(workplace.evaluate(&remote), Verdict::NoMatch);
+		assert_eq!(workplace.evaluate(&unknown), Verdict::Unknown);
+
+		let seniority = rule(json!({"type": "seniority", "values": ["Senior"]}));
+		assert_eq!(seniority.evaluate(&remote), Verdict::Match);
+
+		let title = rule(json!({"type": "title_regex", "pattern": "(?i)^engineer$"}));
+		assert_eq!(title.evaluate(&remote), Verdict::Match);
+		assert_eq!(title.evaluate(&posting(json!({"title": "Engineering Manager"}))), Verdict::NoMatch);
+
+		assert_eq!(rule(json!({"type": "criteria", "text": "Works with databases"})).evaluate(&remote), Verdict::Unknown);
+	}
+
+	#[test]
+	fn min_salary() {
+		let rule = rule(json!({"type": "min_salary", "amount": 80000, "currency": "eur"}));
+
+		assert_eq!(
+			rule.evaluate(&posting(json!({"title": "A", "salary_min": 70000, "salary_max": 90000, "salary_currency": "EUR"}))),
+			Verdict::Match
+		);
+		assert_eq!(rule.evaluate(&posting(json!({"title": "A", "salary_min": 60000, "salary_currency": "EUR"}))), Verdict::NoMatch);
+
+		// salaries in other or unknown currencies can't be compared
+		assert_eq!(rule.evaluate(&posting(json!({"title": "A", "salary_min": 90000, "salary_currency": "USD"}))), Verdict::Unknown);
+		assert_eq!(rule.evaluate(&posting(json!({"title": "A", "salary_min": 90000}))), Verdict::Unknown);
+		assert_eq!(rule.evaluate(&posting(json!({"title": "A"}))), Verdict::Unknown);
+	}
+
+	#[test]
+	fn groups() {
+		let senior = posting(json!({"title": "Rust Engineer", "content": "Rust", "seniority": "senior"}));
+
+		let all = rule(json!({"type": "all", "rules": [
+			{"type": "keywords", "include": ["rust"]},
+			{"type": "criteria", "text": "Interesting"}
+		]}));
+		assert_eq!(all.evaluate(&senior), Verdict::Unknown);
+
+		let any = rule(json!({"type": "any", "rules": [
+			{"type": "seniority", "values": ["entry"]},
+			{"type": "criteria", "text": "Interesting"}
+		]}));
+		assert_eq!(any.evaluate(&senior), Verdict::Unknown);
+
+		let any = rule(json!({"type": "any", "rules": [
+			{"type": "seniority", "values": ["entry"]},
+			{"type": "keywords", "include": ["rust"]}
+		]}));
+		assert_eq!(any.evaluate(&senior), Verdict::Match);
+	}
+
+	#[test]
+	fn validate_rules() {
+		let valid = [
+			json!({"type": "keywords", "include": ["C++"], "exclude": ["php"]}),
+			json!({"type": "title_regex", "pattern": "^(senior|staff) "}),
+			json!({"type": "location", "values": ["Berlin"]}),
+			json!({"type": "workplace", "values": ["Remote"]}),
+			json!({"type": "seniority", "values": ["senior", "lead"]}),
+			json!({"type": "min_salary", "amount": 50000}),
+			json!({"type": "min_salary", "amount": 50000, "currency": "CHF"}),
+			json!({"type": "criteria", "text": "Uses Rust"}),
+			json!({"type": "any", "rules": [{"type": "all", "rules": [{"type": "criteria", "text": "Uses Rust"}]}]}),
+		];
+		for value in valid {
+			assert_eq!(rule(value.clone()).validate(), Ok(()), "{value}");
+		}
+
+		let invalid = [
+			json!({"type": "keywords"}),
+			json!({"type": "keywords", "include": [" "]}),
+			json!({"type": "title_regex", "pattern": "(unclosed"}),
+			json!({"type": "location", "values": []}),
+			json!({"type": "workplace", "values": ["office"]}),
+			json!({"type": "seniority", "values": []}),
+			json!({"type": "min_salary", "amount": 0}),
+			json!({"type": "min_salary", "amount": 50000, "currency": "euro"}),
+			json!({"type": "criteria", "text": ""}),
+			json!({"type": "all", "rules": []}),
+		];
+		for value in invalid {
+			assert!(rule(value.clone()).validate().is_err(), "{value}");
+		}
+	}
+
+	#[test]
+	fn validate_nesting_depth() {
+		let mut value = json!({"type": "criteria", "text": "Uses Rust"});
+		for _ in 0..MAX_DEPTH {
+			value = json!({"type": "all", "rules": [value]});
+		}
+		assert_eq!(rule(value.clone()).validate(), Ok(()));
+
+		value = json!({"type": "any", "rules": [value]});
+		assert!(rule(value).validate().is_err());
+	}
+
+	#[test]
+	fn rules_serialize_as_stored() {
+		let value = json!({"type": "all", "rules": [
+			{"type": "keywords", "include": ["C#"], "exclude": []},
+			{"type": "title_regex", "pattern": "(unclosed"}
+		]});
+		assert_eq!(serde_json::to_value(rule(value.clone())).unwrap(), value);
+	}
+
+	fn filter_rules(filters: &[(&str, serde_json::Value)]) -> FilterRules {
+		FilterRules {
+			rules: filters.iter().map(|(name, value)| (name.to_string(), rule(value.clone()))).collect(),
+		}
+	}
+
+	#[test]
+	fn criteria_are_numbered_per_account() {
+		let rust = filter_rules(&[("Rust", json!({"type": "criteria", "text": "Uses Rust"}))]);
+		let accounts = AccountFilterRules::new(vec![
+			(1, rust.clone()),
+			(2, filter_rules(&[("Remote", json!({"type": "workplace", "values": ["remote"]}))])),
+			(3, FilterRules::default()),
+			(4, filter_rules(&[("Design", json!({"type": "criteria", "text": "Product design"}))])),
+			(5, rust),
+		]);
+
+		// accounts without criteria don't drop the criteria of other accounts, and accounts with the same criteria share a number
+		assert!(accounts.has_criteria());
+		assert_eq!(accounts.criteria(), "1.\n- Rust: Uses Rust\n2.\n- Design: Product design");
+		assert_eq!(
+			accounts.criteria_groups(),
+			[("- Rust: Uses Rust".to_string(), vec![1, 5]), ("- Design: Product design".to_string(), vec![4])]
+		);
+
+		let accounts = AccountFilterRules::new(vec![(1, FilterRules::default())]);
+		assert!(!accounts.has_criteria());
+	}
+
+	#[test]
+	fn accounts_match_their_own_criteria() {
+		let mut accounts = AccountFilterRules::new(vec![
+			(1, filter_rules(&[("Rust", json!({"type": "criteria", "text": "Uses Rust"}))])),
+			(2, filter_rules(&[("Design", json!({"type": "criteria", "text": "Product design"}))])),
+			(3, FilterRules::default()),
+			(
+				4,
+				filter_rules(&[
+					("Senior", json!({"type": "seniority", "values": ["senior"]})),
+					("Rust", json!({"type": "criteria", "text": "Uses Rust"})),
+				]),
+			),
+		]);
+
+		// criteria that haven't been checked by the LLM yet don't exclude postings
+		let rust = posting(json!({"title": "Rust Engineer", "seniority": "entry"}));
+		assert_eq!(accounts.matching_accounts(&rust), [1, 2, 3]);
+
+		accounts.record_criteria("Rust Engineer", &[1]);
+		assert_eq!(accounts.matching_accounts(&rust), [1, 3]);
+
+		// postings the LLM didn't find related to any criteria are only shown to accounts without criteria
+		accounts.record_criteria("Designer", &[]);
+		assert_eq!(accounts.matching_accounts(&posting(json!({"title": "Designer"}))), [3]);
+
+		// unknown criteria numbers are ignored
+		accounts.record_criteria("Product Engineer", &[0, 2, 7]);
+		assert_eq!(accounts.matching_accounts(&posting(json!({"title": "Product Engineer", "seniority": "senior"}))), [2, 3]);
+	}
+
+	#[test]
+	fn postings_excluded_by_all_accounts() {
+		let mut accounts = AccountFilterRules::new(vec![
+			(1, filter_rules(&[("Rust", json!({"type": "criteria", "text": "Uses Rust"}))])),
+			(2, filter_rules(&[("Remote", json!({"type": "workplace", "values": ["remote"]}))])),
+		]);
+
+		let onsite = posting(json!({"title": "Engineer", "workplace": "onsite"}));
+		assert!(!accounts.excludes(&onsite));
+
+		accounts.record_criteria("Engineer", &[]);
+		assert!(accounts.excludes(&onsite));
+	}
+}
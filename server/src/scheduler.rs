@@ -0,0 +1,112 @@
+/// Background scheduler that refreshes sources periodically.
+use crate::entities::{prelude::*, *};
+use crate::jobs::JobQueue;
+use anyhow::Result;
+
+use chrono::{FixedOffset, Utc};
+use rand::Rng;
+use sea_orm::*;
+use serde::Deserialize;
+use std::time::Duration;
+
+/// Scheduler configuration.
+///
+/// Read from the `scheduler` section of `Rocket.toml`; missing values fall back to the defaults.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct SchedulerConfig {
+	/// Whether sources should be refreshed automatically
+	pub enabled: bool,
+
+	/// Default number of minutes between refreshes for sources without a configured interval
+	pub interval: u64,
+
+	/// Maximum number of seconds a refresh gets randomly delayed by
+	/// This is to prevent all sources from being refreshed at the same time
+	pub jitter: u64,
+
+	/// Number of seconds between checks for sources that are due for a refresh
+	pub tick: u64,
+}
+
+impl Default for SchedulerConfig {
+	fn default() -> Self {
+		SchedulerConfig {
+			enabled: true,
+			interval: 360,
+			jitter: 300,
+			tick: 60,
+		}
+	}
+}
+
+/// Scheduler that periodically refreshes postings of all sources.
+#[derive(Clone)]
+pub struct Scheduler {
+	/// Database connection
+	db: DatabaseConnection,
+
+	/// Scheduler configuration
+	config: SchedulerConfig,
+
+	/// Queue refresh jobs are added to
+	jobs: JobQueue,
+}
+
+impl Scheduler {
+	/// Create and return a new scheduler instance.
+	pub fn new(db: DatabaseConnection, config: SchedulerConfig, jobs: JobQueue) -> Self {
+		Scheduler { db, config, jobs }
+	}
+
+	/// Start the scheduler in the background.
+	pub fn start(self) {
+		if !self.config.enabled {
+			return;
+		}
+
+		tokio::spawn(async move {
+			loop {
+				if let Err(e) = self.schedule_due_sources().await {
+					error!("Could not schedule source refreshes: {e}");
+				}
+
+				tokio::time::sleep(Duration::from_secs(self.config.tick)).await;
+			}
+		});
+	}
+
+	/// Return active sources that are due for a refresh.
+	async fn due_sources(&self) -> Result<Vec<source::Model>> {
+		let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+		let sources = Source::find().filter(source::Column::Deleted.eq(false)).all(&self.db).await?;
+
+		Ok(sources
+			.into_iter()
+			.filter(|s| {
+				let interval = match s.refresh_interval {
+					// automatic refreshes are disabled for this source
+					Some(i) if i <= 0 => return false,
+					Some(i) => i as i64,
+					None => self.config.interval as i64,
+				};
+
+				match s.refreshed_at {
+					Some(refreshed_at) => refreshed_at + chrono::Duration::minutes(interval) <= now,
+					None => true,
+				}
+			})
+			.collect())
+	}
+
+	/// Enqueue a refresh job for every source that is due.
+	/// Sources that are already queued or being refreshed are skipped by the job queue.
+	async fn schedule_due_sources(&self) -> Result<()> {
+		for source in self.due_sources().await? {
+			let jitter = rand::thread_rng().gen_range(0..=self.config.jitter);
+			self.jobs.enqueue_delayed(source.id, Duration::from_secs(jitter)).await?;
+		}
+
+		Ok(())
+	}
+}
@@ -0,0 +1,375 @@
+/// Relevance model scoring how well postings match, trained from liked and disliked postings.
+use crate::entities::{prelude::*, *};
+use crate::error::Error;
+use crate::similarity::{MatchScorer, VectorBackend};
+
+use chrono::FixedOffset;
+use sea_orm::*;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// minimum number of liked and of disliked postings required to train a model
+const MIN_EXAMPLES_PER_LABEL: usize = 3;
+
+/// number of gradient descent iterations when training a model from scratch
+const EPOCHS: usize = 500;
+
+/// number of gradient descent iterations when continuing to train the previous model
+const WARM_START_EPOCHS: usize = 100;
+
+/// gradient descent step size
+const LEARNING_RATE: f32 = 1.0;
+
+/// strength of the L2 regularization of the model weights
+const L2_PENALTY: f32 = 0.001;
+
+/// every n-th labelled posting, by ID, is held out to evaluate the model
+const HELD_OUT_EVERY: i32 = 5;
+
+/// Locks per account, so that one account doesn't block the others.
+type AccountLocks = std::sync::Mutex<BTreeMap<i32, Arc<Mutex<()>>>>;
+
+/// Makes sure only one model is trained at a time per account.
+static TRAINING: AccountLocks = std::sync::Mutex::new(BTreeMap::new());
+
+/// Makes sure only one background rescore runs at a time per account.
+static RESCORING: AccountLocks = std::sync::Mutex::new(BTreeMap::new());
+
+/// Accounts for which a background rescore is waiting to be started.
+static RESCORE_QUEUED: std::sync::Mutex<BTreeSet<i32>> = std::sync::Mutex::new(BTreeSet::new());
+
+/// Returns the lock of the account, creating it on first use.
+fn account_lock(locks: &AccountLocks, account_id: i32) -> Arc<Mutex<()>> {
+	locks.lock().unwrap().entry(account_id).or_default().clone()
+}
+
+/// Embedding of a liked or disliked posting.
+struct Example {
+	/// Posting ID
+	posting_id: i32,
+
+	/// Embedding vector
+	vector: Vec<f32>,
+
+	/// Whether the posting was liked
+	is_match: bool,
+}
+
+/// Logistic regression over posting embeddings, predicting whether a posting is a match.
+#[derive(Clone, Debug)]
+pub struct MatchModel {
+	/// Weight per embedding dimension
+	weights: Vec<f32>,
+
+	/// Bias
+	bias: f32,
+}
+
+impl MatchModel {
+	/// Train a model on the examples using batch gradient descent.
+	/// Training continues from the `initial` model if it has the same dimensions.
+	fn train(examples: &[&Example], initial: Option<&MatchModel>, epochs: usize) -> Self {
+		let dimensions = examples.first().map(|e| e.vector.len()).unwrap_or(0);
+		let mut model = match initial {
+			Some(model) if model.weights.len() == dimensions => model.clone(),
+			_ => MatchModel {
+				weights: vec![0.0; dimensions],
+				bias: 0.0,
+			},
+		};
+
+		// weight examples inversely to the frequency of their label, so more likes than dislikes don't bias the model
+		let total = examples.len() as f32;
+		let liked = examples.iter().filter(|e| e.is_match).count().max(1) as f32;
+		let disliked = examples.iter().filter(|e| !e.is_match).count().max(1) as f32;
+
+		for _ in 0..epochs {
+			let mut weight_gradients = vec![0.0; dimensions];
+			let mut bias_gradient = 0.0;
+
+			for example in examples {
+				let (target, label_weight) = if example.is_match { (1.0, total / (2.0 * liked)) } else { (0.0, total / (2.0 * disliked)) };
+				let error = (model.probability(&example.vector) - target) * label_weight;
+
+				for (gradient, x) in weight_gradients.iter_mut().zip(&example.vector) {
+					*gradient += error * x;
+				}
+				bias_gradient += error;
+			}
+
+			for (weight, gradient) in model.weights.iter_mut().zip(&weight_gradients) {
+				*weight -= LEARNING_RATE * (gradient / total + L2_PENALTY * *weight);
+			}
+			model.bias -= LEARNING_RATE * bias_gradient / total;
+		}
+
+		model
+	}
+
+	/// Returns the probability that the posting with the embedding `vector` is a match.
+	fn probability(&self, vector: &[f32]) -> f32 {
+		let z: f32 = self.weights.iter().zip(vector).map(|(w, x)| w * x).sum::<f32>() + self.bias;
+		1.0 / (1.0 + (-z).exp())
+	}
+
+	/// Returns the match score of the posting with the embedding `vector`.
+	/// Like the similarity score, the score ranges from -1 (not a match) to 1 (a match).
+	pub fn score(&self, vector: &[f32]) -> f32 {
+		2.0 * self.probability(vector) - 1.0
+	}
+
+	/// Returns the share of examples that are classified correctly.
+	fn accuracy(&self, examples: &[&Example]) -> Option<f32> {
+		if examples.is_empty() {
+			return None;
+		}
+
+		let correct = examples.iter().filter(|e| (self.probability(&e.vector) >= 0.5) == e.is_match).count();
+		Some(correct as f32 / examples.len() as f32)
+	}
+}
+
+/// Scores postings with the trained model; falls back to the similarity to liked and disliked postings
+/// if no model has been trained yet, or the model doesn't fit the embedding.
+pub struct Scorer {
+	/// Trained model
+	model: Option<MatchModel>,
+
+	/// Similarity to liked and disliked postings
+	similarity: MatchScorer,
+}
+
+impl Scorer {
+	/// Load the most recently trained model of the user.
+	pub async fn load(db: &DatabaseConnection, backend: VectorBackend, user_id: i32) -> Result<Self, Error> {
+		let model = ScoringModel::find()
+			.filter(scoring_model::Column::AccountId.eq(user_id))
+			.order_by_desc(scoring_model::Column::TrainedAt)
+			.one(db)
+			.await?
+			.map(|m| MatchModel { weights: m.weights, bias: m.bias });
+
+		Ok(Scorer {
+			model,
+			similarity: MatchScorer::new(db, backend, user_id).await?,
+		})
+	}
+
+	/// Returns the match scores of postings, given as posting IDs and embeddings.
+	/// Postings that the model doesn't fit are scored by their similarity to liked and disliked postings.
+	pub async fn score_all(&self, db: &DatabaseConnection, postings: &[(i32, Vec<f32>)]) -> Result<Vec<(i32, f32)>, Error> {
+		let (model_fits, unfit): (Vec<_>, Vec<_>) = postings
+			.iter()
+			.cloned()
+			.partition(|(_, vector)| matches!(&self.model, Some(model) if model.weights.len() == vector.len()));
+
+		let mut scores: Vec<(i32, f32)> = match &self.model {
+			Some(model) => model_fits.iter().map(|(id, vector)| (*id, model.score(vector))).collect(),
+			None => vec![],
+		};
+
+		if !unfit.is_empty() {
+			scores.extend(self.similarity.score_all(db, &unfit).await?);
+		}
+
+		Ok(scores)
+	}
+
+	/// Returns the match score of the posting with the embedding `vector`, ranging from -1 to 1.
+	pub async fn score(&self, db: &DatabaseConnection, vector: &[f32]) -> Result<f32, Error> {
+		match &self.model {
+			Some(model) if model.weights.len() == vector.len() => Ok(model.score(vector)),
+			_ => self.similarity.score(db, vector).await,
+		}
+	}
+}
+
+/// Summary of training a model and rescoring postings.
+#[derive(Clone, Debug, Serialize)]
+pub struct TrainingReport {
+	/// Whether a model was trained; requires enough liked and disliked postings
+	pub trained: bool,
+
+	/// Number of liked and disliked postings the model was trained on
+	pub examples: usize,
+
+	/// Number of liked and disliked postings held out to evaluate the model
+	pub held_out: usize,
+
+	/// Share of held out postings that were classified correctly
+	pub accuracy: Option<f32>,
+
+	/// Number of unread postings that were rescored
+	pub rescored: usize,
+}
+
+/// Load the embeddings of all postings the user liked and disliked.
+/// Only embeddings with the most common number of dimensions are used, as embeddings of different models can't be combined.
+async fn load_examples(db: &DatabaseConnection, user_id: i32) -> Result<Vec<Example>, Error> {
+	let labelled: Vec<(i32, Vec<f32>, bool)> = Embedding::find()
+		.select_only()
+		.column(embedding::Column::PostingId)
+		.column(embedding::Column::Vector)
+		.column(posting_state::Column::IsMatch)
+		.join(JoinType::InnerJoin, embedding::Relation::Posting.def())
+		.join(JoinType::InnerJoin, posting::Relation::PostingState.def())
+		.filter(posting_state::Column::AccountId.eq(user_id))
+		.filter(posting_state::Column::IsMatch.is_not_null())
+		.filter(embedding::Column::Vector.is_not_null())
+		.into_tuple()
+		.all(db)
+		.await?;
+
+	let mut dimension_counts: HashMap<usize, usize> = HashMap::new();
+	for (_, vector, _) in &labelled {
+		*dimension_counts.entry(vector.len()).or_default() += 1;
+	}
+	let dimensions = dimension_counts.into_iter().max_by_key(|(_, count)| *count).map(|(d, _)| d).unwrap_or(0);
+
+	Ok(labelled
+		.into_iter()
+		.filter(|(_, vector, _)| vector.len() == dimensions)
+		.map(|(posting_id, vector, is_match)| Example { posting_id, vector, is_match })
+		.collect())
+}
+
+/// Train the model of the user on all postings the user liked and disliked.
+/// Every fifth posting is held out to evaluate the accuracy of the model, before the model is trained on all postings.
+/// Training continues from the previous model, so retraining after a single posting was liked or disliked is fast.
+///
+/// If there aren't enough liked and disliked postings, the previous model is removed and postings are scored by their
+/// similarity to liked and disliked postings instead.
+pub async fn train(db: &DatabaseConnection, user_id: i32) -> Result<TrainingReport, Error> {
+	let training = account_lock(&TRAINING, user_id);
+	let _training = training.lock().await;
+
+	let examples = load_examples(db, user_id).await?;
+	let liked = examples.iter().filter(|e| e.is_match).count();
+	let disliked = examples.len() - liked;
+
+	if liked < MIN_EXAMPLES_PER_LABEL || disliked < MIN_EXAMPLES_PER_LABEL {
+		ScoringModel::delete_many().filter(scoring_model::Column::AccountId.eq(user_id)).exec(db).await?;
+
+		return Ok(TrainingReport {
+			trained: false,
+			examples: examples.len(),
+			held_out: 0,
+			accuracy: None,
+			rescored: 0,
+		});
+	}
+
+	let previous = ScoringModel::find()
+		.filter(scoring_model::Column::AccountId.eq(user_id))
+		.order_by_desc(scoring_model::Column::TrainedAt)
+		.one(db)
+		.await?
+		.map(|m| MatchModel { weights: m.weights, bias: m.bias });
+
+	// training is CPU bound, so it doesn't run on the async runtime
+	let (model, held_out, accuracy) = tokio::task::spawn_blocking(move || {
+		let (held_out, training): (Vec<&Example>, Vec<&Example>) = examples.iter().partition(|e| e.posting_id % HELD_OUT_EVERY == 0);
+
+		// evaluate a model that hasn't seen the held out postings
+		let accuracy = if training.iter().any(|e| e.is_match) && training.iter().any(|e| !e.is_match) {
+			MatchModel::train(&training, None, EPOCHS).accuracy(&held_out)
+		} else {
+			None
+		};
+
+		let all: Vec<&Example> = examples.iter().collect();
+		let model = match &previous {
+			Some(previous) => MatchModel::train(&all, Some(previous), WARM_START_EPOCHS),
+			None => MatchModel::train(&all, None, EPOCHS),
+		};
+
+		(model, held_out.len(), accuracy)
+	})
+	.await
+	.map_err(|e| Error::internal(format!("Could not train scoring model: {e}")))?;
+
+	let txn = db.begin().await?;
+	ScoringModel::delete_many().filter(scoring_model::Column::AccountId.eq(user_id)).exec(&txn).await?;
+	scoring_model::ActiveModel {
+		id: NotSet,
+		account_id: Set(user_id),
+		weights: Set(model.weights),
+		bias: Set(model.bias),
+		examples: Set((liked + disliked) as i32),
+		held_out: Set(held_out as i32),
+		accuracy: Set(accuracy),
+		trained_at: Set(Some(chrono::offset::Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap()))),
+	}
+	.insert(&txn)
+	.await?;
+	txn.commit().await?;
+
+	Ok(TrainingReport {
+		trained: true,
+		examples: liked + disliked,
+		held_out,
+		accuracy,
+		rescored: 0,
+	})
+}
+
+/// Recompute the match score of all postings the user hasn't read from their stored embeddings.
+///
+/// Returns the number of postings that were rescored.
+pub async fn rescore_unread(db: &DatabaseConnection, backend: VectorBackend, user_id: i32) -> Result<usize, Error> {
+	let scorer = Scorer::load(db, backend, user_id).await?;
+
+	let unread: Vec<(i32, Vec<f32>)> = Embedding::find()
+		.select_only()
+		.column(embedding::Column::PostingId)
+		.column(embedding::Column::Vector)
+		.join(JoinType::InnerJoin, embedding::Relation::Posting.def())
+		.join(JoinType::InnerJoin, posting::Relation::PostingState.def())
+		.filter(posting_state::Column::AccountId.eq(user_id))
+		.filter(posting_state::Column::Seen.eq(false))
+		.filter(embedding::Column::Vector.is_not_null())
+		.into_tuple()
+		.all(db)
+		.await?;
+
+	let scores = scorer.score_all(db, &unread).await?;
+
+	// write all scores with a single statement
+	let (posting_ids, match_similarities): (Vec<i32>, Vec<f32>) = scores.into_iter().unzip();
+	db.execute(Statement::from_sql_and_values(
+		DbBackend::Postgres,
+		r#"UPDATE posting_state
+		SET match_similarity = scores.match_similarity
+		FROM unnest($2::integer[], $3::real[]) AS scores(posting_id, match_similarity)
+		WHERE posting_state.account_id = $1 AND posting_state.posting_id = scores.posting_id"#,
+		[user_id.into(), posting_ids.into(), match_similarities.into()],
+	))
+	.await?;
+
+	Ok(unread.len())
+}
+
+/// Retrain the model of the user and rescore their unread postings in the background, e.g. after a posting was liked or disliked.
+/// If a rescore is already queued for the user, it picks up the latest feedback, so no additional rescore is queued.
+pub fn rescore_in_background(db: DatabaseConnection, backend: VectorBackend, user_id: i32) {
+	if !RESCORE_QUEUED.lock().unwrap().insert(user_id) {
+		return;
+	}
+
+	tokio::spawn(async move {
+		let rescoring = account_lock(&RESCORING, user_id);
+		let _rescoring = rescoring.lock().await;
+		RESCORE_QUEUED.lock().unwrap().remove(&user_id);
+
+		if let Err(e) = train(&db, user_id).await {
+			warn!("Could not retrain scoring model of account {user_id}: {e}");
+		}
+
+		match rescore_unread(&db, backend, user_id).await {
+			Ok(rescored) => info!("Rescored {rescored} unread postings of account {user_id}"),
+			Err(e) => warn!("Could not rescore postings: {e}"),
+		}
+	});
+}
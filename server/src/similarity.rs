@@ -0,0 +1,338 @@
+/// Nearest-neighbour queries over the embeddings of postings.
+use crate::entities::{prelude::*, *};
+use crate::error::Error;
+use crate::llm::get_similarity;
+
+use sea_orm::prelude::DateTimeWithTimeZone;
+use sea_orm::*;
+use std::collections::HashMap;
+
+/// Number of dimensions of the embeddings that are indexed by pgvector, matching the default embedding model
+const INDEXED_DIMENSIONS: usize = 1536;
+
+/// Maximum number of liked and disliked postings that are compared if similarities are computed in memory
+const IN_MEMORY_MAX_LABELLED: u64 = 50;
+
+/// Where the cosine similarity of embeddings is computed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum VectorBackend {
+	/// pgvector is installed and embeddings are stored in the indexed `pgvector` column
+	PgvectorColumn,
+
+	/// pgvector is installed, but embeddings are only stored as arrays and are converted on every query
+	PgvectorCast,
+
+	/// pgvector isn't installed; embeddings are loaded and compared in the server
+	InMemory,
+}
+
+impl VectorBackend {
+	/// Returns the backend that is supported by the database.
+	/// The backend is detected once when the server starts and is kept as managed state.
+	pub async fn detect(db: &DatabaseConnection) -> Result<Self, Error> {
+		let has_column = db
+			.query_one(Statement::from_string(
+				DbBackend::Postgres,
+				"SELECT 1 FROM information_schema.columns WHERE table_name = 'embedding' AND column_name = 'pgvector'",
+			))
+			.await?
+			.is_some();
+
+		if has_column {
+			return Ok(VectorBackend::PgvectorColumn);
+		}
+
+		let has_extension = db
+			.query_one(Statement::from_string(DbBackend::Postgres, "SELECT 1 FROM pg_extension WHERE extname = 'vector'"))
+			.await?
+			.is_some();
+
+		if has_extension {
+			Ok(VectorBackend::PgvectorCast)
+		} else {
+			Ok(VectorBackend::InMemory)
+		}
+	}
+
+	/// Returns the SQL expression of the stored embedding as pgvector for embeddings with `dimensions`.
+	/// Embeddings with the indexed number of dimensions are cast in the same way as in the index, so the index is used.
+	fn vector_expr(&self, dimensions: usize) -> String {
+		match self {
+			VectorBackend::PgvectorColumn if dimensions == INDEXED_DIMENSIONS => format!("(embedding.pgvector::vector({INDEXED_DIMENSIONS}))"),
+			VectorBackend::PgvectorColumn => "embedding.pgvector".to_string(),
+			_ => "embedding.vector::vector".to_string(),
+		}
+	}
+
+	/// Returns the SQL condition for embeddings that can be compared to an embedding with `dimensions`.
+	/// Embeddings created with different models can't be compared.
+	fn dimensions_condition(&self, dimensions: usize) -> String {
+		match self {
+			VectorBackend::PgvectorColumn => format!("vector_dims(embedding.pgvector) = {dimensions}"),
+			_ => format!("array_length(embedding.vector, 1) = {dimensions}"),
+		}
+	}
+
+	/// Returns the SQL expression of the query embedding `$1` as pgvector.
+	fn query_expr(&self, dimensions: usize) -> String {
+		format!("$1::real[]::vector({dimensions})")
+	}
+}
+
+/// Find the postings of a user whose embeddings are most similar to `vector`.
+/// Only postings of active sources are considered; the posting `exclude_id` is skipped.
+///
+/// The cosine similarity is computed by pgvector if it is installed, or otherwise by loading all embeddings.
+///
+/// Returns the posting IDs and similarities, most similar first.
+pub async fn nearest_postings(db: &DatabaseConnection, backend: VectorBackend, user_id: i32, vector: &[f32], exclude_id: Option<i32>, limit: u64) -> Result<Vec<(i32, f32)>, Error> {
+	match backend {
+		VectorBackend::InMemory => nearest_postings_in_memory(db, user_id, vector, exclude_id, limit).await,
+		backend => nearest_postings_pgvector(db, backend, user_id, vector, exclude_id, limit).await,
+	}
+}
+
+/// Find the most similar postings using pgvector's cosine distance operator.
+async fn nearest_postings_pgvector(db: &DatabaseConnection, backend: VectorBackend, user_id: i32, vector: &[f32], exclude_id: Option<i32>, limit: u64) -> Result<Vec<(i32, f32)>, Error> {
+	let distance = format!("{} <=> {}", backend.vector_expr(vector.len()), backend.query_expr(vector.len()));
+
+	let rows = db
+		.query_all(Statement::from_sql_and_values(
+			DbBackend::Postgres,
+			format!(
+				r#"SELECT embedding.posting_id, 1 - ({distance}) AS similarity
+				FROM embedding
+				JOIN posting ON posting.id = embedding.posting_id
+				JOIN source ON source.id = posting.source_id
+				JOIN posting_state ON posting_state.posting_id = posting.id AND posting_state.account_id = $4
+				WHERE source.deleted = false
+					AND embedding.posting_id <> $2
+					AND {}
+				ORDER BY {distance}
+				LIMIT $3"#,
+				backend.dimensions_condition(vector.len())
+			),
+			[vector.to_vec().into(), exclude_id.unwrap_or(0).into(), (limit as i64).into(), user_id.into()],
+		))
+		.await?;
+
+	let mut nearest = vec![];
+	for row in rows {
+		let posting_id: i32 = row.try_get("", "posting_id")?;
+		let similarity: f64 = row.try_get("", "similarity")?;
+		nearest.push((posting_id, similarity as f32));
+	}
+
+	Ok(nearest)
+}
+
+/// Find the postings of sources other than `source_id` whose embeddings are most similar to `vector` using pgvector's
+/// cosine distance operator. Only postings of active sources created since `created_after` are considered.
+///
+/// Returns the posting IDs, titles and similarities, most similar first.
+pub async fn nearest_other_source_postings(
+	db: &DatabaseConnection,
+	backend: VectorBackend,
+	source_id: i32,
+	vector: &[f32],
+	created_after: DateTimeWithTimeZone,
+	limit: u64,
+) -> Result<Vec<(i32, String, f32)>, Error> {
+	let distance = format!("{} <=> {}", backend.vector_expr(vector.len()), backend.query_expr(vector.len()));
+
+	let rows = db
+		.query_all(Statement::from_sql_and_values(
+			DbBackend::Postgres,
+			format!(
+				r#"SELECT posting.id, posting.title, 1 - ({distance}) AS similarity
+				FROM embedding
+				JOIN posting ON posting.id = embedding.posting_id
+				JOIN source ON source.id = posting.source_id
+				WHERE source.deleted = false
+					AND posting.source_id <> $2
+					AND posting.created_at >= $3
+					AND {}
+				ORDER BY {distance}
+				LIMIT $4"#,
+				backend.dimensions_condition(vector.len())
+			),
+			[vector.to_vec().into(), source_id.into(), created_after.into(), (limit as i64).into()],
+		))
+		.await?;
+
+	let mut nearest = vec![];
+	for row in rows {
+		let posting_id: i32 = row.try_get("", "id")?;
+		let title: String = row.try_get("", "title")?;
+		let similarity: f64 = row.try_get("", "similarity")?;
+		nearest.push((posting_id, title, similarity as f32));
+	}
+
+	Ok(nearest)
+}
+
+/// Find the most similar postings by computing the cosine similarity to all embeddings.
+async fn nearest_postings_in_memory(db: &DatabaseConnection, user_id: i32, vector: &[f32], exclude_id: Option<i32>, limit: u64) -> Result<Vec<(i32, f32)>, Error> {
+	let embeddings: Vec<(i32, Vec<f32>)> = Embedding::find()
+		.select_only()
+		.column(embedding::Column::PostingId)
+		.column(embedding::Column::Vector)
+		.join(JoinType::InnerJoin, embedding::Relation::Posting.def())
+		.join(JoinType::InnerJoin, posting::Relation::Source.def())
+		.join(JoinType::InnerJoin, posting::Relation::PostingState.def())
+		.filter(posting_state::Column::AccountId.eq(user_id))
+		.filter(source::Column::Deleted.eq(false))
+		.filter(embedding::Column::Vector.is_not_null())
+		.filter(embedding::Column::PostingId.ne(exclude_id.unwrap_or(0)))
+		.into_tuple()
+		.all(db)
+		.await?;
+
+	// embeddings created with different models can't be compared
+	let mut nearest: Vec<(i32, f32)> = embeddings
+		.into_iter()
+		.filter(|(_, v)| v.len() == vector.len())
+		.map(|(posting_id, v)| (posting_id, get_similarity(vector, std::slice::from_ref(&v))))
+		.collect();
+
+	nearest.sort_by(|a, b| b.1.total_cmp(&a.1));
+	nearest.truncate(limit as usize);
+
+	Ok(nearest)
+}
+
+/// Computes how well postings match based on the postings a user previously liked and disliked.
+pub enum MatchScorer {
+	/// Similarities to all liked and disliked postings of the user with the ID are computed by pgvector
+	Pgvector(VectorBackend, i32),
+
+	/// Similarities to the most recently liked and disliked postings are computed in the server
+	InMemory {
+		/// Embeddings of liked postings
+		liked: Vec<Vec<f32>>,
+
+		/// Embeddings of disliked postings
+		disliked: Vec<Vec<f32>>,
+	},
+}
+
+impl MatchScorer {
+	/// Create a new match scorer for a user.
+	/// Without pgvector, the embeddings of the postings the user most recently liked and disliked are loaded.
+	pub async fn new(db: &DatabaseConnection, backend: VectorBackend, user_id: i32) -> Result<Self, Error> {
+		if backend != VectorBackend::InMemory {
+			return Ok(MatchScorer::Pgvector(backend, user_id));
+		}
+
+		Ok(MatchScorer::InMemory {
+			liked: labelled_embeddings(db, user_id, true).await?,
+			disliked: labelled_embeddings(db, user_id, false).await?,
+		})
+	}
+
+	/// Returns the match similarity of a posting embedding: the similarity to the most similar liked posting minus the
+	/// similarity to the most similar disliked posting.
+	pub async fn score(&self, db: &DatabaseConnection, vector: &[f32]) -> Result<f32, Error> {
+		match self {
+			MatchScorer::InMemory { liked, disliked } => Ok(get_similarity(vector, liked) - get_similarity(vector, disliked)),
+			MatchScorer::Pgvector(backend, user_id) => {
+				let similarity = format!("1 - ({} <=> {})", backend.vector_expr(vector.len()), backend.query_expr(vector.len()));
+
+				let row = db
+					.query_one(Statement::from_sql_and_values(
+						DbBackend::Postgres,
+						format!(
+							r#"SELECT
+								coalesce(max({similarity}) FILTER (WHERE posting_state.is_match = true), 0) AS liked,
+								coalesce(max({similarity}) FILTER (WHERE posting_state.is_match = false), 0) AS disliked
+							FROM embedding
+							JOIN posting_state ON posting_state.posting_id = embedding.posting_id
+							WHERE posting_state.account_id = $2
+								AND posting_state.is_match IS NOT NULL
+								AND {}"#,
+							backend.dimensions_condition(vector.len())
+						),
+						[vector.to_vec().into(), (*user_id).into()],
+					))
+					.await?;
+
+				match row {
+					Some(row) => {
+						let liked: f64 = row.try_get("", "liked")?;
+						let disliked: f64 = row.try_get("", "disliked")?;
+						Ok((liked - disliked) as f32)
+					}
+					None => Ok(0.0),
+				}
+			}
+		}
+	}
+
+	/// Returns the match similarities of the stored embeddings of `postings`, given as posting IDs and embeddings.
+	/// With pgvector, the similarities of all postings are computed in a single query; liked and disliked postings
+	/// aren't compared with their own embedding.
+	pub async fn score_all(&self, db: &DatabaseConnection, postings: &[(i32, Vec<f32>)]) -> Result<Vec<(i32, f32)>, Error> {
+		let (backend, user_id) = match self {
+			MatchScorer::InMemory { liked, disliked } => {
+				return Ok(postings.iter().map(|(id, vector)| (*id, get_similarity(vector, liked) - get_similarity(vector, disliked))).collect());
+			}
+			MatchScorer::Pgvector(backend, user_id) => (backend, *user_id),
+		};
+
+		// only embeddings created with the same model are compared
+		let (similarity, dimensions_condition) = match backend {
+			VectorBackend::PgvectorColumn => ("1 - (scored.pgvector <=> labelled.pgvector)", "vector_dims(scored.pgvector) = vector_dims(labelled.pgvector)"),
+			_ => (
+				"1 - (scored.vector::vector <=> labelled.vector::vector)",
+				"array_length(scored.vector, 1) = array_length(labelled.vector, 1)",
+			),
+		};
+
+		let posting_ids: Vec<i32> = postings.iter().map(|(id, _)| *id).collect();
+
+		let rows = db
+			.query_all(Statement::from_sql_and_values(
+				DbBackend::Postgres,
+				format!(
+					r#"SELECT scored.posting_id,
+						coalesce(max({similarity}) FILTER (WHERE posting_state.is_match = true), 0) AS liked,
+						coalesce(max({similarity}) FILTER (WHERE posting_state.is_match = false), 0) AS disliked
+					FROM embedding AS scored
+					JOIN posting_state ON posting_state.account_id = $1 AND posting_state.is_match IS NOT NULL
+					JOIN embedding AS labelled ON labelled.posting_id = posting_state.posting_id AND labelled.posting_id <> scored.posting_id
+						AND {dimensions_condition}
+					WHERE scored.posting_id = ANY($2)
+					GROUP BY scored.posting_id"#
+				),
+				[user_id.into(), posting_ids.clone().into()],
+			))
+			.await?;
+
+		let mut scores = HashMap::new();
+		for row in rows {
+			let posting_id: i32 = row.try_get("", "posting_id")?;
+			let liked: f64 = row.try_get("", "liked")?;
+			let disliked: f64 = row.try_get("", "disliked")?;
+			scores.insert(posting_id, (liked - disliked) as f32);
+		}
+
+		// postings without comparable liked or disliked postings have a neutral score
+		Ok(posting_ids.into_iter().map(|id| (id, scores.get(&id).copied().unwrap_or(0.0))).collect())
+	}
+}
+
+/// Returns the embeddings of the postings the user most recently liked (`is_match`) or disliked.
+async fn labelled_embeddings(db: &DatabaseConnection, user_id: i32, is_match: bool) -> Result<Vec<Vec<f32>>, Error> {
+	Ok(Embedding::find()
+		.select_only()
+		.column(embedding::Column::Vector)
+		.join(JoinType::InnerJoin, embedding::Relation::Posting.def())
+		.join(JoinType::InnerJoin, posting::Relation::PostingState.def())
+		.filter(posting_state::Column::AccountId.eq(user_id))
+		.filter(posting_state::Column::IsMatch.eq(is_match))
+		.order_by_desc(posting::Column::CreatedAt)
+		.limit(IN_MEMORY_MAX_LABELLED)
+		.into_tuple()
+		.all(db)
+		.await?)
+}
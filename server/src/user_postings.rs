@@ -0,0 +1,66 @@
+/// Postings as seen by a user, combined with the user's state of each posting.
+use crate::entities::{prelude::*, *};
+use crate::error::{Error, Result};
+
+use sea_orm::*;
+use serde::Serialize;
+
+/// Posting together with the state of the posting for a user.
+/// Serialized as a single object, so postings have the same fields for every user.
+#[derive(Clone, Debug, Serialize)]
+pub struct UserPosting {
+	/// Posting
+	#[serde(flatten)]
+	pub posting: posting::Model,
+
+	/// Whether the user has read the posting
+	pub seen: bool,
+
+	/// Whether the user has bookmarked the posting
+	pub bookmarked: bool,
+
+	/// Whether the user liked (`true`) or disliked (`false`) the posting
+	pub is_match: Option<bool>,
+
+	/// Match score based on the postings the user liked and disliked
+	pub match_similarity: Option<f32>,
+}
+
+impl UserPosting {
+	/// Combine a posting with the user's state of the posting.
+	pub fn new(posting: posting::Model, state: posting_state::Model) -> Self {
+		UserPosting {
+			posting,
+			seen: state.seen,
+			bookmarked: state.bookmarked,
+			is_match: state.is_match,
+			match_similarity: state.match_similarity,
+		}
+	}
+}
+
+/// Returns the query for the postings of a user, together with their state.
+/// Users only see postings that have been stored for them, i.e. postings that matched their filters.
+pub fn find_user_postings(user_id: i32) -> SelectTwo<Posting, PostingState> {
+	Posting::find().find_also_related(PostingState).filter(posting_state::Column::AccountId.eq(user_id))
+}
+
+/// Load the postings of a user matching the condition, ordered by creation timestamp descending.
+pub async fn user_postings(db: &DatabaseConnection, user_id: i32, condition: Condition) -> Result<Vec<UserPosting>> {
+	Ok(find_user_postings(user_id)
+		.filter(condition)
+		.order_by_desc(posting::Column::CreatedAt)
+		.all(db)
+		.await?
+		.into_iter()
+		.filter_map(|(posting, state)| Some(UserPosting::new(posting, state?)))
+		.collect())
+}
+
+/// Load a specific posting of a user, or a not found error if it hasn't been stored for the user.
+pub async fn user_posting(db: &DatabaseConnection, user_id: i32, id: i32) -> Result<UserPosting> {
+	match find_user_postings(user_id).filter(posting::Column::Id.eq(id)).one(db).await? {
+		Some((posting, Some(state))) => Ok(UserPosting::new(posting, state)),
+		_ => Err(Error::NotFound(format!("Posting {id} does not exist"))),
+	}
+}
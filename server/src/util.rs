@@ -20,3 +20,137 @@ pub fn base_url(mut url: Url) -> Option<Url> {
 
 	Some(url)
 }
+
+/// Query parameters that are only used for tracking and don't identify a page.
+const TRACKING_PARAMS: [&str; 14] = [
+	"fbclid",
+	"gclid",
+	"dclid",
+	"msclkid",
+	"mc_cid",
+	"mc_eid",
+	"_hsenc",
+	"_hsmi",
+	"gh_src",
+	"lever-origin",
+	"lever-source",
+	"ref",
+	"referrer",
+	"trk",
+];
+
+/// Returns the normalized form of the provided `url` that is used to identify duplicate postings.
+///
+/// Fragments, tracking parameters (e.g. `utm_source`) and trailing slashes are removed,
+/// and the remaining query parameters are sorted.
+///
+/// # Examples
+/// use crate::util::canonical_url;
+///
+/// let url = canonical_url("https://Example.com/jobs/1/?utm_source=x&b=2&a=1#apply");
+/// assert!(url, Some("https://example.com/jobs/1?a=1&b=2"));
+pub fn canonical_url(url: &str) -> Option<String> {
+	let mut url = Url::parse(url.trim()).ok()?;
+	url.set_fragment(None);
+
+	let mut params: Vec<(String, String)> = url
+		.query_pairs()
+		.filter(|(key, _)| {
+			let key = key.to_lowercase();
+			!key.starts_with("utm_") && !TRACKING_PARAMS.contains(&key.as_str())
+		})
+		.map(|(key, value)| (key.into_owned(), value.into_owned()))
+		.collect();
+	params.sort();
+
+	if params.is_empty() {
+		url.set_query(None);
+	} else {
+		url.query_pairs_mut().clear().extend_pairs(params);
+	}
+
+	let path = url.path().trim_end_matches('/').to_string();
+	if !path.is_empty() {
+		url.set_path(&path);
+	}
+
+	Some(url.to_string())
+}
+
+/// Returns the `LIKE` pattern matching values that contain `text`.
+/// The `LIKE` wildcards `%` and `_` and the escape character `\` in `text` are matched literally.
+///
+/// # Examples
+/// use crate::util::contains_pattern;
+///
+/// let pattern = contains_pattern("100%");
+/// assert!(pattern, "%100\\%%");
+pub fn contains_pattern(text: &str) -> String {
+	let escaped = text.trim().replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+	format!("%{escaped}%")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn canonical_url_removes_tracking_params() {
+		assert_eq!(
+			canonical_url("https://example.com/jobs/1?utm_source=linkedin&utm_medium=social"),
+			Some("https://example.com/jobs/1".to_string())
+		);
+		assert_eq!(
+			canonical_url("https://example.com/jobs/1?gh_src=abc&id=5&fbclid=xyz"),
+			Some("https://example.com/jobs/1?id=5".to_string())
+		);
+		assert_eq!(
+			canonical_url("https://jobs.lever.co/acme/1?lever-origin=applied&UTM_Campaign=x"),
+			Some("https://jobs.lever.co/acme/1".to_string())
+		);
+	}
+
+	#[test]
+	fn canonical_url_removes_fragment_and_trailing_slash() {
+		assert_eq!(canonical_url("https://example.com/jobs/1/#apply"), Some("https://example.com/jobs/1".to_string()));
+		assert_eq!(canonical_url("https://example.com/jobs/1//"), Some("https://example.com/jobs/1".to_string()));
+		assert_eq!(canonical_url("  https://Example.com/jobs/1  "), Some("https://example.com/jobs/1".to_string()));
+	}
+
+	#[test]
+	fn canonical_url_sorts_params() {
+		assert_eq!(canonical_url("https://example.com/jobs?b=2&a=1"), canonical_url("https://example.com/jobs?a=1&b=2"));
+		assert_eq!(
+			canonical_url("https://example.com/jobs/?utm_source=x&b=2&a=1#apply"),
+			Some("https://example.com/jobs?a=1&b=2".to_string())
+		);
+	}
+
+	#[test]
+	fn canonical_url_keeps_root_path() {
+		assert_eq!(canonical_url("https://example.com"), Some("https://example.com/".to_string()));
+		assert_eq!(canonical_url("https://example.com/"), Some("https://example.com/".to_string()));
+		assert_eq!(canonical_url("https://example.com/?utm_source=x"), Some("https://example.com/".to_string()));
+	}
+
+	#[test]
+	fn canonical_url_rejects_invalid_urls() {
+		assert_eq!(canonical_url("/jobs/1"), None);
+		assert_eq!(canonical_url("not a url"), None);
+	}
+
+	#[test]
+	fn base_url_removes_path_and_query() {
+		assert_eq!(
+			base_url(Url::parse("http://example.com/some/url?page=2").unwrap()).map(|u| u.to_string()),
+			Some("http://example.com/".to_string())
+		);
+	}
+
+	#[test]
+	fn contains_pattern_escapes_wildcards() {
+		assert_eq!(contains_pattern(" Berlin "), "%Berlin%");
+		assert_eq!(contains_pattern("100%_remote"), "%100\\%\\_remote%");
+		assert_eq!(contains_pattern("R&D \\ Ops"), "%R&D \\\\ Ops%");
+	}
+}